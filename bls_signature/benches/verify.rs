@@ -0,0 +1,66 @@
+// Compares the original two-pairing verification with the multi-pairing one, and
+// batch verification with verifying signatures one at a time.
+//
+//     cargo bench --bench verify
+
+use ark_bls12_381::{Bls12_381, G1Projective as G1, G2Projective as G2};
+use ark_ec::pairing::Pairing;
+use ark_ec::{CurveGroup, PrimeGroup};
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use ark_std::test_rng;
+use bls_signature::batch::batch_verify;
+use bls_signature::{hash_to_curve, keygen, sign, verify, MinSig};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+// The previous implementation: two full pairings compared in Fq12.
+fn verify_two_pairings(public_key: &G2, message: &[u8], signature: &G1) -> bool {
+    let h = hash_to_curve::<MinSig>(message);
+    let pairing1 = Bls12_381::pairing(signature.into_affine(), G2::generator().into_affine());
+    let pairing2 = Bls12_381::pairing(h.into_affine(), public_key.into_affine());
+    pairing1 == pairing2
+}
+
+fn signed_batch(n: usize) -> Vec<(G2, Vec<u8>, G1)> {
+    let mut rng = test_rng();
+    (0..n)
+        .map(|i| {
+            let (sk, pk) = keygen::<MinSig, _>(&mut rng);
+            let message = format!("message {}", i).into_bytes();
+            let signature = sign::<MinSig>(&sk, &message);
+            (pk, message, signature)
+        })
+        .collect()
+}
+
+fn single_verification(c: &mut Criterion) {
+    let mut rng = test_rng();
+    let (sk, pk) = keygen::<MinSig, _>(&mut rng);
+    let message = b"Hello, BLS Signatures!";
+    let signature = sign::<MinSig>(&sk, message);
+
+    let mut group = c.benchmark_group("verify");
+    group.bench_function("two pairings", |b| b.iter(|| assert!(verify_two_pairings(&pk, message, &signature))));
+    group.bench_function("multi-pairing", |b| b.iter(|| assert!(verify::<MinSig>(&pk, message, &signature))));
+    group.finish();
+}
+
+fn batch_verification(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batch");
+    group.sample_size(10);
+    for n in [4, 16, 64] {
+        let batch = signed_batch(n);
+        let items: Vec<(G2, &[u8], G1)> = batch.iter().map(|(pk, m, sig)| (*pk, m.as_slice(), *sig)).collect();
+
+        group.bench_with_input(BenchmarkId::new("one by one", n), &items, |b, items| {
+            b.iter(|| assert!(items.iter().all(|(pk, m, sig)| verify::<MinSig>(pk, m, sig))))
+        });
+        group.bench_with_input(BenchmarkId::new("batch_verify", n), &items, |b, items| {
+            let mut rng = StdRng::seed_from_u64(0);
+            b.iter(|| assert!(batch_verify::<MinSig, _>(items, &mut rng)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, single_verification, batch_verification);
+criterion_main!(benches);
@@ -0,0 +1,238 @@
+// Regenerates ../min_pk and ../min_sig with blst, independently of
+// bls_signature. The cases are those of the consensus-spec BLS generator
+// (tests/generators/bls/main.py); min_pk reproduces the published vectors,
+// min_sig runs the same cases with the roles of G1 and G2 swapped.
+//
+//     cargo run --manifest-path fixtures/eth/generate/Cargo.toml
+//
+// Verification follows the spec rather than blst's defaults: every public key
+// goes through KeyValidate, so the point at infinity is rejected, and empty
+// key lists fail.
+
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+const PRIVKEYS: [&str; 3] = [
+    "263dbd792f5b1be47ed85f8938c0f29586af0d3ac7b977f21c278fe1462040e3",
+    "47b8192d77bf871b62e87859d653922725724a5c031afeabc60bcef5ff665138",
+    "328388aff0d4a5b7dc9205abd374e7e98f3cd9f3418edb4eafda5fb16473d216",
+];
+const MESSAGES: [[u8; 32]; 3] = [[0x00; 32], [0x56; 32], [0xab; 32]];
+const SAMPLE_MESSAGE: [u8; 32] = [0x12; 32];
+
+fn hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+// The last four bytes replaced by ff.
+fn tampered(signature: &[u8]) -> Vec<u8> {
+    let mut bytes = signature.to_vec();
+    let n = bytes.len();
+    bytes[n - 4..].fill(0xff);
+    bytes
+}
+
+// The compressed point at infinity.
+fn infinity(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0; len];
+    bytes[0] = 0xc0;
+    bytes
+}
+
+macro_rules! variant {
+    ($name:ident, $dst:expr) => {
+        mod $name {
+            use super::*;
+            use blst::$name::{AggregateSignature, PublicKey, SecretKey, Signature};
+            use blst::BLST_ERROR;
+
+            const DST: &[u8] = $dst;
+
+            fn secret(i: usize) -> SecretKey {
+                SecretKey::from_bytes(&hex::decode(PRIVKEYS[i]).unwrap()).unwrap()
+            }
+
+            fn pubkey(i: usize) -> Vec<u8> {
+                secret(i).sk_to_pk().compress().to_vec()
+            }
+
+            fn sign(i: usize, message: &[u8]) -> Vec<u8> {
+                secret(i).sign(message, DST, &[]).compress().to_vec()
+            }
+
+            fn aggregate(signatures: &[Vec<u8>]) -> Option<Vec<u8>> {
+                let signatures: Vec<Signature> = signatures.iter().map(|s| Signature::from_bytes(s).unwrap()).collect();
+                let refs: Vec<&Signature> = signatures.iter().collect();
+                AggregateSignature::aggregate(&refs, false).ok().map(|a| a.to_signature().compress().to_vec())
+            }
+
+            fn keys(pubkeys: &[Vec<u8>]) -> Option<Vec<PublicKey>> {
+                pubkeys.iter().map(|pk| PublicKey::key_validate(pk).ok()).collect()
+            }
+
+            fn verify(pubkey: &[u8], message: &[u8], signature: &[u8]) -> bool {
+                let (Some(pks), Ok(sig)) = (keys(&[pubkey.to_vec()]), Signature::sig_validate(signature, true)) else {
+                    return false;
+                };
+                sig.verify(true, message, DST, &[], &pks[0], false) == BLST_ERROR::BLST_SUCCESS
+            }
+
+            fn fast_aggregate_verify(pubkeys: &[Vec<u8>], message: &[u8], signature: &[u8]) -> bool {
+                let (Some(pks), Ok(sig)) = (keys(pubkeys), Signature::sig_validate(signature, true)) else {
+                    return false;
+                };
+                let refs: Vec<&PublicKey> = pks.iter().collect();
+                !refs.is_empty() && sig.fast_aggregate_verify(true, message, DST, &refs) == BLST_ERROR::BLST_SUCCESS
+            }
+
+            fn aggregate_verify(pubkeys: &[Vec<u8>], messages: &[Vec<u8>], signature: &[u8]) -> bool {
+                let (Some(pks), Ok(sig)) = (keys(pubkeys), Signature::sig_validate(signature, true)) else {
+                    return false;
+                };
+                let refs: Vec<&PublicKey> = pks.iter().collect();
+                let msgs: Vec<&[u8]> = messages.iter().map(|m| m.as_slice()).collect();
+                !refs.is_empty()
+                    && refs.len() == msgs.len()
+                    && sig.aggregate_verify(true, &msgs, DST, &refs, false) == BLST_ERROR::BLST_SUCCESS
+            }
+
+            pub fn cases() -> Vec<(&'static str, Value)> {
+                let signature_len = sign(0, &MESSAGES[0]).len();
+                let pubkey_len = pubkey(0).len();
+
+                let mut sign_cases = Vec::new();
+                let mut verify_cases = Vec::new();
+                for i in 0..PRIVKEYS.len() {
+                    for (j, message) in MESSAGES.iter().enumerate() {
+                        let case = format!("case_privkey{}_message{}", i, j);
+                        let signature = sign(i, message);
+                        sign_cases.push(json!({
+                            "name": format!("sign_{}", case),
+                            "input": { "privkey": hex(&secret(i).to_bytes()), "message": hex(message) },
+                            "output": hex(&signature),
+                        }));
+                        let wrong = pubkey((i + 1) % PRIVKEYS.len());
+                        let bad = tampered(&signature);
+                        for (kind, pubkey, signature) in
+                            [("valid", pubkey(i), signature.clone()), ("wrong_pubkey", wrong, signature.clone()), ("tampered_signature", pubkey(i), bad)]
+                        {
+                            verify_cases.push(json!({
+                                "name": format!("verify_{}_{}", kind, case),
+                                "input": { "pubkey": hex(&pubkey), "message": hex(message), "signature": hex(&signature) },
+                                "output": verify(&pubkey, message, &signature),
+                            }));
+                        }
+                    }
+                }
+                sign_cases.push(json!({
+                    "name": "sign_case_zero_privkey",
+                    "input": { "privkey": hex(&[0; 32]), "message": hex(&MESSAGES[2]) },
+                    "output": SecretKey::from_bytes(&[0; 32]).ok().map(|sk| hex(&sk.sign(&MESSAGES[2], DST, &[]).compress())),
+                }));
+                let (pubkey_inf, signature_inf) = (infinity(pubkey_len), infinity(signature_len));
+                verify_cases.push(json!({
+                    "name": "verify_infinity_pubkey_and_infinity_signature",
+                    "input": { "pubkey": hex(&pubkey_inf), "message": hex(&MESSAGES[2]), "signature": hex(&signature_inf) },
+                    "output": verify(&pubkey_inf, &MESSAGES[2], &signature_inf),
+                }));
+
+                let mut aggregate_cases = Vec::new();
+                let mut fast_cases = Vec::new();
+                let pubkeys: Vec<Vec<u8>> = (0..PRIVKEYS.len()).map(pubkey).collect();
+                for (j, message) in MESSAGES.iter().enumerate() {
+                    let signatures: Vec<Vec<u8>> = (0..PRIVKEYS.len()).map(|i| sign(i, message)).collect();
+                    let aggregated = aggregate(&signatures).unwrap();
+                    aggregate_cases.push(json!({
+                        "name": format!("aggregate_message{}", j),
+                        "input": signatures.iter().map(|s| hex(s)).collect::<Vec<_>>(),
+                        "output": hex(&aggregated),
+                    }));
+                    let mut extra = pubkeys.clone();
+                    extra.push(pubkeys[0].clone());
+                    let mut with_infinity = pubkeys.clone();
+                    with_infinity.push(pubkey_inf.clone());
+                    for (kind, keys, signature) in [
+                        ("valid", pubkeys.clone(), aggregated.clone()),
+                        ("extra_pubkey", extra, aggregated.clone()),
+                        ("tampered_signature", pubkeys.clone(), tampered(&aggregated)),
+                        ("infinity_pubkey", with_infinity, aggregated.clone()),
+                    ] {
+                        fast_cases.push(json!({
+                            "name": format!("fast_aggregate_verify_{}_message{}", kind, j),
+                            "input": {
+                                "pubkeys": keys.iter().map(|k| hex(k)).collect::<Vec<_>>(),
+                                "message": hex(message),
+                                "signature": hex(&signature),
+                            },
+                            "output": fast_aggregate_verify(&keys, message, &signature),
+                        }));
+                    }
+                }
+                aggregate_cases.push(json!({ "name": "aggregate_na_signatures", "input": [], "output": aggregate(&[]).map(|s| hex(&s)) }));
+                aggregate_cases.push(json!({
+                    "name": "aggregate_infinity_signature",
+                    "input": [hex(&signature_inf)],
+                    "output": aggregate(std::slice::from_ref(&signature_inf)).map(|s| hex(&s)),
+                }));
+                fast_cases.push(json!({
+                    "name": "fast_aggregate_verify_na_pubkeys_and_infinity_signature",
+                    "input": { "pubkeys": [], "message": hex(&MESSAGES[2]), "signature": hex(&signature_inf) },
+                    "output": fast_aggregate_verify(&[], &MESSAGES[2], &signature_inf),
+                }));
+
+                // One key per message.
+                let messages: Vec<Vec<u8>> = MESSAGES.iter().map(|m| m.to_vec()).collect();
+                let signatures: Vec<Vec<u8>> = (0..PRIVKEYS.len()).map(|i| sign(i, &MESSAGES[i])).collect();
+                let aggregated = aggregate(&signatures).unwrap();
+                let mut with_infinity = (pubkeys.clone(), messages.clone());
+                with_infinity.0.push(pubkey_inf.clone());
+                with_infinity.1.push(SAMPLE_MESSAGE.to_vec());
+                let repeated = (pubkeys[..2].to_vec(), vec![messages[0].clone(); 2]);
+                let repeated_signature = aggregate(&[sign(0, &MESSAGES[0]), sign(1, &MESSAGES[0])]).unwrap();
+                let aggregate_verify_cases: Vec<Value> = [
+                    ("valid", (pubkeys.clone(), messages.clone()), aggregated.clone()),
+                    ("tampered_signature", (pubkeys.clone(), messages.clone()), tampered(&aggregated)),
+                    ("infinity_pubkey", with_infinity, aggregated.clone()),
+                    ("repeated_message", repeated, repeated_signature),
+                    ("na_pubkeys_and_infinity_signature", (vec![], vec![]), signature_inf.clone()),
+                ]
+                .into_iter()
+                .map(|(kind, (keys, messages), signature)| {
+                    json!({
+                        "name": format!("aggregate_verify_{}", kind),
+                        "input": {
+                            "pubkeys": keys.iter().map(|k| hex(k)).collect::<Vec<_>>(),
+                            "messages": messages.iter().map(|m| hex(m)).collect::<Vec<_>>(),
+                            "signature": hex(&signature),
+                        },
+                        "output": aggregate_verify(&keys, &messages, &signature),
+                    })
+                })
+                .collect();
+
+                vec![
+                    ("sign", Value::Array(sign_cases)),
+                    ("verify", Value::Array(verify_cases)),
+                    ("aggregate", Value::Array(aggregate_cases)),
+                    ("fast_aggregate_verify", Value::Array(fast_cases)),
+                    ("aggregate_verify", Value::Array(aggregate_verify_cases)),
+                ]
+            }
+        }
+    };
+}
+
+variant!(min_pk, b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_");
+variant!(min_sig, b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_");
+
+fn main() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    for (dir, cases) in [("min_pk", min_pk::cases()), ("min_sig", min_sig::cases())] {
+        for (handler, value) in cases {
+            let path = root.join(dir).join(format!("{}.json", handler));
+            fs::write(&path, serde_json::to_string_pretty(&value).unwrap() + "\n").unwrap();
+            println!("wrote {}", path.display());
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// Signature and public key aggregation, following the IETF BLS signature draft
+// (draft-irtf-cfrg-bls-signature-05, sections 2.8, 2.9, 3.1.1 and 3.3.4).
+//
+// Aggregates are plain sums of group elements. Verifying n signatures on n
+// messages then takes n + 1 Miller loops and a single final exponentiation.
+
+use ark_ec::{CurveGroup, PrimeGroup};
+use ark_std::Zero;
+use std::collections::HashSet;
+
+use crate::{verify, Variant};
+
+// Sum of the signatures, `None` for an empty list.
+pub fn aggregate_signatures<V: Variant>(signatures: &[V::Signature]) -> Option<V::Signature> {
+    if signatures.is_empty() {
+        return None;
+    }
+    Some(signatures.iter().sum())
+}
+
+// Sum of the public keys, `None` for an empty list.
+pub fn aggregate_public_keys<V: Variant>(public_keys: &[V::PublicKey]) -> Option<V::PublicKey> {
+    if public_keys.is_empty() {
+        return None;
+    }
+    Some(public_keys.iter().sum())
+}
+
+// Verifies an aggregate signature by several signers on the same message.
+//
+// Only sound when every public key comes with a proof of possession: otherwise
+// a rogue key chosen as a function of the others makes the sum meaningless.
+// `crate::pop` has the scheme that checks them.
+pub fn fast_aggregate_verify<V: Variant>(public_keys: &[V::PublicKey], message: &[u8], signature: &V::Signature) -> bool {
+    match aggregate_public_keys::<V>(public_keys) {
+        Some(aggregate) => verify::<V>(&aggregate, message, signature),
+        None => false,
+    }
+}
+
+// Verifies an aggregate signature over distinct messages, one per public key:
+//
+//     e(-g, signature) * prod_i e(pk_i, H(m_i)) == 1
+//
+// computed as one multi-pairing. The basic scheme requires the messages to be
+// pairwise distinct, so duplicates are rejected.
+pub fn aggregate_verify<V: Variant>(public_keys: &[V::PublicKey], messages: &[&[u8]], signature: &V::Signature) -> bool {
+    let mut seen = HashSet::new();
+    messages.iter().all(|m| seen.insert(*m))
+        && core_aggregate_verify::<V>(public_keys, messages, signature, V::DST)
+}
+
+// CoreAggregateVerify: the pairing check shared by the basic and PoP schemes,
+// with every public key validated first.
+pub(crate) fn core_aggregate_verify<V: Variant>(
+    public_keys: &[V::PublicKey],
+    messages: &[&[u8]],
+    signature: &V::Signature,
+    dst: &[u8],
+) -> bool {
+    if public_keys.is_empty() || public_keys.len() != messages.len() || public_keys.iter().any(|pk| pk.is_zero()) {
+        return false;
+    }
+
+    let mut pk_points = vec![-V::PublicKey::generator()];
+    let mut sig_points = vec![*signature];
+    for (pk, msg) in public_keys.iter().zip(messages) {
+        pk_points.push(*pk);
+        sig_points.push(V::hash_to_curve(msg, dst));
+    }
+    V::pairing_product_is_one(V::PublicKey::normalize_batch(&pk_points), V::Signature::normalize_batch(&sig_points))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{keygen, sign, MinSig};
+    use ark_bls12_381::{G1Projective as G1, G2Projective as G2};
+    use ark_std::test_rng;
+
+    #[test]
+    fn aggregates_of_empty_lists_are_rejected() {
+        assert!(aggregate_signatures::<MinSig>(&[]).is_none());
+        assert!(aggregate_public_keys::<MinSig>(&[]).is_none());
+        assert!(!fast_aggregate_verify::<MinSig>(&[], b"msg", &G1::generator()));
+        assert!(!aggregate_verify::<MinSig>(&[], &[], &G1::generator()));
+    }
+
+    #[test]
+    fn fast_aggregate_verify_same_message() {
+        let mut rng = test_rng();
+        let keys: Vec<_> = (0..5).map(|_| keygen::<MinSig, _>(&mut rng)).collect();
+        let message = b"block 42";
+        let signatures: Vec<G1> = keys.iter().map(|(sk, _)| sign::<MinSig>(sk, message)).collect();
+        let public_keys: Vec<G2> = keys.iter().map(|(_, pk)| *pk).collect();
+        let aggregate = aggregate_signatures::<MinSig>(&signatures).unwrap();
+
+        assert!(fast_aggregate_verify::<MinSig>(&public_keys, message, &aggregate));
+        assert!(!fast_aggregate_verify::<MinSig>(&public_keys, b"block 43", &aggregate));
+        // Dropping a signer, or a signature, breaks the aggregate.
+        assert!(!fast_aggregate_verify::<MinSig>(&public_keys[1..], message, &aggregate));
+        let partial = aggregate_signatures::<MinSig>(&signatures[1..]).unwrap();
+        assert!(!fast_aggregate_verify::<MinSig>(&public_keys, message, &partial));
+    }
+
+    #[test]
+    fn aggregate_verify_distinct_messages() {
+        let mut rng = test_rng();
+        let keys: Vec<_> = (0..4).map(|_| keygen::<MinSig, _>(&mut rng)).collect();
+        let messages: Vec<Vec<u8>> = (0..4).map(|i| format!("message {}", i).into_bytes()).collect();
+        let messages: Vec<&[u8]> = messages.iter().map(|m| m.as_slice()).collect();
+        let signatures: Vec<G1> = keys.iter().zip(&messages).map(|((sk, _), m)| sign::<MinSig>(sk, m)).collect();
+        let public_keys: Vec<G2> = keys.iter().map(|(_, pk)| *pk).collect();
+        let aggregate = aggregate_signatures::<MinSig>(&signatures).unwrap();
+
+        assert!(aggregate_verify::<MinSig>(&public_keys, &messages, &aggregate));
+
+        // Messages assigned to the wrong keys.
+        let mut swapped = messages.clone();
+        swapped.swap(0, 1);
+        assert!(!aggregate_verify::<MinSig>(&public_keys, &swapped, &aggregate));
+        // Mismatched lengths.
+        assert!(!aggregate_verify::<MinSig>(&public_keys[..3], &messages, &aggregate));
+    }
+
+    #[test]
+    fn aggregate_verify_rejects_repeated_messages() {
+        let mut rng = test_rng();
+        let (sk1, pk1) = keygen::<MinSig, _>(&mut rng);
+        let (sk2, pk2) = keygen::<MinSig, _>(&mut rng);
+        let message: &[u8] = b"same";
+        let aggregate = aggregate_signatures::<MinSig>(&[sign::<MinSig>(&sk1, message), sign::<MinSig>(&sk2, message)]).unwrap();
+
+        // Valid as a same-message aggregate, but not accepted by the distinct-message check.
+        assert!(fast_aggregate_verify::<MinSig>(&[pk1, pk2], message, &aggregate));
+        assert!(!aggregate_verify::<MinSig>(&[pk1, pk2], &[message, message], &aggregate));
+    }
+}
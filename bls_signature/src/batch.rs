@@ -0,0 +1,120 @@
+// Batch verification of many independent (public key, message, signature) triples.
+//
+// Each triple is weighted by a fresh random scalar r_i and all of them are checked
+// at once:
+//
+//     e(-g, sum_i r_i * sig_i) * prod_i e(pk_i, r_i * H(m_i)) == 1
+//
+// n + 1 Miller loops and one final exponentiation, instead of 2n loops and n
+// exponentiations. Without the random weights, two invalid signatures could
+// cancel out; with them, an invalid batch passes with probability about 2^-128.
+// A failed batch does not say which triple is bad; fall back to `verify` for that.
+//
+// The weights must be unpredictable to whoever chose the signatures, hence the
+// `CryptoRng` bound: with a known seed a forger can make the errors cancel.
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ec::{CurveGroup, PrimeGroup};
+use ark_std::rand::{CryptoRng, Rng};
+use ark_std::Zero;
+
+use crate::{hash_to_curve, Variant};
+
+pub fn batch_verify<V: Variant, R: Rng + CryptoRng>(items: &[(V::PublicKey, &[u8], V::Signature)], rng: &mut R) -> bool {
+    if items.is_empty() {
+        return true;
+    }
+    if items.iter().any(|(pk, _, _)| pk.is_zero()) {
+        return false;
+    }
+
+    let mut sig_sum = V::Signature::zero();
+    let mut pk_points = Vec::with_capacity(items.len() + 1);
+    let mut sig_points = Vec::with_capacity(items.len() + 1);
+    for (public_key, message, signature) in items {
+        // 128-bit weights give the same soundness as full scalars at half the cost.
+        let r = ScalarField::from(rng.gen::<u128>());
+        sig_sum += *signature * r;
+        pk_points.push(*public_key);
+        sig_points.push(hash_to_curve::<V>(message) * r);
+    }
+    pk_points.push(-V::PublicKey::generator());
+    sig_points.push(sig_sum);
+
+    V::pairing_product_is_one(V::PublicKey::normalize_batch(&pk_points), V::Signature::normalize_batch(&sig_points))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{keygen, sign, MinPk, MinSig};
+    use ark_bls12_381::{G1Projective as G1, G2Projective as G2};
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+    use ark_std::test_rng;
+
+    // Batch weights come from a CryptoRng; seeded here to keep the tests reproducible.
+    fn weights() -> StdRng {
+        StdRng::seed_from_u64(33)
+    }
+
+    fn signed_batch(n: usize) -> Vec<(G2, Vec<u8>, G1)> {
+        let mut rng = test_rng();
+        (0..n)
+            .map(|i| {
+                let (sk, pk) = keygen::<MinSig, _>(&mut rng);
+                let message = format!("message {}", i).into_bytes();
+                let signature = sign::<MinSig>(&sk, &message);
+                (pk, message, signature)
+            })
+            .collect()
+    }
+
+    fn borrowed(batch: &[(G2, Vec<u8>, G1)]) -> Vec<(G2, &[u8], G1)> {
+        batch.iter().map(|(pk, m, sig)| (*pk, m.as_slice(), *sig)).collect()
+    }
+
+    #[test]
+    fn valid_batch_verifies() {
+        let batch = signed_batch(8);
+        assert!(batch_verify::<MinSig, _>(&borrowed(&batch), &mut weights()));
+        assert!(batch_verify::<MinSig, _>(&[], &mut weights()));
+    }
+
+    #[test]
+    fn one_bad_signature_fails_the_batch() {
+        let mut batch = signed_batch(8);
+        batch[5].1 = b"tampered".to_vec();
+        assert!(!batch_verify::<MinSig, _>(&borrowed(&batch), &mut weights()));
+    }
+
+    #[test]
+    fn cancelling_errors_are_caught() {
+        // Shifting one signature by +D and another by -D keeps the plain sum valid.
+        let mut batch = signed_batch(4);
+        let delta = sign::<MinSig>(&ScalarField::from(7u64), b"offset");
+        batch[0].2 += delta;
+        batch[1].2 -= delta;
+        let sum: G1 = batch.iter().map(|(_, _, sig)| *sig).sum();
+        let public_keys: Vec<G2> = batch.iter().map(|(pk, _, _)| *pk).collect();
+        let messages: Vec<&[u8]> = batch.iter().map(|(_, m, _)| m.as_slice()).collect();
+        assert!(crate::aggregate::aggregate_verify::<MinSig>(&public_keys, &messages, &sum));
+
+        assert!(!batch_verify::<MinSig, _>(&borrowed(&batch), &mut weights()));
+    }
+
+    #[test]
+    fn min_pk_batch_verifies() {
+        let mut rng = test_rng();
+        let signed: Vec<(G1, Vec<u8>, G2)> = (0..4)
+            .map(|i| {
+                let (sk, pk) = keygen::<MinPk, _>(&mut rng);
+                let message = format!("message {}", i).into_bytes();
+                (pk, message.clone(), sign::<MinPk>(&sk, &message))
+            })
+            .collect();
+        let mut items: Vec<(G1, &[u8], G2)> = signed.iter().map(|(pk, m, sig)| (*pk, m.as_slice(), *sig)).collect();
+        assert!(batch_verify::<MinPk, _>(&items, &mut weights()));
+        items[2].1 = b"tampered";
+        assert!(!batch_verify::<MinPk, _>(&items, &mut weights()));
+    }
+}
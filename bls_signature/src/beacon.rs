@@ -0,0 +1,221 @@
+// A drand-style chained randomness beacon.
+//
+// A group of n nodes holds shares of one key (from `dkg`). Round r is the
+// threshold signature
+//
+//     sig_r = H(r || sig_{r-1})^sk        r as 8 big-endian bytes, sig_{r-1} compressed
+//
+// with sig_0 replaced by a public genesis seed, and its randomness is
+// SHA-256(sig_r), i.e. the `vrf` output. Any t nodes produce the round, fewer
+// cannot predict it, and since signatures are unique the value is fixed once
+// the previous round is: t colluding nodes can only delay it, not choose it.
+// Chaining makes every round commit to the whole history, so a verifier that
+// trusts the group key and the genesis seed can check the chain from round 1.
+
+use crate::serialization::signature_to_bytes;
+use crate::threshold::{combine, partial_sign, verify_partial, PartialSignature, SecretKeyShare, ThresholdError};
+use crate::{vrf, Variant};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Beacon<V: Variant> {
+    pub round: u64,
+    pub signature: V::Signature,
+    // The signature of the previous round, or the genesis seed for round 1.
+    pub previous_signature: Vec<u8>,
+}
+
+impl<V: Variant> Beacon<V> {
+    pub fn randomness(&self) -> vrf::Output {
+        vrf::output::<V>(&self.signature)
+    }
+
+    pub fn signature_bytes(&self) -> Vec<u8> {
+        signature_to_bytes::<V>(&self.signature)
+    }
+}
+
+// The message signed in `round`.
+pub fn round_message(round: u64, previous_signature: &[u8]) -> Vec<u8> {
+    [&round.to_be_bytes()[..], previous_signature].concat()
+}
+
+// One beacon node: a key share and the group parameters.
+pub struct Node<V: Variant> {
+    pub share: SecretKeyShare,
+    pub public_share: V::PublicKey,
+}
+
+impl<V: Variant> Node<V> {
+    pub fn partial(&self, round: u64, previous_signature: &[u8]) -> PartialSignature<V> {
+        partial_sign::<V>(&self.share, &round_message(round, previous_signature))
+    }
+}
+
+// Assembles a round from whatever partial signatures arrived, ignoring invalid
+// ones and repeats of an index already seen (a node's valid partial is unique,
+// so a repeat carries nothing new). `public_shares` gives each node's public
+// key share by index.
+pub fn aggregate_round<V: Variant>(
+    round: u64,
+    previous_signature: &[u8],
+    partials: &[PartialSignature<V>],
+    public_shares: impl Fn(u64) -> Option<V::PublicKey>,
+    threshold: usize,
+) -> Result<Beacon<V>, ThresholdError> {
+    let message = round_message(round, previous_signature);
+    let mut seen = HashSet::new();
+    let valid: Vec<PartialSignature<V>> = partials
+        .iter()
+        .filter(|p| public_shares(p.index).is_some_and(|pk| verify_partial(&pk, &message, p)))
+        .filter(|p| seen.insert(p.index))
+        .map(|p| PartialSignature { index: p.index, signature: p.signature })
+        .collect();
+    let signature = combine(&valid, threshold)?;
+    Ok(Beacon { round, signature, previous_signature: previous_signature.to_vec() })
+}
+
+// Checked as a VRF proof, so the group key and the signature must also be in
+// the prime-order subgroup and not the identity.
+pub fn verify_beacon<V: Variant>(group_public_key: &V::PublicKey, beacon: &Beacon<V>) -> bool {
+    vrf::verify::<V>(group_public_key, &round_message(beacon.round, &beacon.previous_signature), &beacon.signature).is_some()
+}
+
+// Checks consecutive rounds starting at 1, each linked to the one before.
+pub fn verify_chain<V: Variant>(group_public_key: &V::PublicKey, genesis_seed: &[u8], beacons: &[Beacon<V>]) -> bool {
+    let mut previous = genesis_seed.to_vec();
+    for (i, beacon) in beacons.iter().enumerate() {
+        if beacon.round != i as u64 + 1 || beacon.previous_signature != previous || !verify_beacon(group_public_key, beacon) {
+            return false;
+        }
+        previous = beacon.signature_bytes();
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dkg::{self, Participant};
+    use crate::{sign, MinSig};
+    use ark_bls12_381::Fr as ScalarField;
+    use ark_std::test_rng;
+
+    const N: usize = 5;
+    const T: usize = 3;
+    const GENESIS: &[u8] = b"beacon genesis seed";
+
+    // Honest DKG among N nodes.
+    fn setup() -> (<MinSig as Variant>::PublicKey, Vec<Node<MinSig>>) {
+        let mut rng = test_rng();
+        let mut participants: Vec<Participant<MinSig>> =
+            (1..=N as u64).map(|i| Participant::new(i, T, N, &mut rng).unwrap()).collect();
+        let dealings: Vec<_> = participants.iter().map(|p| p.dealing().clone()).collect();
+        for dealing in &dealings {
+            let dealer = &participants[dealing.dealer as usize - 1];
+            let shares: Vec<_> = (1..=N as u64).map(|j| dealer.share_for(j)).collect();
+            for (participant, share) in participants.iter_mut().zip(&shares) {
+                participant.receive(dealing, share).unwrap();
+            }
+        }
+        let qualified = dkg::qualified(&dealings, T, &[], &[]);
+        let nodes = participants
+            .iter()
+            .map(|p| {
+                let (_, share) = p.finalize(&qualified).unwrap();
+                Node { share, public_share: dkg::public_key_share(&qualified, share.index) }
+            })
+            .collect();
+        (dkg::group_public_key(&qualified), nodes)
+    }
+
+    #[test]
+    fn nodes_produce_a_verifiable_chain() {
+        let (group_key, nodes) = setup();
+        let public_shares = |i: u64| nodes.get((i as usize).wrapping_sub(1)).map(|n| n.public_share);
+
+        let mut chain: Vec<Beacon<MinSig>> = Vec::new();
+        let mut previous = GENESIS.to_vec();
+        for round in 1..=6u64 {
+            // A different quorum each round: one node is offline, and the first
+            // one to answer sends a bad partial signature.
+            let mut partials: Vec<_> = nodes
+                .iter()
+                .filter(|n| n.share.index != round % N as u64 + 1)
+                .map(|n| n.partial(round, &previous))
+                .collect();
+            partials[0].signature = sign::<MinSig>(&nodes[0].share.secret, &round_message(round + 1, &previous));
+
+            let beacon = aggregate_round(round, &previous, &partials, public_shares, T).unwrap();
+            assert!(verify_beacon(&group_key, &beacon));
+            previous = beacon.signature_bytes();
+            chain.push(beacon);
+        }
+        assert!(verify_chain(&group_key, GENESIS, &chain));
+
+        // Unique signatures mean every quorum agrees on the round.
+        let all: Vec<_> = nodes.iter().map(|n| n.partial(1, GENESIS)).collect();
+        let other_quorum = aggregate_round(1, GENESIS, &all[2..], public_shares, T).unwrap();
+        assert_eq!(other_quorum, chain[0]);
+
+        let randomness: HashSet<_> = chain.iter().map(|b| b.randomness()).collect();
+        assert_eq!(randomness.len(), chain.len());
+    }
+
+    #[test]
+    fn broken_chains_are_rejected() {
+        let (group_key, nodes) = setup();
+        let public_shares = |i: u64| nodes.get((i as usize).wrapping_sub(1)).map(|n| n.public_share);
+        let mut chain = Vec::new();
+        let mut previous = GENESIS.to_vec();
+        for round in 1..=3u64 {
+            let partials: Vec<_> = nodes.iter().map(|n| n.partial(round, &previous)).collect();
+            let beacon = aggregate_round::<MinSig>(round, &previous, &partials, public_shares, T).unwrap();
+            previous = beacon.signature_bytes();
+            chain.push(beacon);
+        }
+
+        assert!(!verify_chain(&group_key, b"another genesis", &chain));
+        // Skipping a round.
+        assert!(!verify_chain(&group_key, GENESIS, &[chain[0].clone(), chain[2].clone()]));
+        // A valid-looking round signed by a single node's share.
+        let mut forged = chain.clone();
+        forged[1].signature = sign::<MinSig>(&nodes[0].share.secret, &round_message(2, &chain[0].signature_bytes()));
+        assert!(!verify_chain(&group_key, GENESIS, &forged));
+        // A different group key.
+        assert!(!verify_chain(&(group_key * ScalarField::from(2u64)), GENESIS, &chain));
+    }
+
+    #[test]
+    fn repeated_partials_count_once() {
+        let (group_key, nodes) = setup();
+        let public_shares = |i: u64| nodes.get((i as usize).wrapping_sub(1)).map(|n| n.public_share);
+        let partials: Vec<_> = nodes.iter().take(T).map(|n| n.partial(1, GENESIS)).collect();
+        let expected = aggregate_round::<MinSig>(1, GENESIS, &partials, public_shares, T).unwrap();
+
+        // A node whose partial arrives twice, before and after the others.
+        let mut repeated = partials.clone();
+        repeated.insert(0, partials[1]);
+        let beacon = aggregate_round::<MinSig>(1, GENESIS, &repeated, public_shares, T).unwrap();
+        assert_eq!(beacon, expected);
+        assert!(verify_beacon(&group_key, &beacon));
+
+        // Repeats do not make up for missing nodes.
+        let short = [partials[0], partials[1], partials[1]];
+        assert_eq!(
+            aggregate_round::<MinSig>(1, GENESIS, &short, public_shares, T),
+            Err(ThresholdError::NotEnoughShares)
+        );
+    }
+
+    #[test]
+    fn too_few_nodes_cannot_produce_a_round() {
+        let (_, nodes) = setup();
+        let public_shares = |i: u64| nodes.get((i as usize).wrapping_sub(1)).map(|n| n.public_share);
+        let partials: Vec<_> = nodes.iter().take(T - 1).map(|n| n.partial(1, GENESIS)).collect();
+        assert_eq!(
+            aggregate_round::<MinSig>(1, GENESIS, &partials, public_shares, T),
+            Err(ThresholdError::NotEnoughShares)
+        );
+    }
+}
@@ -0,0 +1,166 @@
+// Blind BLS signatures (Boldyreva, 2003).
+//
+//     user:   r <- random non-zero scalar,  B = H(m)^r          sends B
+//     signer: S' = B^sk                                         sends S'
+//     user:   S = S'^(1/r) = H(m)^sk                            a plain signature on m
+//
+// The result verifies with `crate::verify` and is indistinguishable from a
+// signature made by `crate::sign`. Because the group has prime order, B is a
+// uniformly random non-identity point whatever m is, so the signer learns
+// nothing about the message and cannot later link S to the session that
+// produced it. The user can check S' before unblinding with `verify_blinded`.
+//
+// The signer refuses the identity and points outside the prime-order subgroup:
+// B^sk for B of small order d would reveal sk mod d. Beyond that it signs
+// whatever point it is given, and must decide who may obtain a signature (e.g.
+// one token per voter) outside this module.
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ec::{CurveGroup, PrimeGroup};
+use ark_ff::Field;
+use ark_serialize::Valid;
+use ark_std::{rand::Rng, UniformRand, Zero};
+
+use crate::{hash_to_curve, Variant};
+
+// Blinds an already hashed point, returning the blinded point and the factor r.
+pub fn blind_point<V: Variant, R: Rng>(point: &V::Signature, rng: &mut R) -> (V::Signature, ScalarField) {
+    let mut r = ScalarField::rand(rng);
+    while r.is_zero() {
+        r = ScalarField::rand(rng);
+    }
+    (*point * r, r)
+}
+
+// B = H(m)^r, to be sent to the signer in place of m.
+pub fn blind<V: Variant, R: Rng>(message: &[u8], rng: &mut R) -> (V::Signature, ScalarField) {
+    blind_point::<V, R>(&hash_to_curve::<V>(message), rng)
+}
+
+// A non-identity point of the prime-order subgroup.
+fn is_valid_point<G: CurveGroup>(point: &G) -> bool {
+    !point.is_zero() && point.into_affine().check().is_ok()
+}
+
+// The signer's side: the same exponentiation as `sign`, applied to B. None if
+// B is not a valid point.
+pub fn sign_blinded<V: Variant>(private_key: &ScalarField, blinded: &V::Signature) -> Option<V::Signature> {
+    is_valid_point(blinded).then(|| *blinded * private_key)
+}
+
+// Checks e(g, S') == e(pk, B), so that a misbehaving signer is caught before unblinding.
+pub fn verify_blinded<V: Variant>(public_key: &V::PublicKey, blinded: &V::Signature, blind_signature: &V::Signature) -> bool {
+    is_valid_point(public_key)
+        && is_valid_point(blinded)
+        && is_valid_point(blind_signature)
+        && V::pairing_product_is_one(
+            V::PublicKey::normalize_batch(&[-V::PublicKey::generator(), *public_key]),
+            V::Signature::normalize_batch(&[*blind_signature, *blinded]),
+        )
+}
+
+// S = S'^(1/r).
+pub fn unblind<V: Variant>(blind_signature: &V::Signature, blinding_factor: &ScalarField) -> V::Signature {
+    *blind_signature * blinding_factor.inverse().expect("blinding factors are non-zero")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{keygen, sign, verify, MinPk, MinSig};
+    use ark_bls12_381::{Fq, G1Affine, G1Projective as G1};
+    use ark_ec::AffineRepr;
+    use ark_ff::PrimeField;
+    use ark_std::test_rng;
+
+    #[test]
+    fn unblinded_signature_is_a_standard_signature() {
+        let mut rng = test_rng();
+        let (sk, pk) = keygen::<MinSig, _>(&mut rng);
+        let message = b"ballot token 17";
+
+        let (blinded, r) = blind::<MinSig, _>(message, &mut rng);
+        let blind_signature = sign_blinded::<MinSig>(&sk, &blinded).unwrap();
+        assert!(verify_blinded::<MinSig>(&pk, &blinded, &blind_signature));
+        let signature = unblind::<MinSig>(&blind_signature, &r);
+
+        assert!(verify::<MinSig>(&pk, message, &signature));
+        assert_eq!(signature, sign::<MinSig>(&sk, message));
+    }
+
+    #[test]
+    fn min_pk_blind_signature() {
+        let mut rng = test_rng();
+        let (sk, pk) = keygen::<MinPk, _>(&mut rng);
+        let (blinded, r) = blind::<MinPk, _>(b"token", &mut rng);
+        let signature = unblind::<MinPk>(&sign_blinded::<MinPk>(&sk, &blinded).unwrap(), &r);
+        assert!(verify::<MinPk>(&pk, b"token", &signature));
+    }
+
+    #[test]
+    fn cheating_signer_is_caught_before_unblinding() {
+        let mut rng = test_rng();
+        let (sk, pk) = keygen::<MinSig, _>(&mut rng);
+        let (blinded, _) = blind::<MinSig, _>(b"token", &mut rng);
+        let wrong_key = sk + ScalarField::from(1u64);
+        assert!(!verify_blinded::<MinSig>(&pk, &blinded, &sign_blinded::<MinSig>(&wrong_key, &blinded).unwrap()));
+    }
+
+    #[test]
+    fn points_of_small_order_are_not_signed() {
+        let mut rng = test_rng();
+        let (sk, pk) = keygen::<MinSig, _>(&mut rng);
+        let (blinded, _) = blind::<MinSig, _>(b"token", &mut rng);
+
+        // A point of order dividing the cofactor: r P for any P off the subgroup.
+        let p = (0u64..)
+            .filter_map(|i| G1Affine::get_point_from_x_unchecked(Fq::from(i), false))
+            .find(|p| !p.is_in_correct_subgroup_assuming_on_curve())
+            .unwrap();
+        let torsion = p.mul_bigint(ScalarField::MODULUS);
+        assert!(!torsion.is_zero());
+        assert_eq!(sign_blinded::<MinSig>(&sk, &torsion), None);
+        assert_eq!(sign_blinded::<MinSig>(&sk, &(blinded + torsion)), None);
+        assert_eq!(sign_blinded::<MinSig>(&sk, &G1::zero()), None);
+
+        // Nor does a signer's answer shifted off the subgroup pass as a blind signature.
+        let blind_signature = sign_blinded::<MinSig>(&sk, &blinded).unwrap();
+        assert!(!verify_blinded::<MinSig>(&pk, &(blinded + torsion), &(blind_signature + torsion * sk)));
+        assert!(!verify_blinded::<MinSig>(&pk, &blinded, &(blind_signature + torsion)));
+    }
+
+    // Everything the signer sees is (B, S' = B^sk). For two messages whose hashes
+    // are P0 = g^a and P1 = g^b, the view produced by m0 with factor r0 is exactly
+    // the view produced by m1 with r1 = r0 * a / b, and both sessions unblind to
+    // valid signatures. Since r0 and r1 are equally likely, the view carries no
+    // information about which message was signed. Real hash outputs have unknown
+    // discrete logarithms; the argument only needs them to be non-identity points
+    // of a prime-order group, which these stand-ins are.
+    #[test]
+    fn signer_view_is_independent_of_the_message() {
+        let mut rng = test_rng();
+        let (sk, _) = keygen::<MinSig, _>(&mut rng);
+        let (a, b) = (ScalarField::rand(&mut rng), ScalarField::rand(&mut rng));
+        let (p0, p1) = (G1::generator() * a, G1::generator() * b);
+
+        let (blinded0, r0) = blind_point::<MinSig, _>(&p0, &mut rng);
+        let r1 = r0 * a * b.inverse().unwrap();
+        let blinded1 = p1 * r1;
+        assert_eq!(blinded0, blinded1);
+
+        let view = (blinded0, sign_blinded::<MinSig>(&sk, &blinded0).unwrap());
+        assert_eq!(view, (blinded1, sign_blinded::<MinSig>(&sk, &blinded1).unwrap()));
+        assert_eq!(unblind::<MinSig>(&view.1, &r0), p0 * sk);
+        assert_eq!(unblind::<MinSig>(&view.1, &r1), p1 * sk);
+    }
+
+    #[test]
+    fn blinding_the_same_message_twice_is_unlinkable() {
+        let mut rng = test_rng();
+        let message = b"same token";
+        let (b0, _) = blind::<MinSig, _>(message, &mut rng);
+        let (b1, _) = blind::<MinSig, _>(message, &mut rng);
+        assert_ne!(b0, b1);
+        assert_ne!(b0, hash_to_curve::<MinSig>(message));
+    }
+}
@@ -0,0 +1,304 @@
+// Distributed key generation without a trusted dealer (Pedersen's joint-Feldman DKG).
+//
+// Every participant i acts as a dealer for a random secret of its own:
+//
+//     1. pick f_i of degree t - 1, broadcast Feldman commitments C_ik = g^a_ik,
+//        and send f_i(j) privately to each participant j;
+//     2. j checks g^f_i(j) == prod_k C_ik^(j^k) and broadcasts a complaint
+//        against i if it does not hold;
+//     3. i answers each complaint by revealing the disputed share; a dealer with
+//        a malformed dealing or an unanswered or wrong answer is disqualified.
+//
+// Over the qualified set Q the joint polynomial is F = sum_{i in Q} f_i. Its
+// shares F(j) = sum_i f_i(j) are `threshold::SecretKeyShare`s, the group public
+// key is g^F(0) = prod_i C_i0, and nobody ever knows F(0). Threshold signing
+// then works exactly as with a dealt key.
+//
+// As noted by Gennaro et al., a rushing adversary can bias the distribution of
+// the group key slightly; that is harmless for BLS signing.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ec::PrimeGroup;
+use ark_std::{rand::Rng, UniformRand, Zero};
+
+use crate::threshold::{evaluate, SecretKeyShare};
+use crate::Variant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DkgError {
+    // The threshold must satisfy 1 <= t <= n, and indices run from 1 to n.
+    InvalidParameters,
+    // No valid share was received from this qualified dealer.
+    MissingShare(u64),
+    // Fewer than t dealers remain qualified.
+    TooFewQualified,
+}
+
+impl fmt::Display for DkgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DkgError::InvalidParameters => write!(f, "invalid threshold or participant index"),
+            DkgError::MissingShare(dealer) => write!(f, "no valid share from dealer {}", dealer),
+            DkgError::TooFewQualified => write!(f, "fewer qualified dealers than the threshold"),
+        }
+    }
+}
+
+impl std::error::Error for DkgError {}
+
+// What a dealer broadcasts: commitments to the coefficients of its polynomial.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dealing<V: Variant> {
+    pub dealer: u64,
+    pub commitments: Vec<V::PublicKey>,
+}
+
+// Broadcast by `accuser` when the share from `dealer` fails verification or never arrived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Complaint {
+    pub accuser: u64,
+    pub dealer: u64,
+}
+
+impl<V: Variant> Dealing<V> {
+    // g^f(j), computed from the commitments alone.
+    pub fn public_share(&self, index: u64) -> V::PublicKey {
+        let x = ScalarField::from(index);
+        self.commitments.iter().rev().fold(V::PublicKey::zero(), |acc, c| acc * x + c)
+    }
+
+    pub fn verify_share(&self, share: &SecretKeyShare) -> bool {
+        V::PublicKey::generator() * share.secret == self.public_share(share.index)
+    }
+}
+
+pub struct Participant<V: Variant> {
+    pub index: u64,
+    threshold: usize,
+    polynomial: Vec<ScalarField>,
+    dealing: Dealing<V>,
+    // Verified shares, by dealer.
+    received: BTreeMap<u64, ScalarField>,
+}
+
+impl<V: Variant> Participant<V> {
+    pub fn new<R: Rng>(index: u64, threshold: usize, n: usize, rng: &mut R) -> Result<Self, DkgError> {
+        if threshold == 0 || threshold > n || index == 0 || index > n as u64 {
+            return Err(DkgError::InvalidParameters);
+        }
+        let polynomial: Vec<ScalarField> = (0..threshold).map(|_| ScalarField::rand(rng)).collect();
+        let commitments = polynomial.iter().map(|a| V::PublicKey::generator() * a).collect();
+        Ok(Participant {
+            index,
+            threshold,
+            polynomial,
+            dealing: Dealing { dealer: index, commitments },
+            received: BTreeMap::new(),
+        })
+    }
+
+    pub fn dealing(&self) -> &Dealing<V> {
+        &self.dealing
+    }
+
+    // The share to send privately to `recipient`.
+    pub fn share_for(&self, recipient: u64) -> SecretKeyShare {
+        SecretKeyShare { index: recipient, secret: evaluate(&self.polynomial, ScalarField::from(recipient)) }
+    }
+
+    // Accepts the share dealt to us if it matches the dealing, otherwise returns
+    // the complaint to broadcast. Also used for shares revealed in answers.
+    pub fn receive(&mut self, dealing: &Dealing<V>, share: &SecretKeyShare) -> Result<(), Complaint> {
+        let complaint = Complaint { accuser: self.index, dealer: dealing.dealer };
+        if dealing.commitments.len() != self.threshold || share.index != self.index || !dealing.verify_share(share) {
+            return Err(complaint);
+        }
+        self.received.insert(dealing.dealer, share.secret);
+        Ok(())
+    }
+
+    // An honest dealer answers a complaint by revealing the accuser's share.
+    pub fn answer(&self, complaint: &Complaint) -> SecretKeyShare {
+        self.share_for(complaint.accuser)
+    }
+
+    // Our share of the joint key, and the group public key.
+    pub fn finalize(&self, qualified: &[&Dealing<V>]) -> Result<(V::PublicKey, SecretKeyShare), DkgError> {
+        if qualified.len() < self.threshold {
+            return Err(DkgError::TooFewQualified);
+        }
+        let mut secret = ScalarField::zero();
+        for dealing in qualified {
+            secret += self.received.get(&dealing.dealer).ok_or(DkgError::MissingShare(dealing.dealer))?;
+        }
+        Ok((group_public_key(qualified), SecretKeyShare { index: self.index, secret }))
+    }
+}
+
+// The qualified set: dealers with a well-formed dealing and every complaint
+// against them answered by a share that verifies. Answers are (complaint,
+// revealed share) pairs as broadcast by the dealers.
+pub fn qualified<'a, V: Variant>(
+    dealings: &'a [Dealing<V>],
+    threshold: usize,
+    complaints: &[Complaint],
+    answers: &[(Complaint, SecretKeyShare)],
+) -> Vec<&'a Dealing<V>> {
+    let mut disqualified = BTreeSet::new();
+    for dealing in dealings {
+        if dealing.commitments.len() != threshold {
+            disqualified.insert(dealing.dealer);
+        }
+    }
+    for complaint in complaints {
+        let answered = answers.iter().any(|(c, share)| {
+            c == complaint
+                && share.index == complaint.accuser
+                && dealings.iter().any(|d| d.dealer == complaint.dealer && d.verify_share(share))
+        });
+        if !answered {
+            disqualified.insert(complaint.dealer);
+        }
+    }
+    dealings.iter().filter(|d| !disqualified.contains(&d.dealer)).collect()
+}
+
+// g^F(0): the product of the qualified dealers' constant-term commitments.
+pub fn group_public_key<V: Variant>(qualified: &[&Dealing<V>]) -> V::PublicKey {
+    qualified.iter().map(|d| d.commitments[0]).sum()
+}
+
+// g^F(j), the key participant j's partial signatures verify under.
+pub fn public_key_share<V: Variant>(qualified: &[&Dealing<V>], index: u64) -> V::PublicKey {
+    qualified.iter().map(|d| d.public_share(index)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::threshold::{combine, partial_sign, verify_partial};
+    use crate::{verify, MinSig};
+    use ark_std::test_rng;
+
+    const N: usize = 5;
+    const T: usize = 3;
+
+    struct Run {
+        participants: Vec<Participant<MinSig>>,
+        dealings: Vec<Dealing<MinSig>>,
+        complaints: Vec<Complaint>,
+        answers: Vec<(Complaint, SecretKeyShare)>,
+    }
+
+    // Runs the protocol in-process. `tamper` may corrupt the private share a
+    // dealer sends, and `honest_answer` decides whether a dealer answers complaints
+    // truthfully.
+    fn run(
+        tamper: impl Fn(u64, &mut SecretKeyShare),
+        honest_answer: impl Fn(u64) -> bool,
+    ) -> Run {
+        let mut rng = test_rng();
+        let mut participants: Vec<Participant<MinSig>> =
+            (1..=N as u64).map(|i| Participant::new(i, T, N, &mut rng).unwrap()).collect();
+        let dealings: Vec<Dealing<MinSig>> = participants.iter().map(|p| p.dealing().clone()).collect();
+
+        let mut complaints = Vec::new();
+        for dealer in 0..N {
+            for recipient in 0..N {
+                let mut share = participants[dealer].share_for(participants[recipient].index);
+                tamper(dealings[dealer].dealer, &mut share);
+                if let Err(complaint) = participants[recipient].receive(&dealings[dealer], &share) {
+                    complaints.push(complaint);
+                }
+            }
+        }
+
+        let mut answers = Vec::new();
+        for complaint in &complaints {
+            let dealer = &participants[complaint.dealer as usize - 1];
+            let mut revealed = dealer.answer(complaint);
+            if !honest_answer(complaint.dealer) {
+                revealed.secret += ScalarField::from(1u64);
+            }
+            // The accuser takes the revealed share if it checks out.
+            let _ = participants[complaint.accuser as usize - 1].receive(&dealings[complaint.dealer as usize - 1], &revealed);
+            answers.push((*complaint, revealed));
+        }
+        Run { participants, dealings, complaints, answers }
+    }
+
+    fn sign_with_quorum(participants: &[Participant<MinSig>], qualified: &[&Dealing<MinSig>], quorum: &[usize]) {
+        let message = b"signed without a trusted dealer";
+        let finals: Vec<_> = participants.iter().map(|p| p.finalize(qualified).unwrap()).collect();
+        let group_key = finals[0].0;
+        assert!(finals.iter().all(|(pk, _)| *pk == group_key));
+
+        let partials: Vec<_> = quorum.iter().map(|&i| partial_sign::<MinSig>(&finals[i].1, message)).collect();
+        for (&i, partial) in quorum.iter().zip(&partials) {
+            assert!(verify_partial(&public_key_share(qualified, finals[i].1.index), message, partial));
+        }
+        assert!(verify::<MinSig>(&group_key, message, &combine(&partials, T).unwrap()));
+    }
+
+    #[test]
+    fn honest_run_produces_a_working_threshold_key() {
+        let Run { participants, dealings, complaints, answers } = run(|_, _| {}, |_| true);
+        assert!(complaints.is_empty());
+        let qualified = qualified(&dealings, T, &complaints, &answers);
+        assert_eq!(qualified.len(), N);
+        sign_with_quorum(&participants, &qualified, &[0, 2, 4]);
+    }
+
+    #[test]
+    fn malicious_dealer_is_disqualified() {
+        // Dealer 2 sends a wrong share to participant 4 and then reveals a wrong
+        // one as well; dealer 5 sends a wrong share to participant 1 but answers
+        // the complaint honestly, so it stays in.
+        let Run { participants, dealings, complaints, answers } = run(
+            |dealer, share| {
+                if (dealer == 2 && share.index == 4) || (dealer == 5 && share.index == 1) {
+                    share.secret += ScalarField::from(7u64);
+                }
+            },
+            |dealer| dealer != 2,
+        );
+        assert_eq!(complaints, vec![Complaint { accuser: 4, dealer: 2 }, Complaint { accuser: 1, dealer: 5 }]);
+
+        let qualified = qualified(&dealings, T, &complaints, &answers);
+        let dealers: Vec<u64> = qualified.iter().map(|d| d.dealer).collect();
+        assert_eq!(dealers, vec![1, 3, 4, 5]);
+        assert_eq!(group_public_key(&qualified), public_key_share(&qualified, 0));
+        sign_with_quorum(&participants, &qualified, &[1, 3, 0]);
+    }
+
+    #[test]
+    fn malformed_dealing_is_disqualified() {
+        let mut rng = test_rng();
+        let honest: Vec<Participant<MinSig>> = (1..=3).map(|i| Participant::new(i, 2, 3, &mut rng).unwrap()).collect();
+        let mut dealings: Vec<_> = honest.iter().map(|p| p.dealing().clone()).collect();
+        // A dealer committing to a degree-2 polynomial could later hand out shares
+        // no 2-subset can interpolate.
+        let extra = dealings[1].commitments[0];
+        dealings[1].commitments.push(extra);
+        let qualified = qualified(&dealings, 2, &[], &[]);
+        assert_eq!(qualified.iter().map(|d| d.dealer).collect::<Vec<_>>(), vec![1, 3]);
+    }
+
+    #[test]
+    fn finalize_needs_enough_dealers_and_shares() {
+        let mut rng = test_rng();
+        assert!(Participant::<MinSig>::new(0, 2, 3, &mut rng).is_err());
+        assert!(Participant::<MinSig>::new(1, 4, 3, &mut rng).is_err());
+
+        let participant = Participant::<MinSig>::new(1, 2, 3, &mut rng).unwrap();
+        let other = Participant::<MinSig>::new(2, 2, 3, &mut rng).unwrap();
+        assert_eq!(participant.finalize(&[participant.dealing()]), Err(DkgError::TooFewQualified));
+        assert_eq!(
+            participant.finalize(&[participant.dealing(), other.dealing()]),
+            Err(DkgError::MissingShare(1))
+        );
+    }
+}
@@ -0,0 +1,142 @@
+// Ethereum consensus-spec BLS test vectors (tests/general/phase0/bls), run
+// against both variants. Ethereum uses the PoP scheme with `MinPk`; the
+// `min_sig` files are the same cases in the other variant. See
+// fixtures/eth/README.md for where they come from.
+//
+// Points are decoded with `serialization`; an encoding it rejects makes
+// verification fail, as in the specs.
+
+use serde_json::Value;
+
+use crate::aggregate::aggregate_signatures;
+use crate::serialization::{public_key_from_bytes, secret_key_from_bytes, signature_from_bytes, signature_to_bytes};
+use crate::{pop, MinPk, MinSig, Variant};
+
+fn bytes(hex: &Value) -> Vec<u8> {
+    hex::decode(hex.as_str().unwrap().trim_start_matches("0x")).unwrap()
+}
+
+fn to_hex<V: Variant>(signature: &V::Signature) -> String {
+    format!("0x{}", hex::encode(signature_to_bytes::<V>(signature)))
+}
+
+fn public_key<V: Variant>(hex: &Value) -> Option<V::PublicKey> {
+    public_key_from_bytes::<V>(&bytes(hex)).ok()
+}
+
+fn signature<V: Variant>(hex: &Value) -> Option<V::Signature> {
+    signature_from_bytes::<V>(&bytes(hex)).ok()
+}
+
+fn public_keys<V: Variant>(list: &Value) -> Option<Vec<V::PublicKey>> {
+    list.as_array().unwrap().iter().map(public_key::<V>).collect()
+}
+
+fn cases(json: &str) -> Vec<Value> {
+    let cases: Value = serde_json::from_str(json).unwrap();
+    cases.as_array().unwrap().clone()
+}
+
+fn check_sign<V: Variant>(json: &str) {
+    for case in cases(json) {
+        let input = &case["input"];
+        let output = secret_key_from_bytes(&bytes(&input["privkey"]))
+            .ok()
+            .map(|sk| to_hex::<V>(&pop::sign::<V>(&sk, &bytes(&input["message"]))));
+        assert_eq!(output.as_deref(), case["output"].as_str(), "{}", case["name"]);
+    }
+}
+
+fn check_verify<V: Variant>(json: &str) {
+    for case in cases(json) {
+        let input = &case["input"];
+        let valid = match (public_key::<V>(&input["pubkey"]), signature::<V>(&input["signature"])) {
+            (Some(pk), Some(sig)) => pop::verify::<V>(&pk, &bytes(&input["message"]), &sig),
+            _ => false,
+        };
+        assert_eq!(valid, case["output"].as_bool().unwrap(), "{}", case["name"]);
+    }
+}
+
+fn check_aggregate<V: Variant>(json: &str) {
+    for case in cases(json) {
+        let signatures: Vec<V::Signature> =
+            case["input"].as_array().unwrap().iter().map(|s| signature::<V>(s).unwrap()).collect();
+        let output = aggregate_signatures::<V>(&signatures).map(|sig| to_hex::<V>(&sig));
+        assert_eq!(output.as_deref(), case["output"].as_str(), "{}", case["name"]);
+    }
+}
+
+fn check_fast_aggregate_verify<V: Variant>(json: &str) {
+    for case in cases(json) {
+        let input = &case["input"];
+        let valid = match (public_keys::<V>(&input["pubkeys"]), signature::<V>(&input["signature"])) {
+            (Some(pks), Some(sig)) => pop::fast_aggregate_verify::<V>(&pks, &bytes(&input["message"]), &sig),
+            _ => false,
+        };
+        assert_eq!(valid, case["output"].as_bool().unwrap(), "{}", case["name"]);
+    }
+}
+
+fn check_aggregate_verify<V: Variant>(json: &str) {
+    for case in cases(json) {
+        let input = &case["input"];
+        let messages: Vec<Vec<u8>> = input["messages"].as_array().unwrap().iter().map(bytes).collect();
+        let messages: Vec<&[u8]> = messages.iter().map(|m| m.as_slice()).collect();
+        let valid = match (public_keys::<V>(&input["pubkeys"]), signature::<V>(&input["signature"])) {
+            (Some(pks), Some(sig)) => pop::aggregate_verify::<V>(&pks, &messages, &sig),
+            _ => false,
+        };
+        assert_eq!(valid, case["output"].as_bool().unwrap(), "{}", case["name"]);
+    }
+}
+
+#[test]
+fn min_pk_sign() {
+    check_sign::<MinPk>(include_str!("../fixtures/eth/min_pk/sign.json"));
+}
+
+#[test]
+fn min_pk_verify() {
+    check_verify::<MinPk>(include_str!("../fixtures/eth/min_pk/verify.json"));
+}
+
+#[test]
+fn min_pk_aggregate() {
+    check_aggregate::<MinPk>(include_str!("../fixtures/eth/min_pk/aggregate.json"));
+}
+
+#[test]
+fn min_pk_fast_aggregate_verify() {
+    check_fast_aggregate_verify::<MinPk>(include_str!("../fixtures/eth/min_pk/fast_aggregate_verify.json"));
+}
+
+#[test]
+fn min_pk_aggregate_verify() {
+    check_aggregate_verify::<MinPk>(include_str!("../fixtures/eth/min_pk/aggregate_verify.json"));
+}
+
+#[test]
+fn min_sig_sign() {
+    check_sign::<MinSig>(include_str!("../fixtures/eth/min_sig/sign.json"));
+}
+
+#[test]
+fn min_sig_verify() {
+    check_verify::<MinSig>(include_str!("../fixtures/eth/min_sig/verify.json"));
+}
+
+#[test]
+fn min_sig_aggregate() {
+    check_aggregate::<MinSig>(include_str!("../fixtures/eth/min_sig/aggregate.json"));
+}
+
+#[test]
+fn min_sig_fast_aggregate_verify() {
+    check_fast_aggregate_verify::<MinSig>(include_str!("../fixtures/eth/min_sig/fast_aggregate_verify.json"));
+}
+
+#[test]
+fn min_sig_aggregate_verify() {
+    check_aggregate_verify::<MinSig>(include_str!("../fixtures/eth/min_sig/aggregate_verify.json"));
+}
@@ -0,0 +1,201 @@
+// Hashing to BLS12-381 as specified in RFC 9380, suites BLS12381G1_XMD:SHA-256_SSWU_RO_
+// and BLS12381G2_XMD:SHA-256_SSWU_RO_.
+//
+//     u = hash_to_field(msg, 2)          expand_message_xmd with SHA-256, 64 bytes per element
+//     Q0 = map_to_curve(u[0])            simplified SWU on an 11-isogenous curve, then the isogeny
+//     Q1 = map_to_curve(u[1])
+//     P = clear_cofactor(Q0 + Q1)        h_eff = 1 - x on G1, the Budroni-Pintore method on G2
+//
+// On G2 the field elements live in Fp2 and the isogeny has degree 3.
+//
+// Nobody learns the discrete logarithm of the result with respect to the
+// generator, which is what makes the hashed point safe to sign.
+//
+// expand_message_xmd and hash_to_field are written out here; the SWU map and the
+// isogeny come from arkworks (`WBMap` with the isogeny constants of
+// `ark_bls12_381`), and cofactor clearing from the curve configuration.
+
+use ark_bls12_381::{g1, g2, G1Projective as G1, G2Projective as G2};
+use ark_ec::hashing::curve_maps::wb::{WBConfig, WBMap};
+use ark_ec::hashing::map_to_curve_hasher::MapToCurve;
+use ark_ec::short_weierstrass::{Affine, Projective};
+use ark_ec::AffineRepr;
+use ark_ff::{Field, PrimeField};
+use sha2::{Digest, Sha256};
+
+// SHA-256 output and block sizes (b_in_bytes and s_in_bytes in the RFC).
+const B_IN_BYTES: usize = 32;
+const S_IN_BYTES: usize = 64;
+// Bytes hashed per base field element: ceil((ceil(log2(p)) + k) / 8) with k = 128.
+const L: usize = 64;
+
+// Shortens a DST longer than 255 bytes as in RFC 9380 section 5.3.3.
+fn dst_prime(dst: &[u8]) -> Vec<u8> {
+    let mut out = if dst.len() > 255 {
+        Sha256::new().chain_update(b"H2C-OVERSIZE-DST-").chain_update(dst).finalize().to_vec()
+    } else {
+        dst.to_vec()
+    };
+    out.push(out.len() as u8);
+    out
+}
+
+// expand_message_xmd with SHA-256 (RFC 9380 section 5.3.1).
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    let ell = len_in_bytes.div_ceil(B_IN_BYTES);
+    assert!(ell <= 255 && len_in_bytes <= 65535, "requested output too long");
+    let dst_prime = dst_prime(dst);
+
+    let b0 = Sha256::new()
+        .chain_update([0u8; S_IN_BYTES])
+        .chain_update(msg)
+        .chain_update((len_in_bytes as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    let mut uniform_bytes = Vec::with_capacity(ell * B_IN_BYTES);
+    let mut b_prev = Sha256::new().chain_update(b0).chain_update([1u8]).chain_update(&dst_prime).finalize();
+    uniform_bytes.extend_from_slice(&b_prev);
+    for i in 2..=ell {
+        let mixed: Vec<u8> = b0.iter().zip(b_prev.iter()).map(|(a, b)| a ^ b).collect();
+        b_prev = Sha256::new().chain_update(mixed).chain_update([i as u8]).chain_update(&dst_prime).finalize();
+        uniform_bytes.extend_from_slice(&b_prev);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+    uniform_bytes
+}
+
+// hash_to_field (RFC 9380 section 5.2) for a field of extension degree m over its prime field.
+pub fn hash_to_field<F: Field>(msg: &[u8], dst: &[u8], count: usize) -> Vec<F> {
+    let m = F::extension_degree() as usize;
+    let uniform_bytes = expand_message_xmd(msg, dst, count * m * L);
+    uniform_bytes
+        .chunks(m * L)
+        .map(|elem| {
+            let coeffs = elem.chunks(L).map(F::BasePrimeField::from_be_bytes_mod_order);
+            F::from_base_prime_field_elems(coeffs).expect("m coefficients")
+        })
+        .collect()
+}
+
+// map_to_curve: simplified SWU onto the isogenous curve followed by the isogeny.
+pub fn map_to_curve<P: WBConfig>(u: P::BaseField) -> Affine<P> {
+    WBMap::<P>::map_to_curve(u).expect("SWU map is defined for every field element")
+}
+
+// The random-oracle encoding for any curve with an SWU-friendly isogeny.
+pub fn hash_to<P: WBConfig>(msg: &[u8], dst: &[u8]) -> Projective<P> {
+    let u = hash_to_field::<P::BaseField>(msg, dst, 2);
+    let q0 = map_to_curve::<P>(u[0]);
+    let q1 = map_to_curve::<P>(u[1]);
+    let r: Affine<P> = (q0 + q1).into();
+    r.clear_cofactor().into()
+}
+
+// BLS12381G1_XMD:SHA-256_SSWU_RO_ with the given domain separation tag.
+pub fn hash_to_g1(msg: &[u8], dst: &[u8]) -> G1 {
+    hash_to::<g1::Config>(msg, dst)
+}
+
+// BLS12381G2_XMD:SHA-256_SSWU_RO_ with the given domain separation tag.
+pub fn hash_to_g2(msg: &[u8], dst: &[u8]) -> G2 {
+    hash_to::<g2::Config>(msg, dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Fq, Fq2, G1Affine, G2Affine};
+    use ark_ec::CurveGroup;
+    use serde_json::Value;
+
+    fn fq(hex: &Value) -> Fq {
+        let bytes = hex::decode(hex.as_str().unwrap().trim_start_matches("0x")).unwrap();
+        Fq::from_be_bytes_mod_order(&bytes)
+    }
+
+    // Q0 and Q1 are on the curve but generally outside the prime-order subgroup.
+    fn point(v: &Value) -> G1Affine {
+        let p = G1Affine::new_unchecked(fq(&v["x"]), fq(&v["y"]));
+        assert!(p.is_on_curve());
+        p
+    }
+
+    // Fp2 elements are written "c0,c1".
+    fn fq2(hex: &Value) -> Fq2 {
+        let (c0, c1) = hex.as_str().unwrap().split_once(',').unwrap();
+        Fq2::new(fq(&Value::from(c0)), fq(&Value::from(c1)))
+    }
+
+    fn point2(v: &Value) -> G2Affine {
+        let p = G2Affine::new_unchecked(fq2(&v["x"]), fq2(&v["y"]));
+        assert!(p.is_on_curve());
+        p
+    }
+
+    // RFC 9380 appendix J.9.1.
+    #[test]
+    fn rfc9380_g1_vectors() {
+        let suite: Value =
+            serde_json::from_str(include_str!("../fixtures/hash_to_curve/BLS12381G1_XMD-SHA-256_SSWU_RO_.json")).unwrap();
+        let dst = suite["dst"].as_str().unwrap().as_bytes();
+        let vectors = suite["vectors"].as_array().unwrap();
+        assert_eq!(vectors.len(), 5);
+
+        for v in vectors {
+            let msg = v["msg"].as_str().unwrap().as_bytes();
+            let u = hash_to_field::<Fq>(msg, dst, 2);
+            assert_eq!(u, vec![fq(&v["u"][0]), fq(&v["u"][1])]);
+            assert_eq!(map_to_curve::<g1::Config>(u[0]), point(&v["Q0"]));
+            assert_eq!(map_to_curve::<g1::Config>(u[1]), point(&v["Q1"]));
+            assert_eq!(hash_to_g1(msg, dst).into_affine(), point(&v["P"]));
+        }
+    }
+
+    // RFC 9380 appendix J.10.1.
+    #[test]
+    fn rfc9380_g2_vectors() {
+        let suite: Value =
+            serde_json::from_str(include_str!("../fixtures/hash_to_curve/BLS12381G2_XMD-SHA-256_SSWU_RO_.json")).unwrap();
+        let dst = suite["dst"].as_str().unwrap().as_bytes();
+        let vectors = suite["vectors"].as_array().unwrap();
+        assert_eq!(vectors.len(), 5);
+
+        for v in vectors {
+            let msg = v["msg"].as_str().unwrap().as_bytes();
+            let u = hash_to_field::<Fq2>(msg, dst, 2);
+            assert_eq!(u, vec![fq2(&v["u"][0]), fq2(&v["u"][1])]);
+            assert_eq!(map_to_curve::<g2::Config>(u[0]), point2(&v["Q0"]));
+            assert_eq!(map_to_curve::<g2::Config>(u[1]), point2(&v["Q1"]));
+            assert_eq!(hash_to_g2(msg, dst).into_affine(), point2(&v["P"]));
+        }
+    }
+
+    // RFC 9380 appendix K.1, expand_message_xmd(SHA-256) with len_in_bytes = 0x20.
+    #[test]
+    fn rfc9380_expand_message_xmd_vectors() {
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        let vectors = [
+            ("", "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"),
+            ("abc", "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"),
+        ];
+        for (msg, expected) in vectors {
+            assert_eq!(hex::encode(expand_message_xmd(msg.as_bytes(), dst, 0x20)), expected);
+        }
+    }
+
+    #[test]
+    fn oversized_dst_is_hashed() {
+        let long_dst = [b'D'; 300];
+        let short_dst = Sha256::new().chain_update(b"H2C-OVERSIZE-DST-").chain_update(long_dst).finalize();
+        assert_eq!(expand_message_xmd(b"msg", &long_dst, 48), expand_message_xmd(b"msg", &short_dst, 48));
+    }
+
+    #[test]
+    fn hashed_points_are_in_the_subgroup_and_separated_by_dst() {
+        let p = hash_to_g1(b"message", b"DST-A").into_affine();
+        assert!(p.is_on_curve() && p.is_in_correct_subgroup_assuming_on_curve());
+        assert_ne!(p, hash_to_g1(b"message", b"DST-B").into_affine());
+    }
+}
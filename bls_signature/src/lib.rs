@@ -0,0 +1,93 @@
+//! BLS signatures over BLS12-381 using the arkworks ecosystem.
+//!
+//! The scheme is generic over a `Variant`: `MinSig` puts signatures in G1 and
+//! public keys in G2, `MinPk` (used by Ethereum consensus) does the opposite.
+//! Messages are hashed into the signature group with the RFC 9380 suites in
+//! `hash_to_curve`. `aggregate` combines signatures and public keys of many
+//! signers and verifies the aggregates; `pop` adds the proof-of-possession
+//! scheme that makes same-message aggregation safe against rogue keys. `batch`
+//! verifies many independent signatures with a single multi-pairing.
+//! `serialization` encodes keys and signatures as compressed points and
+//! rejects anything outside the prime-order subgroup when decoding them.
+//! `threshold` splits a secret key into Shamir shares for t-of-n signing, and
+//! `dkg` generates such shares without any party knowing the whole key.
+//! `vrf` turns unique signatures into verifiable randomness, and `beacon`
+//! chains threshold VRF outputs into a drand-style randomness beacon. `blind`
+//! lets a signer sign a message without seeing it.
+
+pub mod aggregate;
+pub mod batch;
+pub mod beacon;
+pub mod blind;
+pub mod dkg;
+pub mod hash_to_curve;
+pub mod pop;
+pub mod serialization;
+pub mod threshold;
+pub mod variant;
+pub mod vrf;
+
+#[cfg(test)]
+mod eth_vectors;
+
+pub use variant::{MinPk, MinSig, Variant};
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ec::{CurveGroup, PrimeGroup};
+use ark_std::{rand::Rng, UniformRand, Zero};
+
+// Hash message to curve point (signature group)
+pub fn hash_to_curve<V: Variant>(message: &[u8]) -> V::Signature {
+    V::hash_to_curve(message, V::DST)
+}
+
+// Generate a keypair (public key group)
+pub fn keygen<V: Variant, R: Rng>(rng: &mut R) -> (ScalarField, V::PublicKey) {
+    // Generate private key (random scalar)
+    let private_key = ScalarField::rand(rng);
+    // Generate public key (generator point * private key)
+    let public_key = V::PublicKey::generator() * private_key;
+
+    (private_key, public_key)
+}
+
+// Sign a message
+pub fn sign<V: Variant>(private_key: &ScalarField, message: &[u8]) -> V::Signature {
+    sign_with_dst::<V>(private_key, message, V::DST)
+}
+
+// Verify a signature
+pub fn verify<V: Variant>(public_key: &V::PublicKey, message: &[u8], signature: &V::Signature) -> bool {
+    verify_with_dst::<V>(public_key, message, signature, V::DST)
+}
+
+// Sign a message hashed under another ciphersuite's domain separation tag
+pub fn sign_with_dst<V: Variant>(private_key: &ScalarField, message: &[u8], dst: &[u8]) -> V::Signature {
+    // Hash message to curve point and multiply by private key
+    let h = V::hash_to_curve(message, dst);
+    h * private_key
+}
+
+// Verify a signature made with `sign_with_dst`
+//
+// Checks e(g, signature) == e(pk, H(m)) as e(-g, signature) * e(pk, H(m)) == 1:
+// one multi-pairing shares a single final exponentiation between both Miller loops.
+// The identity is not a valid public key (KeyValidate in the IETF draft): it
+// would accept the identity as a signature on every message.
+pub fn verify_with_dst<V: Variant>(
+    public_key: &V::PublicKey,
+    message: &[u8],
+    signature: &V::Signature,
+    dst: &[u8],
+) -> bool {
+    if public_key.is_zero() {
+        return false;
+    }
+    let h = V::hash_to_curve(message, dst);
+    let neg_generator = -V::PublicKey::generator();
+
+    V::pairing_product_is_one(
+        V::PublicKey::normalize_batch(&[neg_generator, *public_key]),
+        V::Signature::normalize_batch(&[*signature, h]),
+    )
+}
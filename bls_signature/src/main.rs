@@ -51,78 +51,60 @@ else:
     print("Signature is invalid.")
  */
 
+use bls_signature::aggregate::{aggregate_signatures, fast_aggregate_verify};
+use bls_signature::serialization::{public_key_to_bytes, secret_key_to_bytes, signature_to_bytes};
+use bls_signature::{keygen, sign, verify, MinPk, MinSig};
 
- use ark_ec::{AdditiveGroup, PrimeGroup, CurveGroup};
- use ark_ec::{pairing::Pairing, AffineRepr};
- use ark_ff::{PrimeField, Field};
- use ark_bls12_381::{G1Affine, G1Projective as G1, Fr as ScalarField, G2Projective as G2, Fq12 as Fq12};
- use ark_std::{Zero, UniformRand, ops::Mul, rand::Rng};
- use sha2::{Sha256, Digest};
- 
- // Hash message to curve point ( G1)
- fn hash_to_curve(message: &[u8]) -> G1 {
-     let mut hasher = Sha256::new();
-     hasher.update(message);
-     let result = hasher.finalize();
-     let scalar = ScalarField::from_le_bytes_mod_order(&result);
-     let base_point = G1::generator();
-     base_point * scalar
- }
- 
- // Generate a keypair ( G2 )
- fn keygen<R: Rng>(rng: &mut R) -> (ScalarField, G2) {
-     // Generate private key (random scalar)
-     let private_key = ScalarField::rand(rng);
-     // Generate public key (generator point * private key)
-     let public_key = G2::generator() * private_key;
-     
-     (private_key, public_key)
- }
- 
- // Sign a message
- fn sign(private_key: &ScalarField, message: &[u8]) -> G1 {
-     // Hash message to curve point and multiply by private key
-     let h = hash_to_curve(message);
-     h * private_key
- }
- 
-// Verify a signature
-fn verify(public_key: &G2, message: &[u8], signature: &G1) -> bool {
-    let h = hash_to_curve(message);
-    
-    // Convert points to affine for pairing
-    let sig_affine = signature.into_affine();
-    let pk_affine = public_key.into_affine();
-    let g1_affine = G1::generator().into_affine();
-    
-    // Compute pairings
-    let pairing1 = ark_bls12_381::Bls12_381::pairing(sig_affine, G2::generator().into_affine());
-    let pairing2 = ark_bls12_381::Bls12_381::pairing(h.into_affine(), pk_affine);
-    
-    pairing1 == pairing2
+fn main() {
+    let mut rng = ark_std::test_rng();
+
+    // Generate keypair
+    let (private_key, public_key) = keygen::<MinSig, _>(&mut rng);
+    println!(
+        "Private key: {}",
+        hex::encode(secret_key_to_bytes(&private_key))
+    );
+    println!(
+        "Public key: {}",
+        hex::encode(public_key_to_bytes::<MinSig>(&public_key))
+    );
+
+    // Message to sign
+    let message = b"Hello, BLS Signatures!";
+
+    // Sign the message
+    let signature = sign::<MinSig>(&private_key, message);
+    println!(
+        "Signature: {}",
+        hex::encode(signature_to_bytes::<MinSig>(&signature))
+    );
+
+    // Verify the signature
+    let is_valid = verify::<MinSig>(&public_key, message, &signature);
+    println!("Signature valid? {}", is_valid);
+
+    // Try verifying with wrong message
+    let wrong_message = b"Wrong message!";
+    let is_invalid = verify::<MinSig>(&public_key, wrong_message, &signature);
+    println!("Wrong message signature valid? {}", is_invalid);
+
+    // Aggregate signatures of several participants on the same message
+    let participants: Vec<_> = (0..3).map(|_| keygen::<MinSig, _>(&mut rng)).collect();
+    let signatures: Vec<_> = participants
+        .iter()
+        .map(|(sk, _)| sign::<MinSig>(sk, message))
+        .collect();
+    let public_keys: Vec<_> = participants.iter().map(|(_, pk)| *pk).collect();
+    let aggregated_signature =
+        aggregate_signatures::<MinSig>(&signatures).expect("at least one signature");
+    let is_valid = fast_aggregate_verify::<MinSig>(&public_keys, message, &aggregated_signature);
+    println!("Aggregated signature valid? {}", is_valid);
+
+    // The same scheme with keys in G1 and signatures in G2, as in Ethereum
+    let (private_key, public_key) = keygen::<MinPk, _>(&mut rng);
+    let signature = sign::<MinPk>(&private_key, message);
+    println!(
+        "Min-pk signature valid? {}",
+        verify::<MinPk>(&public_key, message, &signature)
+    );
 }
- 
- fn main() {
-     let mut rng = ark_std::test_rng();
-     
-     // Generate keypair
-     let (private_key, public_key) = keygen(&mut rng);
-     println!("Private key: {:?}", private_key);
-     println!("Public key: {:?}", public_key);
-     
-     // Message to sign
-     let message = b"Hello, BLS Signatures!";
-     
-     // Sign the message
-     let signature = sign(&private_key, message);
-     println!("Signature: {:?}", signature);
-     
-     // Verify the signature
-     let is_valid = verify(&public_key, message, &signature);
-     println!("Signature valid? {}", is_valid);
-     
-     // Try verifying with wrong message
-     let wrong_message = b"Wrong message!";
-     let is_invalid = verify(&public_key, wrong_message, &signature);
-     println!("Wrong message signature valid? {}", is_invalid);
- }
\ No newline at end of file
@@ -0,0 +1,136 @@
+// Proof-of-possession scheme from the IETF BLS signature draft (section 3.3).
+//
+// Summing public keys is only safe if every key's owner knows its secret key.
+// Otherwise an attacker facing a victim key PK_v registers
+//
+//     PK_evil = g2^x - PK_v
+//
+// so that PK_v + PK_evil = g2^x, and H(m)^x passes as an aggregate signature of
+// both parties on any m. A proof of possession is a signature on the public key
+// itself, under its own domain separation tag; the attacker cannot produce one
+// for PK_evil because nobody knows its discrete logarithm.
+//
+// Signatures in this scheme use their own DST as well (`Variant::POP_SIG_DST`),
+// so they are never interchangeable with basic-scheme signatures from
+// `crate::sign`. With `MinPk` this is the scheme Ethereum consensus uses.
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_std::Zero;
+
+use crate::aggregate::{aggregate_public_keys, core_aggregate_verify};
+use crate::serialization::public_key_to_bytes;
+use crate::{sign_with_dst, verify_with_dst, Variant};
+
+// PopProve: a signature on the compressed encoding of our own public key.
+pub fn pop_prove<V: Variant>(private_key: &ScalarField, public_key: &V::PublicKey) -> V::Signature {
+    sign_with_dst::<V>(private_key, &public_key_to_bytes::<V>(public_key), V::POP_DST)
+}
+
+// PopVerify: checks a proof of possession before the key is accepted for aggregation.
+pub fn pop_verify<V: Variant>(public_key: &V::PublicKey, proof: &V::Signature) -> bool {
+    verify_with_dst::<V>(public_key, &public_key_to_bytes::<V>(public_key), proof, V::POP_DST)
+}
+
+pub fn sign<V: Variant>(private_key: &ScalarField, message: &[u8]) -> V::Signature {
+    sign_with_dst::<V>(private_key, message, V::POP_SIG_DST)
+}
+
+pub fn verify<V: Variant>(public_key: &V::PublicKey, message: &[u8], signature: &V::Signature) -> bool {
+    verify_with_dst::<V>(public_key, message, signature, V::POP_SIG_DST)
+}
+
+// FastAggregateVerify of the PoP scheme. Every public key must have passed
+// `pop_verify` beforehand; identity keys are rejected regardless, as in the
+// Ethereum consensus specs.
+pub fn fast_aggregate_verify<V: Variant>(public_keys: &[V::PublicKey], message: &[u8], signature: &V::Signature) -> bool {
+    if public_keys.iter().any(|pk| pk.is_zero()) {
+        return false;
+    }
+    match aggregate_public_keys::<V>(public_keys) {
+        Some(aggregate) => verify::<V>(&aggregate, message, signature),
+        None => false,
+    }
+}
+
+// Checks every proof of possession, then the aggregate signature.
+pub fn fast_aggregate_verify_with_proofs<V: Variant>(
+    public_keys: &[V::PublicKey],
+    proofs: &[V::Signature],
+    message: &[u8],
+    signature: &V::Signature,
+) -> bool {
+    public_keys.len() == proofs.len()
+        && public_keys.iter().zip(proofs).all(|(pk, proof)| pop_verify::<V>(pk, proof))
+        && fast_aggregate_verify::<V>(public_keys, message, signature)
+}
+
+// AggregateVerify of the PoP scheme. Unlike the basic scheme, messages may
+// repeat: proofs of possession already rule out rogue keys.
+pub fn aggregate_verify<V: Variant>(public_keys: &[V::PublicKey], messages: &[&[u8]], signature: &V::Signature) -> bool {
+    core_aggregate_verify::<V>(public_keys, messages, signature, V::POP_SIG_DST)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregate::aggregate_signatures;
+    use crate::{keygen, MinSig};
+    use ark_bls12_381::{G1Projective as G1, G2Projective as G2};
+    use ark_ec::PrimeGroup;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    fn honest_proofs_verify() {
+        let mut rng = test_rng();
+        let keys: Vec<_> = (0..3).map(|_| keygen::<MinSig, _>(&mut rng)).collect();
+        let public_keys: Vec<G2> = keys.iter().map(|(_, pk)| *pk).collect();
+        let proofs: Vec<G1> = keys.iter().map(|(sk, pk)| pop_prove::<MinSig>(sk, pk)).collect();
+        assert!(public_keys.iter().zip(&proofs).all(|(pk, proof)| pop_verify::<MinSig>(pk, proof)));
+
+        let message = b"attestation";
+        let signatures: Vec<G1> = keys.iter().map(|(sk, _)| sign::<MinSig>(sk, message)).collect();
+        let aggregate = aggregate_signatures::<MinSig>(&signatures).unwrap();
+        assert!(fast_aggregate_verify_with_proofs::<MinSig>(&public_keys, &proofs, message, &aggregate));
+
+        // A proof is bound to its key, and proofs are not message signatures.
+        assert!(!pop_verify::<MinSig>(&public_keys[1], &proofs[0]));
+        assert!(!verify::<MinSig>(&public_keys[0], &public_key_to_bytes::<MinSig>(&public_keys[0]), &proofs[0]));
+    }
+
+    #[test]
+    fn pop_stops_rogue_key_attack() {
+        let mut rng = test_rng();
+        let (victim_sk, victim_pk) = keygen::<MinSig, _>(&mut rng);
+        let victim_proof = pop_prove::<MinSig>(&victim_sk, &victim_pk);
+
+        // The attacker picks x and publishes PK_evil = g2^x - PK_victim.
+        let x = ScalarField::rand(&mut rng);
+        let evil_pk = G2::generator() * x - victim_pk;
+
+        // The victim never signed this, yet naive aggregation accepts H(m)^x as
+        // a joint signature because PK_victim + PK_evil = g2^x.
+        let message = b"victim transfers everything to attacker";
+        let forged = sign::<MinSig>(&x, message);
+        assert!(fast_aggregate_verify::<MinSig>(&[victim_pk, evil_pk], message, &forged));
+
+        // The attacker can only sign with x, which is not the secret key of PK_evil.
+        let evil_proof = sign_with_dst::<MinSig>(&x, &public_key_to_bytes::<MinSig>(&evil_pk), MinSig::POP_DST);
+        assert!(!pop_verify::<MinSig>(&evil_pk, &evil_proof));
+        assert!(!fast_aggregate_verify_with_proofs::<MinSig>(
+            &[victim_pk, evil_pk],
+            &[victim_proof, evil_proof],
+            message,
+            &forged
+        ));
+    }
+
+    #[test]
+    fn scheme_signatures_are_domain_separated() {
+        let mut rng = test_rng();
+        let (sk, pk) = keygen::<MinSig, _>(&mut rng);
+        let message = b"hello";
+        assert!(verify::<MinSig>(&pk, message, &sign::<MinSig>(&sk, message)));
+        assert!(!verify::<MinSig>(&pk, message, &crate::sign::<MinSig>(&sk, message)));
+        assert!(!crate::verify::<MinSig>(&pk, message, &sign::<MinSig>(&sk, message)));
+    }
+}
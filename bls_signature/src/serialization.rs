@@ -0,0 +1,277 @@
+// Byte encodings of keys and signatures.
+//
+// Points use the ZCash compressed format shared by the IETF BLS draft and
+// Ethereum: 48 bytes for G1, 96 for G2 (x = c1 || c0), big-endian x with three
+// flag bits in the most significant bits of the first byte:
+//
+//     0x80  compressed, always set
+//     0x40  point at infinity, the remaining bits must all be zero
+//     0x20  y is the lexicographically larger of the two square roots
+//
+// Decoding is strict. Anything that is not the canonical encoding of a point
+// in the prime-order subgroup is rejected: wrong length, bad flags, x >= p, an
+// x with no point on the curve, or a point outside the subgroup. Accepting a
+// point of small order would let an attacker learn bits of a secret key or
+// forge signatures that verify only on some implementations. The identity is a
+// valid signature encoding but never a valid public key (KeyValidate).
+//
+// Secret keys are 32-byte big-endian integers in [1, r).
+
+use std::fmt;
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
+use ark_serialize::{Compress, Validate};
+
+use crate::Variant;
+
+const COMPRESSED: u8 = 0x80;
+const INFINITY: u8 = 0x40;
+const FLAGS: u8 = 0xe0;
+
+pub const SECRET_KEY_SIZE: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    InvalidLength { expected: usize, found: usize },
+    // Uncompressed or badly flagged encoding, or a coordinate not reduced mod p.
+    InvalidEncoding,
+    // No point on the curve has this x-coordinate.
+    NotOnCurve,
+    // On the curve, but not in the prime-order subgroup.
+    NotInSubgroup,
+    IdentityPublicKey,
+    // Zero, or not below the group order.
+    InvalidSecretKey,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength { expected, found } => write!(f, "expected {} bytes, found {}", expected, found),
+            DecodeError::InvalidEncoding => write!(f, "not a canonical compressed point encoding"),
+            DecodeError::NotOnCurve => write!(f, "point is not on the curve"),
+            DecodeError::NotInSubgroup => write!(f, "point is not in the prime-order subgroup"),
+            DecodeError::IdentityPublicKey => write!(f, "the identity is not a valid public key"),
+            DecodeError::InvalidSecretKey => write!(f, "secret key is not in [1, r)"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+fn encode_point<A: AffineRepr>(point: &A) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(point.compressed_size());
+    point.serialize_compressed(&mut bytes).expect("serializing into a Vec cannot fail");
+    bytes
+}
+
+fn decode_point<A: AffineRepr>(bytes: &[u8]) -> Result<A, DecodeError> {
+    let expected = A::generator().compressed_size();
+    if bytes.len() != expected {
+        return Err(DecodeError::InvalidLength { expected, found: bytes.len() });
+    }
+    if bytes[0] & COMPRESSED == 0 {
+        return Err(DecodeError::InvalidEncoding);
+    }
+    if bytes[0] & INFINITY != 0 {
+        // Exactly 0xc0 followed by zeros.
+        if bytes[0] != COMPRESSED | INFINITY || bytes[1..].iter().any(|&b| b != 0) {
+            return Err(DecodeError::InvalidEncoding);
+        }
+        return Ok(A::zero());
+    }
+
+    // Every coordinate of x, flags stripped, must be below p.
+    let modulus = <A::BaseField as Field>::BasePrimeField::MODULUS.to_bytes_be();
+    let mut x = bytes.to_vec();
+    x[0] &= !FLAGS;
+    if x.chunks(modulus.len()).any(|c| c >= modulus.as_slice()) {
+        return Err(DecodeError::InvalidEncoding);
+    }
+
+    // Unchecked decoding recovers y from x, so it fails only when there is no y;
+    // subgroup membership is checked separately.
+    let point = A::deserialize_with_mode(bytes, Compress::Yes, Validate::No).map_err(|_| DecodeError::NotOnCurve)?;
+    point.check().map_err(|_| DecodeError::NotInSubgroup)?;
+    Ok(point)
+}
+
+pub fn public_key_to_bytes<V: Variant>(public_key: &V::PublicKey) -> Vec<u8> {
+    encode_point(&public_key.into_affine())
+}
+
+pub fn public_key_from_bytes<V: Variant>(bytes: &[u8]) -> Result<V::PublicKey, DecodeError> {
+    let point: <V::PublicKey as CurveGroup>::Affine = decode_point(bytes)?;
+    if point.is_zero() {
+        return Err(DecodeError::IdentityPublicKey);
+    }
+    Ok(point.into())
+}
+
+pub fn signature_to_bytes<V: Variant>(signature: &V::Signature) -> Vec<u8> {
+    encode_point(&signature.into_affine())
+}
+
+pub fn signature_from_bytes<V: Variant>(bytes: &[u8]) -> Result<V::Signature, DecodeError> {
+    let point: <V::Signature as CurveGroup>::Affine = decode_point(bytes)?;
+    Ok(point.into())
+}
+
+pub fn secret_key_to_bytes(secret_key: &ScalarField) -> [u8; SECRET_KEY_SIZE] {
+    secret_key.into_bigint().to_bytes_be().try_into().expect("scalars are 32 bytes")
+}
+
+pub fn secret_key_from_bytes(bytes: &[u8]) -> Result<ScalarField, DecodeError> {
+    if bytes.len() != SECRET_KEY_SIZE {
+        return Err(DecodeError::InvalidLength { expected: SECRET_KEY_SIZE, found: bytes.len() });
+    }
+    // Reducing mod r and re-encoding round-trips only for values below r.
+    let secret_key = ScalarField::from_be_bytes_mod_order(bytes);
+    if secret_key.is_zero() || secret_key_to_bytes(&secret_key) != bytes {
+        return Err(DecodeError::InvalidSecretKey);
+    }
+    Ok(secret_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{keygen, sign, verify, MinPk, MinSig};
+    use ark_bls12_381::{Fq, Fq2, G1Affine, G1Projective as G1, G2Affine, G2Projective as G2};
+    use ark_ec::PrimeGroup;
+    use ark_std::test_rng;
+
+    #[test]
+    fn keys_and_signatures_round_trip() {
+        let mut rng = test_rng();
+        let message = b"round trip";
+
+        let (sk, pk) = keygen::<MinSig, _>(&mut rng);
+        let sig = sign::<MinSig>(&sk, message);
+        assert_eq!(public_key_to_bytes::<MinSig>(&pk).len(), 96);
+        assert_eq!(signature_to_bytes::<MinSig>(&sig).len(), 48);
+        let pk2 = public_key_from_bytes::<MinSig>(&public_key_to_bytes::<MinSig>(&pk)).unwrap();
+        let sig2 = signature_from_bytes::<MinSig>(&signature_to_bytes::<MinSig>(&sig)).unwrap();
+        assert!(verify::<MinSig>(&pk2, message, &sig2));
+
+        let (sk, pk) = keygen::<MinPk, _>(&mut rng);
+        let sig = sign::<MinPk>(&sk, message);
+        assert_eq!(public_key_to_bytes::<MinPk>(&pk).len(), 48);
+        assert_eq!(signature_to_bytes::<MinPk>(&sig).len(), 96);
+        let pk2 = public_key_from_bytes::<MinPk>(&public_key_to_bytes::<MinPk>(&pk)).unwrap();
+        let sig2 = signature_from_bytes::<MinPk>(&signature_to_bytes::<MinPk>(&sig)).unwrap();
+        assert!(verify::<MinPk>(&pk2, message, &sig2));
+
+        assert_eq!(secret_key_from_bytes(&secret_key_to_bytes(&sk)), Ok(sk));
+    }
+
+    // The G1 generator, from the IETF BLS draft and the ZCash spec.
+    #[test]
+    fn g1_generator_encoding() {
+        let bytes = public_key_to_bytes::<MinPk>(&G1::generator());
+        assert_eq!(
+            hex::encode(&bytes),
+            "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_lengths() {
+        let bytes = public_key_to_bytes::<MinPk>(&G1::generator());
+        assert_eq!(
+            public_key_from_bytes::<MinPk>(&bytes[..47]),
+            Err(DecodeError::InvalidLength { expected: 48, found: 47 })
+        );
+        assert_eq!(
+            signature_from_bytes::<MinPk>(&bytes),
+            Err(DecodeError::InvalidLength { expected: 96, found: 48 })
+        );
+        assert_eq!(
+            secret_key_from_bytes(&[1u8; 31]),
+            Err(DecodeError::InvalidLength { expected: 32, found: 31 })
+        );
+    }
+
+    #[test]
+    fn rejects_bad_flags() {
+        let mut bytes = public_key_to_bytes::<MinPk>(&G1::generator());
+        bytes[0] &= !COMPRESSED;
+        assert_eq!(public_key_from_bytes::<MinPk>(&bytes), Err(DecodeError::InvalidEncoding));
+
+        // Infinity with the sign bit set, or with a non-zero x.
+        let mut infinity = vec![0u8; 48];
+        infinity[0] = 0xe0;
+        assert_eq!(signature_from_bytes::<MinSig>(&infinity), Err(DecodeError::InvalidEncoding));
+        infinity[0] = 0xc0;
+        infinity[47] = 1;
+        assert_eq!(signature_from_bytes::<MinSig>(&infinity), Err(DecodeError::InvalidEncoding));
+    }
+
+    #[test]
+    fn rejects_unreduced_coordinates() {
+        // x = p is x = 0 written non-canonically.
+        let mut bytes = Fq::MODULUS.to_bytes_be();
+        bytes[0] |= COMPRESSED;
+        assert_eq!(signature_from_bytes::<MinSig>(&bytes), Err(DecodeError::InvalidEncoding));
+
+        let mut bytes = [Fq::MODULUS.to_bytes_be(), vec![0u8; 48]].concat();
+        bytes[0] |= COMPRESSED;
+        assert_eq!(signature_from_bytes::<MinPk>(&bytes), Err(DecodeError::InvalidEncoding));
+    }
+
+    #[test]
+    fn rejects_points_off_the_curve() {
+        // The first x for which x^3 + 4 is not a square.
+        let x = (0u64..).map(Fq::from).find(|x| G1Affine::get_point_from_x_unchecked(*x, false).is_none()).unwrap();
+        let mut bytes = x.into_bigint().to_bytes_be();
+        bytes[0] |= COMPRESSED;
+        assert_eq!(signature_from_bytes::<MinSig>(&bytes), Err(DecodeError::NotOnCurve));
+
+        let x = (0u64..)
+            .map(|i| Fq2::new(Fq::from(i), Fq::from(1u64)))
+            .find(|x| G2Affine::get_point_from_x_unchecked(*x, false).is_none())
+            .unwrap();
+        let mut bytes = [x.c1.into_bigint().to_bytes_be(), x.c0.into_bigint().to_bytes_be()].concat();
+        bytes[0] |= COMPRESSED;
+        assert_eq!(signature_from_bytes::<MinPk>(&bytes), Err(DecodeError::NotOnCurve));
+    }
+
+    #[test]
+    fn rejects_points_outside_the_subgroup() {
+        // Almost every point on the curve lies outside the subgroup, because the
+        // cofactors are large.
+        let p = (0u64..)
+            .filter_map(|i| G1Affine::get_point_from_x_unchecked(Fq::from(i), false))
+            .find(|p| !p.is_in_correct_subgroup_assuming_on_curve())
+            .unwrap();
+        assert_eq!(signature_from_bytes::<MinSig>(&encode_point(&p)), Err(DecodeError::NotInSubgroup));
+        assert_eq!(public_key_from_bytes::<MinPk>(&encode_point(&p)), Err(DecodeError::NotInSubgroup));
+
+        let q = (0u64..)
+            .filter_map(|i| G2Affine::get_point_from_x_unchecked(Fq2::new(Fq::from(i), Fq::from(1u64)), false))
+            .find(|q| !q.is_in_correct_subgroup_assuming_on_curve())
+            .unwrap();
+        assert_eq!(signature_from_bytes::<MinPk>(&encode_point(&q)), Err(DecodeError::NotInSubgroup));
+        assert_eq!(public_key_from_bytes::<MinSig>(&encode_point(&q)), Err(DecodeError::NotInSubgroup));
+    }
+
+    #[test]
+    fn rejects_identity_public_key() {
+        let infinity = public_key_to_bytes::<MinSig>(&G2::zero());
+        assert_eq!(infinity[0], 0xc0);
+        assert_eq!(public_key_from_bytes::<MinSig>(&infinity), Err(DecodeError::IdentityPublicKey));
+        assert_eq!(public_key_from_bytes::<MinPk>(&[&[0xc0][..], &[0u8; 47]].concat()), Err(DecodeError::IdentityPublicKey));
+        // The identity still decodes as a signature; it simply never verifies.
+        assert!(signature_from_bytes::<MinPk>(&infinity).unwrap().is_zero());
+    }
+
+    #[test]
+    fn rejects_out_of_range_secret_keys() {
+        assert_eq!(secret_key_from_bytes(&[0u8; 32]), Err(DecodeError::InvalidSecretKey));
+        let order = ScalarField::MODULUS.to_bytes_be();
+        assert_eq!(secret_key_from_bytes(&order), Err(DecodeError::InvalidSecretKey));
+        assert_eq!(secret_key_from_bytes(&[0xff; 32]), Err(DecodeError::InvalidSecretKey));
+    }
+}
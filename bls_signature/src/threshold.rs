@@ -0,0 +1,227 @@
+// t-of-n threshold signatures with a Shamir-shared secret key.
+//
+// The dealer picks a random polynomial of degree t - 1 with f(0) = sk and hands
+// share holder i the value f(i), i = 1..n. Any t of them can reconstruct sk by
+// Lagrange interpolation, t - 1 learn nothing about it. Signing is linear in the
+// key, so the reconstruction happens in the exponent instead:
+//
+//     sig = sum_i lambda_i * H(m)^f(i) = H(m)^f(0)        lambda_i = prod_{j != i} x_j / (x_j - x_i)
+//
+// and the secret key itself is never assembled. The combined signature is an
+// ordinary signature under the original public key, identical whichever t
+// shares took part.
+//
+// Partial signatures are plain signatures under the holder's public key share
+// g^f(i), so a bad one can be spotted with `verify_partial` before combining.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ec::PrimeGroup;
+use ark_ff::Field;
+use ark_std::{rand::Rng, UniformRand, Zero};
+
+use crate::{keygen, sign, verify, Variant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdError {
+    // The threshold must satisfy 1 <= t <= n.
+    InvalidThreshold,
+    NotEnoughShares,
+    // Share indices start at 1; index 0 would be the secret itself.
+    ZeroIndex,
+    DuplicateIndex,
+}
+
+impl fmt::Display for ThresholdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThresholdError::InvalidThreshold => write!(f, "threshold must be between 1 and the number of shares"),
+            ThresholdError::NotEnoughShares => write!(f, "fewer partial signatures than the threshold"),
+            ThresholdError::ZeroIndex => write!(f, "share index 0 is reserved for the secret"),
+            ThresholdError::DuplicateIndex => write!(f, "the same share index appears twice"),
+        }
+    }
+}
+
+impl std::error::Error for ThresholdError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecretKeyShare {
+    pub index: u64,
+    pub secret: ScalarField,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialSignature<V: Variant> {
+    pub index: u64,
+    pub signature: V::Signature,
+}
+
+// Evaluates the polynomial with the given coefficients (constant term first) at x.
+pub(crate) fn evaluate(coefficients: &[ScalarField], x: ScalarField) -> ScalarField {
+    coefficients.iter().rev().fold(ScalarField::zero(), |acc, c| acc * x + c)
+}
+
+// Splits `secret` into n shares, any `threshold` of which recover it.
+pub fn split_secret<R: Rng>(
+    secret: &ScalarField,
+    threshold: usize,
+    n: usize,
+    rng: &mut R,
+) -> Result<Vec<SecretKeyShare>, ThresholdError> {
+    if threshold == 0 || threshold > n {
+        return Err(ThresholdError::InvalidThreshold);
+    }
+    let mut coefficients = vec![*secret];
+    coefficients.extend((1..threshold).map(|_| ScalarField::rand(rng)));
+
+    Ok((1..=n as u64)
+        .map(|index| SecretKeyShare { index, secret: evaluate(&coefficients, ScalarField::from(index)) })
+        .collect())
+}
+
+// A fresh key pair whose secret key only exists as shares.
+pub fn threshold_keygen<V: Variant, R: Rng>(
+    threshold: usize,
+    n: usize,
+    rng: &mut R,
+) -> Result<(V::PublicKey, Vec<SecretKeyShare>), ThresholdError> {
+    let (secret, public_key) = keygen::<V, R>(rng);
+    Ok((public_key, split_secret(&secret, threshold, n, rng)?))
+}
+
+// The public key matching a share, g^f(i), against which partial signatures verify.
+pub fn public_key_share<V: Variant>(share: &SecretKeyShare) -> V::PublicKey {
+    V::PublicKey::generator() * share.secret
+}
+
+pub fn partial_sign<V: Variant>(share: &SecretKeyShare, message: &[u8]) -> PartialSignature<V> {
+    PartialSignature { index: share.index, signature: sign::<V>(&share.secret, message) }
+}
+
+pub fn verify_partial<V: Variant>(public_key_share: &V::PublicKey, message: &[u8], partial: &PartialSignature<V>) -> bool {
+    verify::<V>(public_key_share, message, &partial.signature)
+}
+
+// Lagrange coefficients for evaluating at 0 the polynomial through the given x-coordinates.
+pub fn lagrange_coefficients(indices: &[u64]) -> Result<Vec<ScalarField>, ThresholdError> {
+    if indices.contains(&0) {
+        return Err(ThresholdError::ZeroIndex);
+    }
+    let mut seen = HashSet::new();
+    if !indices.iter().all(|i| seen.insert(*i)) {
+        return Err(ThresholdError::DuplicateIndex);
+    }
+
+    let xs: Vec<ScalarField> = indices.iter().map(|&i| ScalarField::from(i)).collect();
+    Ok(xs
+        .iter()
+        .map(|xi| {
+            let (num, den) = xs
+                .iter()
+                .filter(|xj| *xj != xi)
+                .fold((ScalarField::ONE, ScalarField::ONE), |(num, den), xj| (num * xj, den * (*xj - xi)));
+            num * den.inverse().expect("indices are distinct")
+        })
+        .collect())
+}
+
+// Combines the first `threshold` partial signatures into a signature under the
+// original public key. Partial signatures are not checked here; an invalid one
+// makes the result invalid.
+pub fn combine<V: Variant>(partials: &[PartialSignature<V>], threshold: usize) -> Result<V::Signature, ThresholdError> {
+    if threshold == 0 {
+        return Err(ThresholdError::InvalidThreshold);
+    }
+    if partials.len() < threshold {
+        return Err(ThresholdError::NotEnoughShares);
+    }
+    let partials = &partials[..threshold];
+    let indices: Vec<u64> = partials.iter().map(|p| p.index).collect();
+    let lambdas = lagrange_coefficients(&indices)?;
+
+    Ok(partials.iter().zip(lambdas).map(|(p, lambda)| p.signature * lambda).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MinPk, MinSig};
+    use ark_std::test_rng;
+
+    #[test]
+    fn any_t_shares_reconstruct_the_secret() {
+        let mut rng = test_rng();
+        let secret = ScalarField::rand(&mut rng);
+        let shares = split_secret(&secret, 3, 5, &mut rng).unwrap();
+
+        for subset in [[0, 1, 2], [0, 2, 4], [4, 3, 1]] {
+            let chosen: Vec<_> = subset.iter().map(|&i| shares[i]).collect();
+            let indices: Vec<u64> = chosen.iter().map(|s| s.index).collect();
+            let lambdas = lagrange_coefficients(&indices).unwrap();
+            let recovered: ScalarField = chosen.iter().zip(lambdas).map(|(s, l)| s.secret * l).sum();
+            assert_eq!(recovered, secret);
+        }
+    }
+
+    #[test]
+    fn threshold_signature_verifies_under_the_group_key() {
+        let mut rng = test_rng();
+        let (public_key, shares) = threshold_keygen::<MinSig, _>(3, 5, &mut rng).unwrap();
+        let message = b"block 1000";
+        let partials: Vec<_> = shares.iter().map(|s| partial_sign::<MinSig>(s, message)).collect();
+
+        let from_first = combine(&partials[..3], 3).unwrap();
+        let from_last = combine(&[partials[4], partials[2], partials[3]], 3).unwrap();
+        assert!(verify::<MinSig>(&public_key, message, &from_first));
+        // BLS signatures are unique, so every quorum produces the same one.
+        assert_eq!(from_first, from_last);
+    }
+
+    #[test]
+    fn min_pk_threshold_signature() {
+        let mut rng = test_rng();
+        let (public_key, shares) = threshold_keygen::<MinPk, _>(2, 3, &mut rng).unwrap();
+        let message = b"attestation";
+        let partials: Vec<_> = shares.iter().map(|s| partial_sign::<MinPk>(s, message)).collect();
+        assert!(verify::<MinPk>(&public_key, message, &combine(&partials[1..], 2).unwrap()));
+    }
+
+    #[test]
+    fn fewer_than_t_shares_do_not_sign() {
+        let mut rng = test_rng();
+        let (public_key, shares) = threshold_keygen::<MinSig, _>(3, 5, &mut rng).unwrap();
+        let message = b"block 1000";
+        let partials: Vec<_> = shares.iter().map(|s| partial_sign::<MinSig>(s, message)).collect();
+
+        assert_eq!(combine(&partials[..2], 3), Err(ThresholdError::NotEnoughShares));
+        // Interpolating two shares as if t were 2 gives a signature under the wrong key.
+        assert!(!verify::<MinSig>(&public_key, message, &combine(&partials[..2], 2).unwrap()));
+    }
+
+    #[test]
+    fn bad_partial_signatures_are_detected() {
+        let mut rng = test_rng();
+        let (public_key, shares) = threshold_keygen::<MinSig, _>(2, 3, &mut rng).unwrap();
+        let message = b"block 1000";
+        let mut partials: Vec<_> = shares.iter().map(|s| partial_sign::<MinSig>(s, message)).collect();
+        partials[0].signature = sign::<MinSig>(&shares[0].secret, b"something else");
+
+        assert!(!verify_partial(&public_key_share::<MinSig>(&shares[0]), message, &partials[0]));
+        assert!(verify_partial(&public_key_share::<MinSig>(&shares[1]), message, &partials[1]));
+        assert!(!verify::<MinSig>(&public_key, message, &combine(&partials, 2).unwrap()));
+        assert!(verify::<MinSig>(&public_key, message, &combine(&partials[1..], 2).unwrap()));
+    }
+
+    #[test]
+    fn invalid_parameters_are_rejected() {
+        let mut rng = test_rng();
+        let secret = ScalarField::rand(&mut rng);
+        assert_eq!(split_secret(&secret, 0, 3, &mut rng), Err(ThresholdError::InvalidThreshold));
+        assert_eq!(split_secret(&secret, 4, 3, &mut rng), Err(ThresholdError::InvalidThreshold));
+        assert_eq!(lagrange_coefficients(&[1, 2, 1]), Err(ThresholdError::DuplicateIndex));
+        assert_eq!(lagrange_coefficients(&[0, 2]), Err(ThresholdError::ZeroIndex));
+    }
+}
@@ -0,0 +1,83 @@
+// Which group carries public keys and which carries signatures.
+//
+// The IETF BLS signature draft defines two variants over BLS12-381:
+//
+//     MinSig   signatures in G1 (48 bytes), public keys in G2 (96 bytes)
+//     MinPk    public keys in G1 (48 bytes), signatures in G2 (96 bytes)
+//
+// MinSig suits schemes that store many signatures, MinPk those that store many
+// keys; Ethereum consensus uses MinPk. Messages are always hashed into the
+// signature group, and every check is a product of pairings e(pk_i, sig_i)
+// whose arguments only need to be put in the order the pairing expects.
+//
+// The variant is a type parameter of the scheme functions, e.g.
+// `sign::<MinPk>(&sk, msg)`, so keys of one variant cannot be fed to the other.
+
+use ark_bls12_381::{Bls12_381, Fr as ScalarField, G1Projective as G1, G2Projective as G2};
+use ark_ec::pairing::Pairing;
+use ark_ec::CurveGroup;
+use ark_std::Zero;
+
+use crate::hash_to_curve::{hash_to_g1, hash_to_g2};
+
+pub type PublicKey<V> = <V as Variant>::PublicKey;
+pub type Signature<V> = <V as Variant>::Signature;
+pub type PublicKeyAffine<V> = <PublicKey<V> as CurveGroup>::Affine;
+pub type SignatureAffine<V> = <Signature<V> as CurveGroup>::Affine;
+
+pub trait Variant {
+    type PublicKey: CurveGroup<ScalarField = ScalarField>;
+    type Signature: CurveGroup<ScalarField = ScalarField>;
+
+    // Ciphersuite IDs of the basic scheme and of the proof-of-possession scheme,
+    // and the tag proofs of possession are hashed under.
+    const DST: &'static [u8];
+    const POP_SIG_DST: &'static [u8];
+    const POP_DST: &'static [u8];
+
+    fn hash_to_curve(message: &[u8], dst: &[u8]) -> Self::Signature;
+
+    // Whether prod_i e(public_keys[i], signatures[i]) is the identity, computed
+    // as one multi-pairing.
+    fn pairing_product_is_one(public_keys: Vec<PublicKeyAffine<Self>>, signatures: Vec<SignatureAffine<Self>>) -> bool;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MinSig;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MinPk;
+
+impl Variant for MinSig {
+    type PublicKey = G2;
+    type Signature = G1;
+
+    const DST: &'static [u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+    const POP_SIG_DST: &'static [u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_";
+    const POP_DST: &'static [u8] = b"BLS_POP_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_";
+
+    fn hash_to_curve(message: &[u8], dst: &[u8]) -> G1 {
+        hash_to_g1(message, dst)
+    }
+
+    fn pairing_product_is_one(public_keys: Vec<PublicKeyAffine<Self>>, signatures: Vec<SignatureAffine<Self>>) -> bool {
+        Bls12_381::multi_pairing(signatures, public_keys).is_zero()
+    }
+}
+
+impl Variant for MinPk {
+    type PublicKey = G1;
+    type Signature = G2;
+
+    const DST: &'static [u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+    const POP_SIG_DST: &'static [u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+    const POP_DST: &'static [u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+    fn hash_to_curve(message: &[u8], dst: &[u8]) -> G2 {
+        hash_to_g2(message, dst)
+    }
+
+    fn pairing_product_is_one(public_keys: Vec<PublicKeyAffine<Self>>, signatures: Vec<SignatureAffine<Self>>) -> bool {
+        Bls12_381::multi_pairing(public_keys, signatures).is_zero()
+    }
+}
@@ -0,0 +1,103 @@
+// A verifiable random function from BLS signatures.
+//
+//     prove(sk, alpha)     pi = H(alpha)^sk, a basic-scheme signature
+//     verify(pk, alpha, pi) checks the signature and returns beta
+//     output(pi)           beta = SHA-256(compressed pi)
+//
+// BLS signatures are deterministic and unique: for a valid public key and a
+// given message exactly one point verifies, so beta is fixed by (pk, alpha) and
+// the prover cannot grind it. Without sk, beta is unpredictable. Uniqueness
+// rests on both points lying in the prime-order subgroup: a proof shifted by a
+// point of small order can pass the pairing check with another output. Decoding
+// (`serialization`) rejects such points, and `verify` checks again, since its
+// arguments need not come from bytes.
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ec::CurveGroup;
+use ark_serialize::Valid;
+use sha2::{Digest, Sha256};
+
+use crate::serialization::signature_to_bytes;
+use crate::{sign, verify as verify_signature, Variant};
+
+pub type Output = [u8; 32];
+
+pub fn output<V: Variant>(proof: &V::Signature) -> Output {
+    Sha256::digest(signature_to_bytes::<V>(proof)).into()
+}
+
+// The VRF output on `alpha` together with the proof that it is correct.
+pub fn prove<V: Variant>(private_key: &ScalarField, alpha: &[u8]) -> (Output, V::Signature) {
+    let proof = sign::<V>(private_key, alpha);
+    (output::<V>(&proof), proof)
+}
+
+// On the curve, in the prime-order subgroup and not the identity.
+fn is_valid_point<G: CurveGroup>(point: &G) -> bool {
+    !point.is_zero() && point.into_affine().check().is_ok()
+}
+
+// The output if `proof` is valid for `alpha` under `public_key`.
+pub fn verify<V: Variant>(public_key: &V::PublicKey, alpha: &[u8], proof: &V::Signature) -> Option<Output> {
+    let valid = is_valid_point(public_key) && is_valid_point(proof) && verify_signature::<V>(public_key, alpha, proof);
+    valid.then(|| output::<V>(proof))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{keygen, MinPk, MinSig};
+    use ark_bls12_381::{Fq, G1Affine, G1Projective as G1, G2Projective as G2};
+    use ark_ec::AffineRepr;
+    use ark_ff::PrimeField;
+    use ark_std::{test_rng, Zero};
+
+    #[test]
+    fn outputs_verify_and_are_deterministic() {
+        let mut rng = test_rng();
+        let (sk, pk) = keygen::<MinSig, _>(&mut rng);
+        let (beta, proof) = prove::<MinSig>(&sk, b"epoch 7");
+        assert_eq!(verify::<MinSig>(&pk, b"epoch 7", &proof), Some(beta));
+        assert_eq!(prove::<MinSig>(&sk, b"epoch 7").0, beta);
+        assert_ne!(prove::<MinSig>(&sk, b"epoch 8").0, beta);
+
+        let (sk, pk) = keygen::<MinPk, _>(&mut rng);
+        let (beta, proof) = prove::<MinPk>(&sk, b"epoch 7");
+        assert_eq!(verify::<MinPk>(&pk, b"epoch 7", &proof), Some(beta));
+    }
+
+    #[test]
+    fn wrong_input_key_or_proof_is_rejected() {
+        let mut rng = test_rng();
+        let (sk, pk) = keygen::<MinSig, _>(&mut rng);
+        let (_, other_pk) = keygen::<MinSig, _>(&mut rng);
+        let (_, proof) = prove::<MinSig>(&sk, b"alpha");
+
+        assert_eq!(verify::<MinSig>(&pk, b"beta", &proof), None);
+        assert_eq!(verify::<MinSig>(&other_pk, b"alpha", &proof), None);
+        // Any other point, however chosen, does not verify: there is one output per input.
+        assert_eq!(verify::<MinSig>(&pk, b"alpha", &(proof + proof)), None);
+    }
+
+    #[test]
+    fn points_outside_the_subgroup_or_at_infinity_are_rejected() {
+        let mut rng = test_rng();
+        let (sk, pk) = keygen::<MinSig, _>(&mut rng);
+        let (_, proof) = prove::<MinSig>(&sk, b"alpha");
+
+        // A point of order dividing the cofactor: r P for any P off the subgroup.
+        let p = (0u64..)
+            .filter_map(|i| G1Affine::get_point_from_x_unchecked(Fq::from(i), false))
+            .find(|p| !p.is_in_correct_subgroup_assuming_on_curve())
+            .unwrap();
+        let torsion = p.mul_bigint(ScalarField::MODULUS);
+        assert!(!torsion.is_zero());
+        let shifted = proof + torsion;
+        // The pairing check alone does not see the shift.
+        assert!(verify_signature::<MinSig>(&pk, b"alpha", &shifted));
+        assert_ne!(output::<MinSig>(&shifted), output::<MinSig>(&proof));
+        assert_eq!(verify::<MinSig>(&pk, b"alpha", &shifted), None);
+
+        assert_eq!(verify::<MinSig>(&G2::zero(), b"alpha", &G1::zero()), None);
+    }
+}
@@ -0,0 +1,249 @@
+// Point representations: affine, Jacobian and homogeneous projective coordinates.
+//
+// All three implement `CurvePoint`, so the scalar multiplication routines can run
+// on any of them. Affine additions need a field inversion each; the other two
+// defer it to a single inversion in `to_affine`.
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+use super::{Curve, Point};
+
+// Group operations shared by every coordinate system.
+pub trait CurvePoint: Clone {
+    fn identity() -> Self;
+    fn from_affine(p: &Point) -> Self;
+    fn to_affine(&self, curve: &Curve) -> Point;
+    fn is_identity(&self) -> bool;
+    fn add(&self, other: &Self, curve: &Curve) -> Self;
+    fn double(&self, curve: &Curve) -> Self;
+    fn neg(&self, curve: &Curve) -> Self;
+}
+
+impl CurvePoint for Point {
+    fn identity() -> Self {
+        Point::Infinity
+    }
+
+    fn from_affine(p: &Point) -> Self {
+        p.clone()
+    }
+
+    fn to_affine(&self, _curve: &Curve) -> Point {
+        self.clone()
+    }
+
+    fn is_identity(&self) -> bool {
+        matches!(self, Point::Infinity)
+    }
+
+    // lambda = (y2 - y1) / (x2 - x1), x3 = lambda^2 - x1 - x2, y3 = lambda (x1 - x3) - y1
+    fn add(&self, other: &Self, curve: &Curve) -> Self {
+        let f = &curve.field;
+        let (x1, y1, x2, y2) = match (self, other) {
+            (Point::Infinity, q) => return q.clone(),
+            (p, Point::Infinity) => return p.clone(),
+            (Point::Affine { x: x1, y: y1 }, Point::Affine { x: x2, y: y2 }) => (x1, y1, x2, y2),
+        };
+        if x1 == x2 {
+            return if f.add(y1, y2).is_zero() { Point::Infinity } else { self.double(curve) };
+        }
+        let lambda = f.mul(&f.sub(y2, y1), &f.inv(&f.sub(x2, x1)).expect("x1 != x2"));
+        let x3 = f.sub(&f.sub(&f.square(&lambda), x1), x2);
+        let y3 = f.sub(&f.mul(&lambda, &f.sub(x1, &x3)), y1);
+        Point::Affine { x: x3, y: y3 }
+    }
+
+    // lambda = (3 x^2 + a) / (2 y), x3 = lambda^2 - 2 x, y3 = lambda (x - x3) - y
+    fn double(&self, curve: &Curve) -> Self {
+        let f = &curve.field;
+        let (x, y) = match self {
+            Point::Affine { x, y } if !y.is_zero() => (x, y),
+            _ => return Point::Infinity,
+        };
+        let num = f.add(&f.mul_small(&f.square(x), 3), &curve.a);
+        let lambda = f.mul(&num, &f.inv(&f.mul_small(y, 2)).expect("y != 0"));
+        let x3 = f.sub(&f.square(&lambda), &f.mul_small(x, 2));
+        let y3 = f.sub(&f.mul(&lambda, &f.sub(x, &x3)), y);
+        Point::Affine { x: x3, y: y3 }
+    }
+
+    fn neg(&self, curve: &Curve) -> Self {
+        match self {
+            Point::Infinity => Point::Infinity,
+            Point::Affine { x, y } => Point::Affine { x: x.clone(), y: curve.field.neg(y) },
+        }
+    }
+}
+
+// Jacobian coordinates: (X, Y, Z) stands for (X / Z^2, Y / Z^3), Z = 0 is the identity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Jacobian {
+    pub x: BigUint,
+    pub y: BigUint,
+    pub z: BigUint,
+}
+
+impl CurvePoint for Jacobian {
+    fn identity() -> Self {
+        Jacobian { x: BigUint::one(), y: BigUint::one(), z: BigUint::zero() }
+    }
+
+    fn from_affine(p: &Point) -> Self {
+        match p {
+            Point::Infinity => Self::identity(),
+            Point::Affine { x, y } => Jacobian { x: x.clone(), y: y.clone(), z: BigUint::one() },
+        }
+    }
+
+    fn to_affine(&self, curve: &Curve) -> Point {
+        let f = &curve.field;
+        let Some(z_inv) = f.inv(&self.z) else {
+            return Point::Infinity;
+        };
+        let z_inv2 = f.square(&z_inv);
+        let z_inv3 = f.mul(&z_inv2, &z_inv);
+        Point::Affine { x: f.mul(&self.x, &z_inv2), y: f.mul(&self.y, &z_inv3) }
+    }
+
+    fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    // "add-1998-cmo-2" from the Explicit-Formulas Database.
+    fn add(&self, other: &Self, curve: &Curve) -> Self {
+        if self.is_identity() {
+            return other.clone();
+        }
+        if other.is_identity() {
+            return self.clone();
+        }
+        let f = &curve.field;
+        let z1z1 = f.square(&self.z);
+        let z2z2 = f.square(&other.z);
+        let u1 = f.mul(&self.x, &z2z2);
+        let u2 = f.mul(&other.x, &z1z1);
+        let s1 = f.mul(&f.mul(&self.y, &other.z), &z2z2);
+        let s2 = f.mul(&f.mul(&other.y, &self.z), &z1z1);
+        if u1 == u2 {
+            return if s1 == s2 { self.double(curve) } else { Self::identity() };
+        }
+        let h = f.sub(&u2, &u1);
+        let r = f.sub(&s2, &s1);
+        let hh = f.square(&h);
+        let hhh = f.mul(&h, &hh);
+        let v = f.mul(&u1, &hh);
+        let x3 = f.sub(&f.sub(&f.square(&r), &hhh), &f.mul_small(&v, 2));
+        let y3 = f.sub(&f.mul(&r, &f.sub(&v, &x3)), &f.mul(&s1, &hhh));
+        let z3 = f.mul(&f.mul(&self.z, &other.z), &h);
+        Jacobian { x: x3, y: y3, z: z3 }
+    }
+
+    // "dbl-1998-cmo-2" for arbitrary a.
+    fn double(&self, curve: &Curve) -> Self {
+        if self.is_identity() || self.y.is_zero() {
+            return Self::identity();
+        }
+        let f = &curve.field;
+        let xx = f.square(&self.x);
+        let yy = f.square(&self.y);
+        let yyyy = f.square(&yy);
+        let zz = f.square(&self.z);
+        let s = f.mul_small(&f.mul(&self.x, &yy), 4);
+        let m = f.add(&f.mul_small(&xx, 3), &f.mul(&curve.a, &f.square(&zz)));
+        let x3 = f.sub(&f.square(&m), &f.mul_small(&s, 2));
+        let y3 = f.sub(&f.mul(&m, &f.sub(&s, &x3)), &f.mul_small(&yyyy, 8));
+        let z3 = f.mul_small(&f.mul(&self.y, &self.z), 2);
+        Jacobian { x: x3, y: y3, z: z3 }
+    }
+
+    fn neg(&self, curve: &Curve) -> Self {
+        Jacobian { x: self.x.clone(), y: curve.field.neg(&self.y), z: self.z.clone() }
+    }
+}
+
+// Homogeneous projective coordinates: (X, Y, Z) stands for (X / Z, Y / Z), Z = 0 is the identity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Projective {
+    pub x: BigUint,
+    pub y: BigUint,
+    pub z: BigUint,
+}
+
+impl CurvePoint for Projective {
+    fn identity() -> Self {
+        Projective { x: BigUint::zero(), y: BigUint::one(), z: BigUint::zero() }
+    }
+
+    fn from_affine(p: &Point) -> Self {
+        match p {
+            Point::Infinity => Self::identity(),
+            Point::Affine { x, y } => Projective { x: x.clone(), y: y.clone(), z: BigUint::one() },
+        }
+    }
+
+    fn to_affine(&self, curve: &Curve) -> Point {
+        let f = &curve.field;
+        let Some(z_inv) = f.inv(&self.z) else {
+            return Point::Infinity;
+        };
+        Point::Affine { x: f.mul(&self.x, &z_inv), y: f.mul(&self.y, &z_inv) }
+    }
+
+    fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    // "add-1998-cmo-2" from the Explicit-Formulas Database.
+    fn add(&self, other: &Self, curve: &Curve) -> Self {
+        if self.is_identity() {
+            return other.clone();
+        }
+        if other.is_identity() {
+            return self.clone();
+        }
+        let f = &curve.field;
+        let y1z2 = f.mul(&self.y, &other.z);
+        let x1z2 = f.mul(&self.x, &other.z);
+        let z1z2 = f.mul(&self.z, &other.z);
+        let u = f.sub(&f.mul(&other.y, &self.z), &y1z2);
+        let v = f.sub(&f.mul(&other.x, &self.z), &x1z2);
+        if v.is_zero() {
+            return if u.is_zero() { self.double(curve) } else { Self::identity() };
+        }
+        let uu = f.square(&u);
+        let vv = f.square(&v);
+        let vvv = f.mul(&v, &vv);
+        let r = f.mul(&vv, &x1z2);
+        let a = f.sub(&f.sub(&f.mul(&uu, &z1z2), &vvv), &f.mul_small(&r, 2));
+        let x3 = f.mul(&v, &a);
+        let y3 = f.sub(&f.mul(&u, &f.sub(&r, &a)), &f.mul(&vvv, &y1z2));
+        let z3 = f.mul(&vvv, &z1z2);
+        Projective { x: x3, y: y3, z: z3 }
+    }
+
+    // "dbl-2007-bl" for arbitrary a.
+    fn double(&self, curve: &Curve) -> Self {
+        if self.is_identity() || self.y.is_zero() {
+            return Self::identity();
+        }
+        let f = &curve.field;
+        let xx = f.square(&self.x);
+        let zz = f.square(&self.z);
+        let w = f.add(&f.mul(&curve.a, &zz), &f.mul_small(&xx, 3));
+        let s = f.mul_small(&f.mul(&self.y, &self.z), 2);
+        let ss = f.square(&s);
+        let sss = f.mul(&s, &ss);
+        let r = f.mul(&self.y, &s);
+        let rr = f.square(&r);
+        let b = f.sub(&f.sub(&f.square(&f.add(&self.x, &r)), &xx), &rr);
+        let h = f.sub(&f.square(&w), &f.mul_small(&b, 2));
+        let x3 = f.mul(&h, &s);
+        let y3 = f.sub(&f.mul(&w, &f.sub(&b, &h)), &f.mul_small(&rr, 2));
+        Projective { x: x3, y: y3, z: sss }
+    }
+
+    fn neg(&self, curve: &Curve) -> Self {
+        Projective { x: self.x.clone(), y: curve.field.neg(&self.y), z: self.z.clone() }
+    }
+}
@@ -0,0 +1,128 @@
+// Arithmetic in a prime field F_p with the modulus chosen at runtime.
+//
+// Elements are plain `BigUint`s kept in the range [0, p); the field only carries
+// the modulus and performs every operation modulo it.
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrimeField {
+    p: BigUint,
+}
+
+impl PrimeField {
+    // Creates the field of integers modulo the odd prime `p`. Primality is not checked.
+    pub fn new(p: BigUint) -> Self {
+        assert!(p > BigUint::from(2u32), "modulus must be an odd prime");
+        PrimeField { p }
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.p
+    }
+
+    // Number of bytes needed to encode an element.
+    pub fn byte_len(&self) -> usize {
+        self.p.bits().div_ceil(8) as usize
+    }
+
+    pub fn reduce(&self, a: &BigUint) -> BigUint {
+        a % &self.p
+    }
+
+    pub fn add(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        (a + b) % &self.p
+    }
+
+    pub fn sub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        let (a, b) = (a % &self.p, b % &self.p);
+        if a >= b {
+            a - b
+        } else {
+            &self.p - b + a
+        }
+    }
+
+    pub fn neg(&self, a: &BigUint) -> BigUint {
+        self.sub(&BigUint::zero(), a)
+    }
+
+    pub fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        (a * b) % &self.p
+    }
+
+    pub fn square(&self, a: &BigUint) -> BigUint {
+        self.mul(a, a)
+    }
+
+    // Multiplies an element by a small constant.
+    pub fn mul_small(&self, a: &BigUint, k: u32) -> BigUint {
+        (a * k) % &self.p
+    }
+
+    pub fn pow(&self, a: &BigUint, e: &BigUint) -> BigUint {
+        a.modpow(e, &self.p)
+    }
+
+    // Multiplicative inverse by Fermat's little theorem, `None` for zero.
+    pub fn inv(&self, a: &BigUint) -> Option<BigUint> {
+        if (a % &self.p).is_zero() {
+            return None;
+        }
+        Some(self.pow(a, &(&self.p - 2u32)))
+    }
+
+    pub fn is_square(&self, a: &BigUint) -> bool {
+        let a = self.reduce(a);
+        a.is_zero() || self.pow(&a, &((&self.p - 1u32) >> 1)).is_one()
+    }
+
+    // A square root of `a`, or `None` if `a` is not a quadratic residue.
+    // Uses the (p + 1) / 4 shortcut when p = 3 mod 4 and Tonelli-Shanks otherwise.
+    pub fn sqrt(&self, a: &BigUint) -> Option<BigUint> {
+        let a = self.reduce(a);
+        if a.is_zero() {
+            return Some(a);
+        }
+        if !self.is_square(&a) {
+            return None;
+        }
+
+        let p = &self.p;
+        if (p % 4u32) == BigUint::from(3u32) {
+            return Some(self.pow(&a, &((p + 1u32) >> 2)));
+        }
+
+        // Write p - 1 = q * 2^s with q odd.
+        let p_minus_one = p - 1u32;
+        let s = p_minus_one.trailing_zeros().expect("p - 1 is non-zero");
+        let q = &p_minus_one >> s;
+
+        // Any quadratic non-residue z.
+        let mut z = BigUint::from(2u32);
+        while self.is_square(&z) {
+            z += 1u32;
+        }
+
+        let mut m = s;
+        let mut c = self.pow(&z, &q);
+        let mut t = self.pow(&a, &q);
+        let mut r = self.pow(&a, &((&q + 1u32) >> 1));
+        while !t.is_one() {
+            // Least i with t^(2^i) = 1.
+            let mut i = 0;
+            let mut t2i = t.clone();
+            while !t2i.is_one() {
+                t2i = self.square(&t2i);
+                i += 1;
+            }
+            let b = self.pow(&c, &(BigUint::one() << (m - i - 1)));
+            m = i;
+            c = self.square(&b);
+            t = self.mul(&t, &c);
+            r = self.mul(&r, &b);
+        }
+        Some(r)
+    }
+}
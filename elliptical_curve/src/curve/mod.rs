@@ -0,0 +1,335 @@
+// Elliptic curve arithmetic from scratch, for short Weierstrass curves
+// y^2 = x^3 + a x + b over a prime field.
+//
+// Unlike the rest of the crate, which delegates to libsecp256k1, everything here
+// is written out by hand on top of `BigUint`: field arithmetic, the group law in
+// affine, Jacobian and projective coordinates, three scalar multiplication
+// algorithms and SEC1 point encoding. The tests check every result against
+// `secp256k1::PublicKey`.
+
+pub mod coordinates;
+pub mod field;
+pub mod scalar_mul;
+
+use num_bigint::BigUint;
+use num_traits::{Num, Zero};
+
+pub use coordinates::{CurvePoint, Jacobian, Projective};
+pub use field::PrimeField;
+
+// A point in affine coordinates, or the point at infinity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Point {
+    Infinity,
+    Affine { x: BigUint, y: BigUint },
+}
+
+// Curve parameters: the field, the coefficients a and b, a generator and its order.
+#[derive(Debug, Clone)]
+pub struct Curve {
+    pub field: PrimeField,
+    pub a: BigUint,
+    pub b: BigUint,
+    pub generator: Point,
+    pub order: BigUint,
+}
+
+fn from_hex(s: &str) -> BigUint {
+    BigUint::from_str_radix(s, 16).expect("valid hex constant")
+}
+
+impl Curve {
+    pub fn new(p: BigUint, a: BigUint, b: BigUint, generator: Point, order: BigUint) -> Self {
+        let field = PrimeField::new(p);
+        let curve = Curve { a: field.reduce(&a), b: field.reduce(&b), field, generator, order };
+        assert!(curve.is_on_curve(&curve.generator), "generator is not on the curve");
+        curve
+    }
+
+    // secp256k1 as specified in SEC 2: y^2 = x^3 + 7.
+    pub fn secp256k1() -> Self {
+        Curve::new(
+            from_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F"),
+            BigUint::from(0u32),
+            BigUint::from(7u32),
+            Point::Affine {
+                x: from_hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"),
+                y: from_hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8"),
+            },
+            from_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141"),
+        )
+    }
+
+    // Right-hand side of the curve equation, x^3 + a x + b.
+    fn rhs(&self, x: &BigUint) -> BigUint {
+        let f = &self.field;
+        f.add(&f.add(&f.mul(&f.square(x), x), &f.mul(&self.a, x)), &self.b)
+    }
+
+    pub fn is_on_curve(&self, point: &Point) -> bool {
+        match point {
+            Point::Infinity => true,
+            Point::Affine { x, y } => {
+                x < self.field.modulus() && y < self.field.modulus() && self.field.square(y) == self.rhs(x)
+            }
+        }
+    }
+
+    pub fn add(&self, p: &Point, q: &Point) -> Point {
+        Jacobian::from_affine(p).add(&Jacobian::from_affine(q), self).to_affine(self)
+    }
+
+    pub fn neg(&self, p: &Point) -> Point {
+        p.neg(self)
+    }
+
+    // k * P using width-4 wNAF in Jacobian coordinates.
+    pub fn mul(&self, k: &BigUint, point: &Point) -> Point {
+        scalar_mul::wnaf::<Jacobian>(self, k, point, 4)
+    }
+
+    pub fn mul_generator(&self, k: &BigUint) -> Point {
+        self.mul(k, &self.generator)
+    }
+
+    fn encode_coordinate(&self, v: &BigUint) -> Vec<u8> {
+        let bytes = v.to_bytes_be();
+        let mut out = vec![0u8; self.field.byte_len() - bytes.len()];
+        out.extend_from_slice(&bytes);
+        out
+    }
+
+    // SEC1 compressed encoding: 0x02 or 0x03 (parity of y) followed by x.
+    // The point at infinity is the single byte 0x00.
+    pub fn compress(&self, point: &Point) -> Vec<u8> {
+        match point {
+            Point::Infinity => vec![0x00],
+            Point::Affine { x, y } => {
+                let mut out = vec![if y.bit(0) { 0x03 } else { 0x02 }];
+                out.extend(self.encode_coordinate(x));
+                out
+            }
+        }
+    }
+
+    // SEC1 uncompressed encoding: 0x04 followed by x and y.
+    pub fn serialize_uncompressed(&self, point: &Point) -> Vec<u8> {
+        match point {
+            Point::Infinity => vec![0x00],
+            Point::Affine { x, y } => {
+                let mut out = vec![0x04];
+                out.extend(self.encode_coordinate(x));
+                out.extend(self.encode_coordinate(y));
+                out
+            }
+        }
+    }
+
+    // Parses a compressed or uncompressed SEC1 encoding, recovering y from x for the
+    // compressed form. Returns `None` for malformed input or points off the curve.
+    pub fn decompress(&self, bytes: &[u8]) -> Option<Point> {
+        let len = self.field.byte_len();
+        let (&tag, rest) = bytes.split_first()?;
+        let point = match (tag, rest.len()) {
+            (0x00, 0) => Point::Infinity,
+            (0x02 | 0x03, n) if n == len => {
+                let x = BigUint::from_bytes_be(rest);
+                if &x >= self.field.modulus() {
+                    return None;
+                }
+                let mut y = self.field.sqrt(&self.rhs(&x))?;
+                // y = 0 has no odd root, so 0x03 would be a second encoding of it.
+                if y.is_zero() && tag == 0x03 {
+                    return None;
+                }
+                if y.bit(0) != (tag == 0x03) {
+                    y = self.field.neg(&y);
+                }
+                Point::Affine { x, y }
+            }
+            (0x04, n) if n == 2 * len => Point::Affine {
+                x: BigUint::from_bytes_be(&rest[..len]),
+                y: BigUint::from_bytes_be(&rest[len..]),
+            },
+            _ => return None,
+        };
+        self.is_on_curve(&point).then_some(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scalar_mul::{double_and_add, montgomery_ladder, wnaf, wnaf_digits};
+    use super::*;
+    use num_traits::{One, Zero};
+    use secp256k1::rand::rngs::StdRng;
+    use secp256k1::rand::{RngCore, SeedableRng};
+    use secp256k1::{PublicKey, Secp256k1, SecretKey};
+
+    // Every algorithm in every coordinate system.
+    fn all_multiplications(curve: &Curve, k: &BigUint, p: &Point) -> Vec<Point> {
+        vec![
+            double_and_add::<Point>(curve, k, p),
+            double_and_add::<Jacobian>(curve, k, p),
+            double_and_add::<Projective>(curve, k, p),
+            wnaf::<Point>(curve, k, p, 3),
+            wnaf::<Jacobian>(curve, k, p, 4),
+            wnaf::<Projective>(curve, k, p, 5),
+            montgomery_ladder::<Point>(curve, k, p),
+            montgomery_ladder::<Jacobian>(curve, k, p),
+            montgomery_ladder::<Projective>(curve, k, p),
+        ]
+    }
+
+    fn test_scalars(curve: &Curve) -> Vec<[u8; 32]> {
+        let mut scalars = vec![[0u8; 32]; 4];
+        scalars[0][31] = 1;
+        scalars[1][31] = 2;
+        scalars[2] = [0xcd; 32];
+        let n_minus_one = (&curve.order - 1u32).to_bytes_be();
+        scalars[3].copy_from_slice(&n_minus_one);
+        let mut rng = StdRng::seed_from_u64(27);
+        for _ in 0..3 {
+            let mut k = [0u8; 32];
+            rng.fill_bytes(&mut k);
+            scalars.push(k);
+        }
+        scalars
+    }
+
+    #[test]
+    fn scalar_multiplication_matches_libsecp256k1() {
+        let secp = Secp256k1::new();
+        let curve = Curve::secp256k1();
+        for k in test_scalars(&curve) {
+            let secret_key = SecretKey::from_slice(&k).unwrap();
+            let expected = PublicKey::from_secret_key(&secp, &secret_key);
+            for result in all_multiplications(&curve, &BigUint::from_bytes_be(&k), &curve.generator) {
+                assert_eq!(curve.compress(&result), expected.serialize().to_vec());
+                assert_eq!(curve.serialize_uncompressed(&result), expected.serialize_uncompressed().to_vec());
+            }
+        }
+    }
+
+    #[test]
+    fn arbitrary_base_point_matches_libsecp256k1() {
+        let secp = Secp256k1::new();
+        let curve = Curve::secp256k1();
+        let base_key = PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&[0x11; 32]).unwrap());
+        let base = curve.decompress(&base_key.serialize()).unwrap();
+        let tweak = SecretKey::from_slice(&[0x5a; 32]).unwrap();
+        let expected = base_key.mul_tweak(&secp, &tweak.into()).unwrap();
+        for result in all_multiplications(&curve, &BigUint::from_bytes_be(&[0x5a; 32]), &base) {
+            assert_eq!(curve.compress(&result), expected.serialize().to_vec());
+        }
+    }
+
+    #[test]
+    fn addition_matches_libsecp256k1() {
+        let secp = Secp256k1::new();
+        let curve = Curve::secp256k1();
+        let pk1 = PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&[0x01; 32]).unwrap());
+        let pk2 = PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&[0x02; 32]).unwrap());
+        let expected = pk1.combine(&pk2).unwrap().serialize().to_vec();
+        let p = curve.decompress(&pk1.serialize()).unwrap();
+        let q = curve.decompress(&pk2.serialize()).unwrap();
+
+        assert_eq!(curve.compress(&p.add(&q, &curve)), expected);
+        let jac = Jacobian::from_affine(&p).add(&Jacobian::from_affine(&q), &curve);
+        assert_eq!(curve.compress(&jac.to_affine(&curve)), expected);
+        let proj = Projective::from_affine(&p).add(&Projective::from_affine(&q), &curve);
+        assert_eq!(curve.compress(&proj.to_affine(&curve)), expected);
+
+        // P + P goes through doubling, P + (-P) gives the identity.
+        let doubled = pk1.combine(&pk1).unwrap().serialize().to_vec();
+        assert_eq!(curve.compress(&curve.add(&p, &p)), doubled);
+        assert_eq!(curve.add(&p, &curve.neg(&p)), Point::Infinity);
+    }
+
+    #[test]
+    fn group_order_annihilates_generator() {
+        let curve = Curve::secp256k1();
+        assert_eq!(curve.mul_generator(&curve.order), Point::Infinity);
+        assert_eq!(curve.mul_generator(&BigUint::zero()), Point::Infinity);
+        assert_eq!(curve.mul_generator(&(&curve.order + 1u32)), curve.generator);
+    }
+
+    #[test]
+    fn compression_round_trips_and_rejects_invalid_points() {
+        let secp = Secp256k1::new();
+        let curve = Curve::secp256k1();
+        for k in test_scalars(&curve) {
+            let pk = PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&k).unwrap());
+            let point = curve.decompress(&pk.serialize()).unwrap();
+            assert_eq!(curve.decompress(&pk.serialize_uncompressed()), Some(point.clone()));
+            assert_eq!(curve.compress(&point), pk.serialize().to_vec());
+        }
+        assert_eq!(curve.decompress(&[0x00]), Some(Point::Infinity));
+
+        // x = 5 gives x^3 + 7 = 132, which is not a square mod p.
+        let mut bad = vec![0x02];
+        bad.extend(curve.encode_coordinate(&BigUint::from(5u32)));
+        assert_eq!(curve.decompress(&bad), None);
+        // Truncated and wrongly tagged encodings.
+        assert_eq!(curve.decompress(&bad[..20]), None);
+        bad[0] = 0x05;
+        assert_eq!(curve.decompress(&bad), None);
+    }
+
+    #[test]
+    fn wnaf_digits_reconstruct_scalar() {
+        let k = BigUint::from_bytes_be(&[0xcd; 32]);
+        for w in 2..=6 {
+            let digits = wnaf_digits(&k, w);
+            let mut acc = num_bigint::BigInt::zero();
+            for &d in digits.iter().rev() {
+                acc = acc * 2 + d;
+            }
+            assert_eq!(acc, k.clone().into());
+            assert!(digits.iter().all(|&d| d == 0 || (d % 2 != 0 && d.abs() < (1 << (w - 1)))));
+        }
+    }
+
+    // y^2 = x^3 + 2x + 3 over F_97: a != 0 exercises the general doubling formulas
+    // and p = 1 mod 4 exercises Tonelli-Shanks.
+    #[test]
+    fn small_curve_with_nonzero_a() {
+        let p = 97u32;
+        let on_curve = |x: u32, y: u32| (y * y) % p == (x * x * x + 2 * x + 3) % p;
+        let points: Vec<(u32, u32)> = (0..p).flat_map(|x| (0..p).map(move |y| (x, y))).filter(|&(x, y)| on_curve(x, y)).collect();
+        // Group order: the affine points plus infinity. The order of g divides it.
+        let order = BigUint::from(points.len() as u32 + 1);
+        let (gx, gy) = points[0];
+        let g = Point::Affine { x: BigUint::from(gx), y: BigUint::from(gy) };
+        let curve = Curve::new(BigUint::from(p), BigUint::from(2u32), BigUint::from(3u32), g.clone(), order.clone());
+
+        let mut expected = Point::Infinity;
+        let mut k = BigUint::zero();
+        while k <= &order * 2u32 {
+            for result in all_multiplications(&curve, &k, &g) {
+                assert_eq!(result, expected);
+            }
+            if let Point::Affine { .. } = expected {
+                assert_eq!(curve.decompress(&curve.compress(&expected)), Some(expected.clone()));
+            }
+            expected = expected.add(&g, &curve);
+            k += BigUint::one();
+        }
+
+        // Every point, not just multiples of g, survives compression.
+        for (x, y) in points {
+            let point = Point::Affine { x: BigUint::from(x), y: BigUint::from(y) };
+            assert_eq!(curve.decompress(&curve.compress(&point)), Some(point));
+        }
+
+        // The points of order two, x = 30, 68, 96, have y = 0: only 0x02 encodes them.
+        for x in [30u32, 68, 96] {
+            let point = Point::Affine { x: BigUint::from(x), y: BigUint::zero() };
+            let mut encoding = curve.compress(&point);
+            assert_eq!(encoding[0], 0x02);
+            assert_eq!(curve.decompress(&encoding), Some(point));
+            encoding[0] = 0x03;
+            assert_eq!(curve.decompress(&encoding), None);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+// Scalar multiplication k * P, generic over the coordinate system.
+//
+// None of these run in constant time: `BigUint` arithmetic and the branches on
+// scalar bits leak timing, which is fine for a teaching implementation but not
+// for handling real secret keys.
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use super::coordinates::CurvePoint;
+use super::{Curve, Point};
+
+// Left-to-right binary double-and-add.
+pub fn double_and_add<P: CurvePoint>(curve: &Curve, k: &BigUint, point: &Point) -> Point {
+    let base = P::from_affine(point);
+    let mut acc = P::identity();
+    for i in (0..k.bits()).rev() {
+        acc = acc.double(curve);
+        if k.bit(i) {
+            acc = acc.add(&base, curve);
+        }
+    }
+    acc.to_affine(curve)
+}
+
+// Width-w non-adjacent form of k, least significant digit first. Every non-zero
+// digit is odd and lies in (-2^(w-1), 2^(w-1)), and any w consecutive digits
+// contain at most one non-zero digit.
+pub fn wnaf_digits(k: &BigUint, w: u32) -> Vec<i64> {
+    assert!((2..=16).contains(&w), "window width must be between 2 and 16");
+    let modulus = 1i64 << w;
+    let half = 1i64 << (w - 1);
+    let mut k = k.clone();
+    let mut digits = Vec::new();
+    while !k.is_zero() {
+        let digit = if k.bit(0) {
+            let low = (&k % BigUint::from(modulus as u64)).to_u64_digits().first().copied().unwrap_or(0) as i64;
+            let d = if low >= half { low - modulus } else { low };
+            if d >= 0 {
+                k -= BigUint::from(d as u64);
+            } else {
+                k += BigUint::from((-d) as u64);
+            }
+            d
+        } else {
+            0
+        };
+        digits.push(digit);
+        k >>= 1;
+    }
+    digits
+}
+
+// wNAF multiplication with window width `w`, precomputing P, 3P, ..., (2^(w-1) - 1)P.
+pub fn wnaf<P: CurvePoint>(curve: &Curve, k: &BigUint, point: &Point, w: u32) -> Point {
+    assert!((2..=16).contains(&w), "window width must be between 2 and 16");
+    let base = P::from_affine(point);
+    let twice = base.double(curve);
+    let mut table = vec![base];
+    for i in 1..(1usize << (w - 2)) {
+        let next = table[i - 1].add(&twice, curve);
+        table.push(next);
+    }
+
+    let mut acc = P::identity();
+    for &d in wnaf_digits(k, w).iter().rev() {
+        acc = acc.double(curve);
+        if d > 0 {
+            acc = acc.add(&table[(d as usize) / 2], curve);
+        } else if d < 0 {
+            acc = acc.add(&table[(-d as usize) / 2].neg(curve), curve);
+        }
+    }
+    acc.to_affine(curve)
+}
+
+// Montgomery ladder: one addition and one doubling per bit whatever its value,
+// keeping the invariant R1 - R0 = P.
+pub fn montgomery_ladder<P: CurvePoint>(curve: &Curve, k: &BigUint, point: &Point) -> Point {
+    let mut r0 = P::identity();
+    let mut r1 = P::from_affine(point);
+    for i in (0..k.bits()).rev() {
+        if k.bit(i) {
+            r0 = r0.add(&r1, curve);
+            r1 = r1.double(curve);
+        } else {
+            r1 = r0.add(&r1, curve);
+            r0 = r0.double(curve);
+        }
+    }
+    r0.to_affine(curve)
+}
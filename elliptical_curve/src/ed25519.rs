@@ -0,0 +1,158 @@
+// Ed25519 signatures (RFC 8032), including the prehashed Ed25519ph variant.
+//
+// Wraps ed25519-dalek behind the same shape as the secp256k1 demo in `main.rs`:
+// `SecretKey::from_slice`, `PublicKey::from_secret_key`, then `sign` and `verify`.
+
+use ed25519_dalek::{Sha512, SigningKey, VerifyingKey};
+use secp256k1::rand::{CryptoRng, RngCore};
+use sha2::Digest;
+
+pub use ed25519_dalek::{Signature, SignatureError as Error};
+
+#[derive(Clone)]
+pub struct SecretKey(SigningKey);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicKey(VerifyingKey);
+
+impl SecretKey {
+    // A fresh random key.
+    pub fn new<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut bytes = [0u8; 32];
+        rng.fill_bytes(&mut bytes);
+        SecretKey(SigningKey::from_bytes(&bytes))
+    }
+
+    // Any 32 bytes form a valid Ed25519 secret key (the seed of RFC 8032).
+    pub fn from_slice(data: &[u8]) -> Result<Self, Error> {
+        let bytes: [u8; 32] = data.try_into().map_err(|_| Error::new())?;
+        Ok(SecretKey(SigningKey::from_bytes(&bytes)))
+    }
+
+    pub fn secret_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+}
+
+impl PublicKey {
+    pub fn from_secret_key(secret_key: &SecretKey) -> Self {
+        PublicKey(secret_key.0.verifying_key())
+    }
+
+    // Parses a 32-byte compressed Edwards point.
+    pub fn from_slice(data: &[u8]) -> Result<Self, Error> {
+        let bytes: [u8; 32] = data.try_into().map_err(|_| Error::new())?;
+        Ok(PublicKey(VerifyingKey::from_bytes(&bytes)?))
+    }
+
+    pub fn serialize(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+}
+
+pub fn sign(message: &[u8], secret_key: &SecretKey) -> Signature {
+    use ed25519_dalek::Signer;
+    secret_key.0.sign(message)
+}
+
+// Strict verification: besides the RFC 8032 checks, rejects small-order public keys
+// and non-canonical signature encodings, so a signature cannot be malleated.
+pub fn verify(message: &[u8], signature: &Signature, public_key: &PublicKey) -> Result<(), Error> {
+    public_key.0.verify_strict(message, signature)
+}
+
+// Ed25519ph: signs SHA-512(message), with an optional context string of at most 255 bytes.
+pub fn sign_prehashed(message: &[u8], secret_key: &SecretKey, context: Option<&[u8]>) -> Result<Signature, Error> {
+    secret_key.0.sign_prehashed(Sha512::new().chain_update(message), context)
+}
+
+pub fn verify_prehashed(
+    message: &[u8],
+    context: Option<&[u8]>,
+    signature: &Signature,
+    public_key: &PublicKey,
+) -> Result<(), Error> {
+    public_key.0.verify_prehashed_strict(Sha512::new().chain_update(message), context, signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Vector {
+        secret: &'static str,
+        public: &'static str,
+        message: &'static str,
+        signature: &'static str,
+    }
+
+    // RFC 8032 section 7.1: TEST 1, TEST 2, TEST 3 and TEST SHA(abc).
+    const ED25519_VECTORS: [Vector; 4] = [
+        Vector {
+            secret: "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            public: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            message: "",
+            signature: "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        },
+        Vector {
+            secret: "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            public: "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            message: "72",
+            signature: "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+        },
+        Vector {
+            secret: "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+            public: "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            message: "af82",
+            signature: "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+        },
+        Vector {
+            secret: "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42",
+            public: "ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf",
+            message: "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+            signature: "dc2a4459e7369633a52b1bf277839a00201009a3efbf3ecb69bea2186c26b58909351fc9ac90b3ecfdfbc7c66431e0303dca179c138ac17ad9bef1177331a704",
+        },
+    ];
+
+    // RFC 8032 section 7.3: Ed25519ph TEST abc.
+    const ED25519PH_VECTOR: Vector = Vector {
+        secret: "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42",
+        public: "ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf",
+        message: "616263",
+        signature: "98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae4131f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406",
+    };
+
+    fn decode(v: &Vector) -> (SecretKey, PublicKey, Vec<u8>, Signature) {
+        let secret_key = SecretKey::from_slice(&hex::decode(v.secret).unwrap()).unwrap();
+        let public_key = PublicKey::from_slice(&hex::decode(v.public).unwrap()).unwrap();
+        let message = hex::decode(v.message).unwrap();
+        let signature = Signature::from_slice(&hex::decode(v.signature).unwrap()).unwrap();
+        (secret_key, public_key, message, signature)
+    }
+
+    #[test]
+    fn rfc8032_ed25519_vectors() {
+        for v in &ED25519_VECTORS {
+            let (secret_key, public_key, message, signature) = decode(v);
+            assert_eq!(PublicKey::from_secret_key(&secret_key), public_key);
+            assert_eq!(sign(&message, &secret_key), signature);
+            assert!(verify(&message, &signature, &public_key).is_ok());
+
+            let mut tampered = message.clone();
+            tampered.push(0);
+            assert!(verify(&tampered, &signature, &public_key).is_err());
+        }
+    }
+
+    #[test]
+    fn rfc8032_ed25519ph_vector() {
+        let (secret_key, public_key, message, signature) = decode(&ED25519PH_VECTOR);
+        assert_eq!(PublicKey::from_secret_key(&secret_key), public_key);
+        assert_eq!(sign_prehashed(&message, &secret_key, None).unwrap(), signature);
+        assert!(verify_prehashed(&message, None, &signature, &public_key).is_ok());
+
+        // The ph signature is domain separated from a plain signature and from other contexts.
+        assert!(verify(&message, &signature, &public_key).is_err());
+        assert!(verify_prehashed(&message, Some(b"ctx"), &signature, &public_key).is_err());
+    }
+}
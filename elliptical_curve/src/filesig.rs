@@ -0,0 +1,204 @@
+// Signing and verifying arbitrary files with secp256k1 ECDSA.
+//
+// Files are streamed through SHA-256 in fixed-size chunks, so their size is not
+// limited by memory. Signatures are DER encoded and exchanged as hex or base64
+// text (the reader names the encoding, as the signer did), keys as hex (32-byte secret key, 33-byte compressed public key).
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use secp256k1::ecdsa::Signature;
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey, Signing, Verification};
+use sha2::{Digest, Sha256};
+
+// Size of the chunks read from a file while hashing it.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+// Errors raised while reading inputs or decoding keys and signatures.
+#[derive(Debug)]
+pub enum FileSigError {
+    Io(io::Error),
+    Decode(String),
+    Secp(secp256k1::Error),
+}
+
+impl fmt::Display for FileSigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileSigError::Io(e) => write!(f, "I/O error: {}", e),
+            FileSigError::Decode(msg) => write!(f, "decoding error: {}", msg),
+            FileSigError::Secp(e) => write!(f, "secp256k1 error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FileSigError {}
+
+impl From<io::Error> for FileSigError {
+    fn from(e: io::Error) -> Self {
+        FileSigError::Io(e)
+    }
+}
+
+impl From<secp256k1::Error> for FileSigError {
+    fn from(e: secp256k1::Error) -> Self {
+        FileSigError::Secp(e)
+    }
+}
+
+// Text encoding used when printing a DER signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Hex,
+    Base64,
+}
+
+// Computes a SHA-256 hash of the input data.
+pub fn compute_hash(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    let result = hasher.finalize();
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&result);
+    hash
+}
+
+// Computes a SHA-256 hash of everything read from `reader`, one chunk at a time.
+pub fn hash_reader<R: Read>(mut reader: R) -> io::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize().into())
+}
+
+// Computes a SHA-256 hash of the file at `path`.
+pub fn hash_file<P: AsRef<Path>>(path: P) -> io::Result<[u8; 32]> {
+    hash_reader(File::open(path)?)
+}
+
+// Signs a 32-byte digest with the secret key.
+pub fn sign_digest<C: Signing>(secp: &Secp256k1<C>, secret_key: &SecretKey, digest: [u8; 32]) -> Signature {
+    secp.sign_ecdsa(&Message::from_digest(digest), secret_key)
+}
+
+// Checks a signature over a 32-byte digest against the public key.
+pub fn verify_digest<C: Verification>(
+    secp: &Secp256k1<C>,
+    public_key: &PublicKey,
+    digest: [u8; 32],
+    signature: &Signature,
+) -> bool {
+    secp.verify_ecdsa(&Message::from_digest(digest), signature, public_key).is_ok()
+}
+
+// Encodes a signature as DER, then as hex or base64 text.
+pub fn encode_signature(signature: &Signature, encoding: Encoding) -> String {
+    let der = signature.serialize_der();
+    match encoding {
+        Encoding::Hex => hex::encode(&*der),
+        Encoding::Base64 => BASE64.encode(&*der),
+    }
+}
+
+// Decodes a DER signature given as text in the named encoding. The encoding
+// is never guessed: some strings are valid hex and valid base64 at once.
+pub fn decode_signature(text: &str, encoding: Encoding) -> Result<Signature, FileSigError> {
+    let text = text.trim();
+    let der = match encoding {
+        Encoding::Hex => hex::decode(text).map_err(|e| FileSigError::Decode(format!("signature: {}", e)))?,
+        Encoding::Base64 => BASE64.decode(text).map_err(|e| FileSigError::Decode(format!("signature: {}", e)))?,
+    };
+    Ok(Signature::from_der(&der)?)
+}
+
+// Parses a hex encoded secret key.
+pub fn decode_secret_key(text: &str) -> Result<SecretKey, FileSigError> {
+    let bytes = hex::decode(text.trim()).map_err(|e| FileSigError::Decode(format!("secret key: {}", e)))?;
+    Ok(SecretKey::from_slice(&bytes)?)
+}
+
+// Parses a hex encoded public key (compressed or uncompressed SEC1).
+pub fn decode_public_key(text: &str) -> Result<PublicKey, FileSigError> {
+    let bytes = hex::decode(text.trim()).map_err(|e| FileSigError::Decode(format!("public key: {}", e)))?;
+    Ok(PublicKey::from_slice(&bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hands out at most `max` bytes per read, like a pipe or socket.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        max: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.data.len().min(self.max).min(buf.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    fn key_pair() -> (Secp256k1<secp256k1::All>, SecretKey, PublicKey) {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[0xcd; 32]).unwrap();
+        let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+        (secp, secret_key, public_key)
+    }
+
+    #[test]
+    fn streaming_hash_matches_one_shot_hash() {
+        // Several chunks and a partial one.
+        let data: Vec<u8> = (0..3 * CHUNK_SIZE + 123).map(|i| (i % 251) as u8).collect();
+        assert_eq!(hash_reader(data.as_slice()).unwrap(), compute_hash(&data));
+        assert_eq!(hash_reader(Trickle { data: &data, max: 1000 }).unwrap(), compute_hash(&data));
+        assert_eq!(hash_reader(io::empty()).unwrap(), compute_hash(b""));
+
+        let path = std::env::temp_dir().join(format!("filesig-hash-{}", std::process::id()));
+        std::fs::write(&path, &data).unwrap();
+        assert_eq!(hash_file(&path).unwrap(), compute_hash(&data));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn signatures_round_trip_through_both_encodings() {
+        let (secp, secret_key, public_key) = key_pair();
+        let digest = compute_hash(b"file contents");
+        let signature = sign_digest(&secp, &secret_key, digest);
+        for encoding in [Encoding::Hex, Encoding::Base64] {
+            let text = encode_signature(&signature, encoding);
+            let decoded = decode_signature(&format!("{}\n", text), encoding).unwrap();
+            assert_eq!(decoded, signature);
+            assert!(verify_digest(&secp, &public_key, digest, &decoded));
+            assert!(!verify_digest(&secp, &public_key, compute_hash(b"other contents"), &decoded));
+        }
+
+        let hex = encode_signature(&signature, Encoding::Hex);
+        assert!(decode_signature(&hex, Encoding::Base64).is_err());
+        assert!(decode_signature(&encode_signature(&signature, Encoding::Base64), Encoding::Hex).is_err());
+    }
+
+    #[test]
+    fn keys_decode_from_hex() {
+        let (_, secret_key, public_key) = key_pair();
+        assert_eq!(decode_secret_key(&hex::encode(secret_key.secret_bytes())).unwrap(), secret_key);
+        assert_eq!(decode_public_key(&hex::encode(public_key.serialize())).unwrap(), public_key);
+        assert!(matches!(decode_secret_key("zz"), Err(FileSigError::Decode(_))));
+        assert!(matches!(decode_secret_key(&"00".repeat(32)), Err(FileSigError::Secp(_))));
+    }
+}
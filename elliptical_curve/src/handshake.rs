@@ -0,0 +1,378 @@
+// Authenticated elliptic-curve Diffie-Hellman handshake over secp256k1.
+//
+// Both sides hold a long-term `SecretKey` and know (pin) the peer's long-term
+// `PublicKey`. Each side generates an ephemeral key pair for the exchange and
+// signs the running transcript hash with its long-term key, so a man in the
+// middle cannot substitute its own ephemeral keys.
+//
+//   client -> server   ClientHello  = client ephemeral key || client nonce
+//   server -> client   ServerHello  = server ephemeral key || server nonce || server static key
+//                                     || ECDSA_server(H(transcript))
+//   client -> server   ClientFinish = client static key || ECDSA_client(H(transcript))
+//
+// Session keys come from HKDF-SHA256 with the ephemeral ECDH secret as input key
+// material and the final transcript hash as salt, one ChaCha20-Poly1305 key per
+// direction. The state machine is transport agnostic; `write_frame`/`read_frame`
+// carry its messages over any byte stream such as a `TcpStream`.
+
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hkdf::Hkdf;
+use secp256k1::ecdh::SharedSecret;
+use secp256k1::ecdsa::Signature;
+use secp256k1::rand::{CryptoRng, RngCore};
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+use sha2::{Digest, Sha256};
+
+const PROTOCOL_LABEL: &[u8] = b"elliptical_curve ECDH handshake v1";
+const KEY_LEN: usize = 33;
+const NONCE_LEN: usize = 32;
+const SIG_LEN: usize = 64;
+const CLIENT_HELLO_LEN: usize = KEY_LEN + NONCE_LEN;
+const SERVER_HELLO_LEN: usize = KEY_LEN + NONCE_LEN + KEY_LEN + SIG_LEN;
+const CLIENT_FINISH_LEN: usize = KEY_LEN + SIG_LEN;
+// Frames larger than this are refused rather than allocated.
+const MAX_FRAME_LEN: usize = 1 << 20;
+
+#[derive(Debug)]
+pub enum HandshakeError {
+    Io(io::Error),
+    // A handshake message has the wrong length or an invalid key encoding.
+    Malformed(&'static str),
+    // The peer's long-term key is not the one we expected.
+    UnexpectedPeer,
+    // The peer's signature over the transcript does not verify.
+    BadSignature,
+    // A record failed authentication or the sequence number ran out.
+    Decrypt,
+}
+
+impl fmt::Display for HandshakeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandshakeError::Io(e) => write!(f, "I/O error: {}", e),
+            HandshakeError::Malformed(what) => write!(f, "malformed {}", what),
+            HandshakeError::UnexpectedPeer => write!(f, "peer presented an unexpected static key"),
+            HandshakeError::BadSignature => write!(f, "transcript signature does not verify"),
+            HandshakeError::Decrypt => write!(f, "record failed authentication"),
+        }
+    }
+}
+
+impl std::error::Error for HandshakeError {}
+
+impl From<io::Error> for HandshakeError {
+    fn from(e: io::Error) -> Self {
+        HandshakeError::Io(e)
+    }
+}
+
+fn parse_key(bytes: &[u8], what: &'static str) -> Result<PublicKey, HandshakeError> {
+    PublicKey::from_slice(bytes).map_err(|_| HandshakeError::Malformed(what))
+}
+
+fn random_nonce<R: RngCore + CryptoRng>(rng: &mut R) -> [u8; NONCE_LEN] {
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill_bytes(&mut nonce);
+    nonce
+}
+
+fn sign_transcript(transcript: &Sha256, key: &SecretKey) -> [u8; SIG_LEN] {
+    let digest: [u8; 32] = transcript.clone().finalize().into();
+    Secp256k1::signing_only().sign_ecdsa(&Message::from_digest(digest), key).serialize_compact()
+}
+
+fn verify_transcript(transcript: &Sha256, sig: &[u8], key: &PublicKey) -> Result<(), HandshakeError> {
+    let digest: [u8; 32] = transcript.clone().finalize().into();
+    let sig = Signature::from_compact(sig).map_err(|_| HandshakeError::Malformed("signature"))?;
+    Secp256k1::verification_only()
+        .verify_ecdsa(&Message::from_digest(digest), &sig, key)
+        .map_err(|_| HandshakeError::BadSignature)
+}
+
+// Derives the client-to-server and server-to-client keys.
+fn derive_keys(shared: &SharedSecret, transcript: &Sha256) -> ([u8; 32], [u8; 32]) {
+    let salt = transcript.clone().finalize();
+    let hkdf = Hkdf::<Sha256>::new(Some(&salt), &shared.secret_bytes());
+    let mut c2s = [0u8; 32];
+    let mut s2c = [0u8; 32];
+    hkdf.expand(b"client to server", &mut c2s).expect("32 bytes is a valid HKDF length");
+    hkdf.expand(b"server to client", &mut s2c).expect("32 bytes is a valid HKDF length");
+    (c2s, s2c)
+}
+
+fn new_transcript() -> Sha256 {
+    let mut transcript = Sha256::new();
+    transcript.update(PROTOCOL_LABEL);
+    transcript
+}
+
+// Client side, after sending ClientHello and before receiving ServerHello.
+pub struct ClientHandshake {
+    static_key: SecretKey,
+    server_key: PublicKey,
+    ephemeral: SecretKey,
+    transcript: Sha256,
+}
+
+impl ClientHandshake {
+    // Starts a handshake with the server whose long-term key is `server_key`.
+    // Returns the state and the ClientHello to send.
+    pub fn start<R: RngCore + CryptoRng>(static_key: SecretKey, server_key: PublicKey, rng: &mut R) -> (Self, Vec<u8>) {
+        let ephemeral = SecretKey::new(rng);
+        let mut hello = PublicKey::from_secret_key(&Secp256k1::signing_only(), &ephemeral).serialize().to_vec();
+        hello.extend_from_slice(&random_nonce(rng));
+
+        let mut transcript = new_transcript();
+        transcript.update(&hello);
+        (ClientHandshake { static_key, server_key, ephemeral, transcript }, hello)
+    }
+
+    // Checks the ServerHello. Returns the established session and the ClientFinish to send.
+    pub fn finish(mut self, server_hello: &[u8]) -> Result<(Session, Vec<u8>), HandshakeError> {
+        if server_hello.len() != SERVER_HELLO_LEN {
+            return Err(HandshakeError::Malformed("ServerHello"));
+        }
+        let (signed, sig) = server_hello.split_at(SERVER_HELLO_LEN - SIG_LEN);
+        let server_ephemeral = parse_key(&signed[..KEY_LEN], "server ephemeral key")?;
+        let server_static = parse_key(&signed[KEY_LEN + NONCE_LEN..], "server static key")?;
+        if server_static != self.server_key {
+            return Err(HandshakeError::UnexpectedPeer);
+        }
+        self.transcript.update(signed);
+        verify_transcript(&self.transcript, sig, &server_static)?;
+        self.transcript.update(sig);
+
+        let mut finish = PublicKey::from_secret_key(&Secp256k1::signing_only(), &self.static_key).serialize().to_vec();
+        self.transcript.update(&finish);
+        finish.extend_from_slice(&sign_transcript(&self.transcript, &self.static_key));
+        self.transcript.update(&finish[KEY_LEN..]);
+
+        let shared = SharedSecret::new(&server_ephemeral, &self.ephemeral);
+        let (c2s, s2c) = derive_keys(&shared, &self.transcript);
+        Ok((Session::new(c2s, s2c), finish))
+    }
+}
+
+// Server side, after sending ServerHello and before receiving ClientFinish.
+pub struct ServerHandshake {
+    client_key: PublicKey,
+    shared: SharedSecret,
+    transcript: Sha256,
+}
+
+impl ServerHandshake {
+    // Answers a ClientHello from the client whose long-term key is `client_key`.
+    // Returns the state and the ServerHello to send.
+    pub fn respond<R: RngCore + CryptoRng>(
+        static_key: &SecretKey,
+        client_key: PublicKey,
+        client_hello: &[u8],
+        rng: &mut R,
+    ) -> Result<(Self, Vec<u8>), HandshakeError> {
+        if client_hello.len() != CLIENT_HELLO_LEN {
+            return Err(HandshakeError::Malformed("ClientHello"));
+        }
+        let client_ephemeral = parse_key(&client_hello[..KEY_LEN], "client ephemeral key")?;
+        let mut transcript = new_transcript();
+        transcript.update(client_hello);
+
+        let secp = Secp256k1::signing_only();
+        let ephemeral = SecretKey::new(rng);
+        let mut hello = PublicKey::from_secret_key(&secp, &ephemeral).serialize().to_vec();
+        hello.extend_from_slice(&random_nonce(rng));
+        hello.extend_from_slice(&PublicKey::from_secret_key(&secp, static_key).serialize());
+        transcript.update(&hello);
+        let sig = sign_transcript(&transcript, static_key);
+        transcript.update(sig);
+        hello.extend_from_slice(&sig);
+
+        let shared = SharedSecret::new(&client_ephemeral, &ephemeral);
+        Ok((ServerHandshake { client_key, shared, transcript }, hello))
+    }
+
+    // Checks the ClientFinish and returns the established session.
+    pub fn finish(mut self, client_finish: &[u8]) -> Result<Session, HandshakeError> {
+        if client_finish.len() != CLIENT_FINISH_LEN {
+            return Err(HandshakeError::Malformed("ClientFinish"));
+        }
+        let (key, sig) = client_finish.split_at(KEY_LEN);
+        let client_static = parse_key(key, "client static key")?;
+        if client_static != self.client_key {
+            return Err(HandshakeError::UnexpectedPeer);
+        }
+        self.transcript.update(key);
+        verify_transcript(&self.transcript, sig, &client_static)?;
+        self.transcript.update(sig);
+
+        let (c2s, s2c) = derive_keys(&self.shared, &self.transcript);
+        Ok(Session::new(s2c, c2s))
+    }
+}
+
+// An established channel: one key and sequence number per direction. The sequence
+// number is the AEAD nonce, so reordered, replayed or dropped records fail to open.
+pub struct Session {
+    send: ChaCha20Poly1305,
+    recv: ChaCha20Poly1305,
+    send_seq: u64,
+    recv_seq: u64,
+}
+
+impl Session {
+    fn new(send_key: [u8; 32], recv_key: [u8; 32]) -> Self {
+        Session {
+            send: ChaCha20Poly1305::new(Key::from_slice(&send_key)),
+            recv: ChaCha20Poly1305::new(Key::from_slice(&recv_key)),
+            send_seq: 0,
+            recv_seq: 0,
+        }
+    }
+
+    fn nonce(seq: u64) -> Nonce {
+        let mut nonce = [0u8; 12];
+        nonce[4..].copy_from_slice(&seq.to_be_bytes());
+        *Nonce::from_slice(&nonce)
+    }
+
+    pub fn seal(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, HandshakeError> {
+        let nonce = Self::nonce(self.send_seq);
+        self.send_seq = self.send_seq.checked_add(1).ok_or(HandshakeError::Decrypt)?;
+        self.send.encrypt(&nonce, plaintext).map_err(|_| HandshakeError::Decrypt)
+    }
+
+    pub fn open(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>, HandshakeError> {
+        let nonce = Self::nonce(self.recv_seq);
+        let plaintext = self.recv.decrypt(&nonce, ciphertext).map_err(|_| HandshakeError::Decrypt)?;
+        self.recv_seq = self.recv_seq.checked_add(1).ok_or(HandshakeError::Decrypt)?;
+        Ok(plaintext)
+    }
+}
+
+// Writes a frame: a 4-byte big-endian length followed by the payload.
+pub fn write_frame<W: Write>(writer: &mut W, payload: &[u8]) -> io::Result<()> {
+    if payload.len() > MAX_FRAME_LEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "frame too large"));
+    }
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+pub fn read_frame<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "frame too large"));
+    }
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload)?;
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secp256k1::rand::thread_rng;
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+
+    fn key_pair(byte: u8) -> (SecretKey, PublicKey) {
+        let secret_key = SecretKey::from_slice(&[byte; 32]).unwrap();
+        (secret_key, PublicKey::from_secret_key(&Secp256k1::new(), &secret_key))
+    }
+
+    #[test]
+    fn handshake_and_messages_over_tcp_loopback() {
+        let (server_sk, server_pk) = key_pair(0x01);
+        let (client_sk, client_pk) = key_pair(0x02);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || -> Result<(), HandshakeError> {
+            let (mut stream, _) = listener.accept()?;
+            let client_hello = read_frame(&mut stream)?;
+            let (state, server_hello) = ServerHandshake::respond(&server_sk, client_pk, &client_hello, &mut thread_rng())?;
+            write_frame(&mut stream, &server_hello)?;
+            let mut session = state.finish(&read_frame(&mut stream)?)?;
+
+            // Echo every message back in upper case until the client says bye.
+            loop {
+                let msg = session.open(&read_frame(&mut stream)?)?;
+                let reply = msg.to_ascii_uppercase();
+                write_frame(&mut stream, &session.seal(&reply)?)?;
+                if msg == b"bye" {
+                    return Ok(());
+                }
+            }
+        });
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let (state, client_hello) = ClientHandshake::start(client_sk, server_pk, &mut thread_rng());
+        write_frame(&mut stream, &client_hello).unwrap();
+        let (mut session, finish) = state.finish(&read_frame(&mut stream).unwrap()).unwrap();
+        write_frame(&mut stream, &finish).unwrap();
+
+        for msg in [&b"hello server"[..], b"second message", b"bye"] {
+            let record = session.seal(msg).unwrap();
+            assert!(!record.windows(msg.len()).any(|w| w == msg), "plaintext visible on the wire");
+            write_frame(&mut stream, &record).unwrap();
+            let reply = session.open(&read_frame(&mut stream).unwrap()).unwrap();
+            assert_eq!(reply, msg.to_ascii_uppercase());
+        }
+        server.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn client_rejects_impostor_server() {
+        let (_, server_pk) = key_pair(0x01);
+        let (impostor_sk, _) = key_pair(0x03);
+        let (client_sk, client_pk) = key_pair(0x02);
+
+        let (client, hello) = ClientHandshake::start(client_sk, server_pk, &mut thread_rng());
+        let (_, server_hello) = ServerHandshake::respond(&impostor_sk, client_pk, &hello, &mut thread_rng()).unwrap();
+        assert!(matches!(client.finish(&server_hello), Err(HandshakeError::UnexpectedPeer)));
+    }
+
+    #[test]
+    fn tampered_server_hello_fails_signature_check() {
+        let (server_sk, server_pk) = key_pair(0x01);
+        let (client_sk, client_pk) = key_pair(0x02);
+
+        let (client, hello) = ClientHandshake::start(client_sk, server_pk, &mut thread_rng());
+        let (_, mut server_hello) = ServerHandshake::respond(&server_sk, client_pk, &hello, &mut thread_rng()).unwrap();
+        // Flip a bit of the server nonce, which the signature covers.
+        server_hello[KEY_LEN] ^= 1;
+        assert!(matches!(client.finish(&server_hello), Err(HandshakeError::BadSignature)));
+    }
+
+    #[test]
+    fn server_rejects_unknown_client_and_records_are_authenticated() {
+        let (server_sk, server_pk) = key_pair(0x01);
+        let (client_sk, client_pk) = key_pair(0x02);
+        let (other_sk, _) = key_pair(0x04);
+
+        // A client signing with a key the server does not expect.
+        let (client, hello) = ClientHandshake::start(other_sk, server_pk, &mut thread_rng());
+        let (server, server_hello) = ServerHandshake::respond(&server_sk, client_pk, &hello, &mut thread_rng()).unwrap();
+        let (_, finish) = client.finish(&server_hello).unwrap();
+        assert!(matches!(server.finish(&finish), Err(HandshakeError::UnexpectedPeer)));
+
+        // A genuine session rejects modified and replayed records.
+        let (client, hello) = ClientHandshake::start(client_sk, server_pk, &mut thread_rng());
+        let (server, server_hello) = ServerHandshake::respond(&server_sk, client_pk, &hello, &mut thread_rng()).unwrap();
+        let (mut client_session, finish) = client.finish(&server_hello).unwrap();
+        let mut server_session = server.finish(&finish).unwrap();
+
+        let record = client_session.seal(b"transfer 10").unwrap();
+        let mut tampered = record.clone();
+        tampered[0] ^= 1;
+        assert!(matches!(server_session.open(&tampered), Err(HandshakeError::Decrypt)));
+        assert_eq!(server_session.open(&record).unwrap(), b"transfer 10");
+        assert!(matches!(server_session.open(&record), Err(HandshakeError::Decrypt)));
+    }
+}
@@ -0,0 +1,14 @@
+//! Elliptic curve cryptography over secp256k1 and Curve25519.
+//!
+//! `filesig` holds the hashing, signing and signature-encoding helpers shared by
+//! the demo in `main.rs` and the `sign`/`verify` command-line tool. `curve` is a
+//! self-contained implementation of the curve arithmetic itself. `ed25519` and
+//! `x25519` offer signatures and key agreement on Curve25519 with the same
+//! key/sign/verify shape as the secp256k1 code. `handshake` builds an
+//! authenticated ECDH key exchange with encrypted sessions on top of secp256k1.
+
+pub mod curve;
+pub mod ed25519;
+pub mod filesig;
+pub mod handshake;
+pub mod x25519;
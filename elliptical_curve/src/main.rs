@@ -11,21 +11,137 @@ sha2 = "0.10.6"
 hex = "0.4"
 */
 
+/*
+Command-line usage:
+    keygen [--out <prefix>]                 print a new key pair, or write <prefix>.key and <prefix>.pub
+    sign <file> [--key <key>] [--base64]    sign a file; the secret key defaults to $EC_SECRET_KEY
+    verify <file> <sig> <pubkey> [--base64] check a signature, exiting with status 1 if it is invalid
 
+<key>, <sig> and <pubkey> are either the text itself or a path to a file holding it. Signatures
+are hex unless --base64 is given, to both sign and verify. keygen --out creates <prefix>.key
+readable by its owner only.
+Without arguments the original demo below is run.
+*/
+
+
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::Path;
+use std::process::ExitCode;
+
+use elliptical_curve::filesig::{self, Encoding, FileSigError};
 use secp256k1::{Secp256k1, Message, SecretKey, PublicKey};
-use sha2::{Sha256, Digest};
-
-// Computes a SHA-256 hash of the input data.
-fn compute_hash(data: &[u8]) -> [u8; 32] {
-    let mut hasher = Sha256::new();
-    hasher.update(data);
-    let result = hasher.finalize();
-    let mut hash = [0u8; 32];
-    hash.copy_from_slice(&result);
-    hash
+
+// Exit status for a signature that does not verify.
+const EXIT_INVALID: u8 = 1;
+// Exit status for bad usage, unreadable files or malformed keys.
+const EXIT_ERROR: u8 = 2;
+
+const USAGE: &str = "usage:
+    EllipticalCurve keygen [--out <prefix>]
+    EllipticalCurve sign <file> [--key <key>] [--base64]
+    EllipticalCurve verify <file> <sig> <pubkey> [--base64]";
+
+// Returns the contents of the file at `arg` if it names one, `arg` itself otherwise.
+fn read_arg_or_file(arg: &str) -> Result<String, FileSigError> {
+    let path = Path::new(arg);
+    if path.is_file() {
+        Ok(fs::read_to_string(path)?.trim().to_string())
+    } else {
+        Ok(arg.to_string())
+    }
+}
+
+// Writes a secret key file readable by its owner only, also when it replaces
+// an existing file (`mode` applies only to newly created ones).
+fn write_secret_key(path: &str, contents: &str) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    file.write_all(contents.as_bytes())
+}
+
+fn keygen(args: &[String]) -> Result<u8, Box<dyn Error>> {
+    let secp = Secp256k1::new();
+    let (secret_key, public_key) = secp.generate_keypair(&mut secp256k1::rand::thread_rng());
+    let secret_hex = hex::encode(secret_key.secret_bytes());
+    let public_hex = hex::encode(public_key.serialize());
+
+    match args {
+        [] => {
+            println!("secret key: {}", secret_hex);
+            println!("public key: {}", public_hex);
+        }
+        [flag, prefix] if flag == "--out" => {
+            write_secret_key(&format!("{}.key", prefix), &format!("{}\n", secret_hex))?;
+            fs::write(format!("{}.pub", prefix), format!("{}\n", public_hex))?;
+            println!("wrote {0}.key and {0}.pub", prefix);
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(0)
+}
+
+fn sign(args: &[String]) -> Result<u8, Box<dyn Error>> {
+    let mut file = None;
+    let mut key = None;
+    let mut encoding = Encoding::Hex;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--key" => key = Some(iter.next().ok_or(USAGE)?.clone()),
+            "--base64" => encoding = Encoding::Base64,
+            _ if file.is_none() => file = Some(arg.clone()),
+            _ => return Err(USAGE.into()),
+        }
+    }
+    let file = file.ok_or(USAGE)?;
+    let key = match key {
+        Some(key) => key,
+        None => env::var("EC_SECRET_KEY")
+            .map_err(|_| "no --key given and EC_SECRET_KEY is not set")?,
+    };
+
+    let secp = Secp256k1::new();
+    let secret_key = filesig::decode_secret_key(&read_arg_or_file(&key)?)?;
+    let digest = filesig::hash_file(&file)?;
+    let signature = filesig::sign_digest(&secp, &secret_key, digest);
+    println!("{}", filesig::encode_signature(&signature, encoding));
+    Ok(0)
+}
+
+fn verify(args: &[String]) -> Result<u8, Box<dyn Error>> {
+    let (args, encoding) = match args {
+        [args @ .., flag] if flag == "--base64" => (args, Encoding::Base64),
+        _ => (args, Encoding::Hex),
+    };
+    let [file, sig, pubkey] = args else {
+        return Err(USAGE.into());
+    };
+
+    let secp = Secp256k1::verification_only();
+    let signature = filesig::decode_signature(&read_arg_or_file(sig)?, encoding)?;
+    let public_key = filesig::decode_public_key(&read_arg_or_file(pubkey)?)?;
+    let digest = filesig::hash_file(file)?;
+    if filesig::verify_digest(&secp, &public_key, digest, &signature) {
+        println!("Signature is valid!");
+        Ok(0)
+    } else {
+        eprintln!("Signature is INVALID");
+        Ok(EXIT_INVALID)
+    }
 }
 
-fn main() {
+// The original demo: sign and verify a fixed message with a fixed key.
+fn demo() {
     let secp = Secp256k1::new();
 
     let secret_key = SecretKey::from_slice(&[0xcd; 32]).expect("32 bytes, within curve order");
@@ -33,7 +149,7 @@ fn main() {
     let public_key = PublicKey::from_secret_key(&secp, &secret_key);
     println!("Public key is : {:?}", public_key);
 
-    let hash_msg = compute_hash(b"I am batman");
+    let hash_msg = filesig::compute_hash(b"I am batman");
     println!("The message SHA256 hash is : {:?}", hash_msg.iter().map(|b| format!("{:02x}", b)).collect::<String>());
     let message = Message::from_digest(hash_msg);
     
@@ -43,4 +159,26 @@ fn main() {
 
     assert!(secp.verify_ecdsa(&message, &sig, &public_key).is_ok());
     println!("Signature is valid!");
-}
\ No newline at end of file
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        None => {
+            demo();
+            Ok(0)
+        }
+        Some("keygen") => keygen(&args[1..]),
+        Some("sign") => sign(&args[1..]),
+        Some("verify") => verify(&args[1..]),
+        Some(_) => Err(USAGE.into()),
+    };
+
+    match result {
+        Ok(code) => ExitCode::from(code),
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
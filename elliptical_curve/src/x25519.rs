@@ -0,0 +1,148 @@
+// X25519 key agreement (RFC 7748).
+//
+// Wraps x25519-dalek behind the same key shape as the secp256k1 demo:
+// `SecretKey::from_slice`, `PublicKey::from_secret_key`, then `shared_secret`.
+
+use std::fmt;
+
+use secp256k1::rand::{CryptoRng, RngCore};
+use x25519_dalek::StaticSecret;
+
+pub use x25519_dalek::x25519;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    // Keys are exactly 32 bytes.
+    InvalidLength,
+    // The peer's public key has small order, so the shared secret is all zeros.
+    NonContributory,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidLength => write!(f, "X25519 keys must be 32 bytes"),
+            Error::NonContributory => write!(f, "X25519 shared secret is all zeros"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Clone)]
+pub struct SecretKey(StaticSecret);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicKey(x25519_dalek::PublicKey);
+
+impl SecretKey {
+    pub fn new<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut bytes = [0u8; 32];
+        rng.fill_bytes(&mut bytes);
+        SecretKey(StaticSecret::from(bytes))
+    }
+
+    // Any 32 bytes are a valid scalar; clamping happens inside every X25519 call.
+    pub fn from_slice(data: &[u8]) -> Result<Self, Error> {
+        let bytes: [u8; 32] = data.try_into().map_err(|_| Error::InvalidLength)?;
+        Ok(SecretKey(StaticSecret::from(bytes)))
+    }
+
+    pub fn secret_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+}
+
+impl PublicKey {
+    // X25519(k, 9): the u-coordinate of k times the base point.
+    pub fn from_secret_key(secret_key: &SecretKey) -> Self {
+        PublicKey(x25519_dalek::PublicKey::from(&secret_key.0))
+    }
+
+    pub fn from_slice(data: &[u8]) -> Result<Self, Error> {
+        let bytes: [u8; 32] = data.try_into().map_err(|_| Error::InvalidLength)?;
+        Ok(PublicKey(x25519_dalek::PublicKey::from(bytes)))
+    }
+
+    pub fn serialize(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+}
+
+// X25519(our secret, their public), rejecting the all-zero output as RFC 7748 section 6.1 advises.
+pub fn shared_secret(secret_key: &SecretKey, public_key: &PublicKey) -> Result<[u8; 32], Error> {
+    let shared = secret_key.0.diffie_hellman(&public_key.0);
+    if !shared.was_contributory() {
+        return Err(Error::NonContributory);
+    }
+    Ok(shared.to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(s: &str) -> [u8; 32] {
+        hex::decode(s).unwrap().try_into().unwrap()
+    }
+
+    // RFC 7748 section 5.2.
+    #[test]
+    fn rfc7748_function_vectors() {
+        let vectors = [
+            (
+                "a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4",
+                "e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c",
+                "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552",
+            ),
+            (
+                "4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d",
+                "e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493",
+                "95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957",
+            ),
+        ];
+        for (scalar, u, expected) in vectors {
+            assert_eq!(x25519(bytes(scalar), bytes(u)), bytes(expected));
+        }
+    }
+
+    // RFC 7748 section 5.2, iterated: k, u <- X25519(k, u), k starting at 9.
+    // The one-million-iteration vector is left out to keep the test fast.
+    #[test]
+    fn rfc7748_iterated_vectors() {
+        let mut k = bytes("0900000000000000000000000000000000000000000000000000000000000000");
+        let mut u = k;
+        for i in 1..=1000 {
+            let next = x25519(k, u);
+            u = k;
+            k = next;
+            if i == 1 {
+                assert_eq!(k, bytes("422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079"));
+            }
+        }
+        assert_eq!(k, bytes("684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51"));
+    }
+
+    // RFC 7748 section 6.1.
+    #[test]
+    fn rfc7748_diffie_hellman() {
+        let alice = SecretKey::from_slice(&bytes("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a")).unwrap();
+        let bob = SecretKey::from_slice(&bytes("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb")).unwrap();
+        let alice_public = PublicKey::from_secret_key(&alice);
+        let bob_public = PublicKey::from_secret_key(&bob);
+        assert_eq!(alice_public.serialize(), bytes("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"));
+        assert_eq!(bob_public.serialize(), bytes("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f"));
+
+        let expected = bytes("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+        assert_eq!(shared_secret(&alice, &bob_public).unwrap(), expected);
+        assert_eq!(shared_secret(&bob, &alice_public).unwrap(), expected);
+    }
+
+    #[test]
+    fn rejects_small_order_public_key() {
+        let secret_key = SecretKey::from_slice(&[0x42; 32]).unwrap();
+        let zero = PublicKey::from_slice(&[0u8; 32]).unwrap();
+        assert_eq!(shared_secret(&secret_key, &zero), Err(Error::NonContributory));
+        assert_eq!(PublicKey::from_slice(&[0u8; 31]), Err(Error::InvalidLength));
+    }
+}
@@ -0,0 +1,96 @@
+// The keygen/sign/verify commands end to end, through the built binary.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_EllipticalCurve")).args(args).env_remove("EC_SECRET_KEY").output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap().trim().to_string()
+}
+
+// A fresh directory per test.
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("elliptical-curve-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn sign_and_verify_exit_codes() {
+    let dir = scratch("verify");
+    let prefix = dir.join("alice");
+    let prefix = prefix.to_str().unwrap();
+    assert_eq!(run(&["keygen", "--out", prefix]).status.code(), Some(0));
+    let (key, public) = (format!("{}.key", prefix), format!("{}.pub", prefix));
+    let file = dir.join("message.txt");
+    fs::write(&file, b"pay bob 10").unwrap();
+    let file = file.to_str().unwrap();
+
+    for flags in [&[][..], &["--base64"][..]] {
+        let signed = run(&[&["sign", file, "--key", &key][..], flags].concat());
+        assert_eq!(signed.status.code(), Some(0));
+        let signature = stdout(&signed);
+        assert_eq!(run(&[&["verify", file, &signature, &public][..], flags].concat()).status.code(), Some(0));
+
+        // Another file, or a signature read in the other encoding.
+        let other = dir.join("other.txt");
+        fs::write(&other, b"pay bob 1000").unwrap();
+        let other = other.to_str().unwrap();
+        assert_eq!(run(&[&["verify", other, &signature, &public][..], flags].concat()).status.code(), Some(1));
+        let swapped: &[&str] = if flags.is_empty() { &["--base64"] } else { &[] };
+        assert_eq!(run(&[&["verify", file, &signature, &public][..], swapped].concat()).status.code(), Some(2));
+    }
+
+    // A valid signature under another key.
+    let bob = dir.join("bob");
+    let bob = bob.to_str().unwrap();
+    assert_eq!(run(&["keygen", "--out", bob]).status.code(), Some(0));
+    let signature = stdout(&run(&["sign", file, "--key", &key]));
+    assert_eq!(run(&["verify", file, &signature, &format!("{}.pub", bob)]).status.code(), Some(1));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn usage_errors() {
+    let dir = scratch("usage");
+    let file = dir.join("message.txt");
+    fs::write(&file, b"hello").unwrap();
+    let file = file.to_str().unwrap();
+
+    // --key without a value does not fall back to $EC_SECRET_KEY.
+    let output = Command::new(env!("CARGO_BIN_EXE_EllipticalCurve"))
+        .args(["sign", file, "--key"])
+        .env("EC_SECRET_KEY", "cd".repeat(32))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("usage:"));
+
+    assert_eq!(run(&["sign", file]).status.code(), Some(2));
+    assert_eq!(run(&["verify", file]).status.code(), Some(2));
+    assert_eq!(run(&["keygen", "--out"]).status.code(), Some(2));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn secret_key_file_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = scratch("mode");
+    let prefix = dir.join("alice");
+    let key = dir.join("alice.key");
+    // An existing world-readable file is tightened too.
+    fs::write(&key, b"old").unwrap();
+    fs::set_permissions(&key, fs::Permissions::from_mode(0o644)).unwrap();
+
+    assert_eq!(run(&["keygen", "--out", prefix.to_str().unwrap()]).status.code(), Some(0));
+    assert_eq!(fs::metadata(&key).unwrap().permissions().mode() & 0o777, 0o600);
+    assert_eq!(fs::read_to_string(&key).unwrap().trim().len(), 64);
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,58 @@
+// Verifying n Groth16 proofs of the cubic circuit: one by one, as one batch, and
+// as a SnarkPack aggregate (time to aggregate and time to verify the aggregate).
+//
+//     cargo bench --bench aggregation
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use ark_std::test_rng;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use groth_16::cubic::CubicDemoCircuit;
+use groth_16::groth16::aggregate::{aggregate_proofs, setup_aggregation, verify_aggregate_proof};
+use groth_16::groth16::batch::batch_verify;
+use groth_16::groth16::{create_proof, generate_parameters, verify_proof, Proof};
+
+const SIZES: [usize; 6] = [1, 4, 16, 64, 256, 1024];
+
+fn aggregation(c: &mut Criterion) {
+    let mut rng = test_rng();
+    let pk = generate_parameters::<Bls12_381, _, _>(CubicDemoCircuit::<Fr> { x: None }, &mut rng).unwrap();
+    let max = *SIZES.last().unwrap();
+    let proofs: Vec<(Vec<Fr>, Proof<Bls12_381>)> = (0..max as u64)
+        .map(|x| {
+            let x = Fr::from(x);
+            let proof = create_proof(&pk, CubicDemoCircuit { x: Some(x) }, &mut rng).unwrap();
+            (vec![x * x * x + x + Fr::from(5u64)], proof)
+        })
+        .collect();
+    let key = setup_aggregation::<Bls12_381, _>(max, &mut rng);
+    let vkey = key.verifier_key();
+
+    let mut group = c.benchmark_group("aggregation");
+    group.sample_size(10);
+    for n in SIZES {
+        let items: Vec<(&[Fr], &Proof<Bls12_381>)> = proofs[..n].iter().map(|(x, p)| (x.as_slice(), p)).collect();
+        let inputs: Vec<&[Fr]> = items.iter().map(|(x, _)| *x).collect();
+        let aggregate = aggregate_proofs(&key, &pk.vk, &items).unwrap();
+        println!("n = {}: aggregate proof of {} bytes", n, aggregate.compressed_size());
+
+        group.bench_with_input(BenchmarkId::new("one by one", n), &items, |b, items| {
+            b.iter(|| assert!(items.iter().all(|(x, p)| verify_proof(&pk.vk, x, p).unwrap())))
+        });
+        group.bench_with_input(BenchmarkId::new("batch_verify", n), &items, |b, items| {
+            let mut rng = StdRng::from_entropy();
+            b.iter(|| assert!(batch_verify(&pk.vk, items, &mut rng).unwrap()))
+        });
+        group.bench_with_input(BenchmarkId::new("aggregate", n), &items, |b, items| {
+            b.iter(|| aggregate_proofs(&key, &pk.vk, items).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("verify aggregate", n), &inputs, |b, inputs| {
+            b.iter(|| assert!(verify_aggregate_proof(&vkey, &pk.vk, inputs, &aggregate).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, aggregation);
+criterion_main!(benches);
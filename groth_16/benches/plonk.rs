@@ -0,0 +1,63 @@
+// Groth16 against PLONK on the cubic circuit: setup, proving and verification
+// time, and proof size. PLONK runs twice, on the R1CS translation of
+// `CubicDemoCircuit` and on the one-row circuit with the cube gate; its setup
+// is only the preprocessing, the SRS being shared by every circuit. At these
+// toy sizes t splits into (3n + 6) / n pieces, so the two-row circuit has the
+// larger proof.
+//
+//     cargo bench --bench plonk
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_relations::r1cs::SynthesisMode;
+use ark_serialize::CanonicalSerialize;
+use ark_std::test_rng;
+use criterion::{criterion_group, criterion_main, Criterion};
+use groth_16::cubic::CubicDemoCircuit;
+use groth_16::groth16::{create_proof, generate_parameters, verify_proof};
+use groth_16::plonk::{self, cubic_circuit, kzg, preprocess, Circuit};
+
+fn plonk_against_groth16(c: &mut Criterion) {
+    let mut rng = test_rng();
+    let x = Fr::from(3u64);
+    let inputs = [Fr::from(35u64)];
+    let mut group = c.benchmark_group("cubic");
+    group.sample_size(10);
+
+    let pk = generate_parameters::<Bls12_381, _, _>(CubicDemoCircuit::<Fr> { x: None }, &mut rng).unwrap();
+    let proof = create_proof(&pk, CubicDemoCircuit { x: Some(x) }, &mut rng).unwrap();
+    println!("groth16: proof of {} bytes", proof.compressed_size());
+    group.bench_function("groth16 setup", |b| {
+        let mut rng = test_rng();
+        b.iter(|| generate_parameters::<Bls12_381, _, _>(CubicDemoCircuit::<Fr> { x: None }, &mut rng).unwrap())
+    });
+    group.bench_function("groth16 prove", |b| {
+        let mut rng = test_rng();
+        b.iter(|| create_proof(&pk, CubicDemoCircuit { x: Some(x) }, &mut rng).unwrap())
+    });
+    group.bench_function("groth16 verify", |b| b.iter(|| assert!(verify_proof(&pk.vk, &inputs, &proof).unwrap())));
+
+    let srs = kzg::setup::<Bls12_381, _>(64, &mut rng);
+    let r1cs = |x: Option<Fr>| {
+        let mode = if x.is_some() { SynthesisMode::Prove { construct_matrices: true } } else { SynthesisMode::Setup };
+        Circuit::from_r1cs(CubicDemoCircuit { x }, mode).unwrap()
+    };
+    let circuits: [(&str, Circuit<Fr>, Circuit<Fr>); 2] =
+        [("plonk r1cs", r1cs(None), r1cs(Some(x))), ("plonk cube gate", cubic_circuit(None), cubic_circuit(Some(x)))];
+    for (name, setup, witness) in &circuits {
+        let pk = preprocess(&srs, setup).unwrap();
+        let proof = plonk::create_proof(&pk, witness, &mut rng).unwrap();
+        println!("{}: {} rows, proof of {} bytes", name, pk.vk.n, proof.compressed_size());
+        group.bench_function(format!("{} setup", name), |b| b.iter(|| preprocess(&srs, setup).unwrap()));
+        group.bench_function(format!("{} prove", name), |b| {
+            let mut rng = test_rng();
+            b.iter(|| plonk::create_proof(&pk, witness, &mut rng).unwrap())
+        });
+        group.bench_function(format!("{} verify", name), |b| {
+            b.iter(|| assert!(plonk::verify_proof(&pk.vk, &inputs, &proof).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, plonk_against_groth16);
+criterion_main!(benches);
@@ -0,0 +1,390 @@
+// Circuits compiled by circom: the `.r1cs` constraint format and `.wtns`
+// witness files (iden3 binary formats, r1cs version 1 and wtns version 2).
+//
+// Both files are a 4-byte magic, a version, and a list of sections, each a
+// type, a byte length and the payload; sections may come in any order and
+// unknown ones are skipped. Integers are little-endian and field elements are
+// `field size` little-endian bytes in normal (not Montgomery) form. The prime
+// recorded in the file must be the modulus of the field asked for, BN254's
+// scalar field for circom's default.
+//
+// Wire 0 is the constant one, followed by the public outputs, the public
+// inputs, and everything private. `CircomCircuit` maps the public wires to
+// instance variables in that order, so the public inputs of a proof are the
+// witness values of wires 1..=outputs+inputs.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use ark_ff::{BigInteger, PrimeField};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, LinearCombination, SynthesisError, Variable};
+
+const R1CS_MAGIC: &[u8; 4] = b"r1cs";
+const WTNS_MAGIC: &[u8; 4] = b"wtns";
+
+const R1CS_HEADER: u32 = 1;
+const R1CS_CONSTRAINTS: u32 = 2;
+const WTNS_HEADER: u32 = 1;
+const WTNS_VALUES: u32 = 2;
+
+#[derive(Debug)]
+pub enum CircomError {
+    Io(io::Error),
+    // Not an r1cs or wtns file.
+    InvalidMagic,
+    UnsupportedVersion(u32),
+    MissingSection(u32),
+    // The file is over a different prime than the requested field.
+    FieldMismatch,
+    Truncated,
+    // A field element that is not below the modulus.
+    NonCanonicalElement,
+    WireOutOfRange(usize),
+    // The witness does not hold one value per wire.
+    WitnessLength { expected: usize, found: usize },
+}
+
+impl fmt::Display for CircomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircomError::Io(e) => write!(f, "I/O error: {}", e),
+            CircomError::InvalidMagic => write!(f, "not a circom r1cs or wtns file"),
+            CircomError::UnsupportedVersion(v) => write!(f, "unsupported file version {}", v),
+            CircomError::MissingSection(t) => write!(f, "missing section of type {}", t),
+            CircomError::FieldMismatch => write!(f, "the file is over a different prime field"),
+            CircomError::Truncated => write!(f, "unexpected end of file"),
+            CircomError::NonCanonicalElement => write!(f, "field element not below the modulus"),
+            CircomError::WireOutOfRange(w) => write!(f, "wire {} is out of range", w),
+            CircomError::WitnessLength { expected, found } => {
+                write!(f, "witness has {} values for {} wires", found, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CircomError {}
+
+impl From<io::Error> for CircomError {
+    fn from(e: io::Error) -> Self {
+        CircomError::Io(e)
+    }
+}
+
+// sum coeff * wire.
+pub type Terms<F> = Vec<(usize, F)>;
+
+// A * B - C = 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constraint<F: PrimeField> {
+    pub a: Terms<F>,
+    pub b: Terms<F>,
+    pub c: Terms<F>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct R1cs<F: PrimeField> {
+    pub num_wires: usize,
+    pub num_public_outputs: usize,
+    pub num_public_inputs: usize,
+    pub num_private_inputs: usize,
+    pub constraints: Vec<Constraint<F>>,
+}
+
+impl<F: PrimeField> R1cs<F> {
+    // Public outputs and public inputs, the instance of a proof.
+    pub fn num_public(&self) -> usize {
+        self.num_public_outputs + self.num_public_inputs
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], CircomError> {
+        if self.bytes.len() < n {
+            return Err(CircomError::Truncated);
+        }
+        let (head, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, CircomError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, CircomError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn usize(&mut self) -> Result<usize, CircomError> {
+        usize::try_from(self.u32()?).map_err(|_| CircomError::Truncated)
+    }
+
+    fn element<F: PrimeField>(&mut self, size: usize) -> Result<F, CircomError> {
+        let bytes = self.take(size)?;
+        let value = F::from_le_bytes_mod_order(bytes);
+        // Reducing is lossless only if the bytes were already below the modulus.
+        let mut canonical = value.into_bigint().to_bytes_le();
+        canonical.resize(size, 0);
+        if canonical != bytes {
+            return Err(CircomError::NonCanonicalElement);
+        }
+        Ok(value)
+    }
+
+    // The field size and prime that open both header sections.
+    fn field<F: PrimeField>(&mut self) -> Result<usize, CircomError> {
+        let size = self.usize()?;
+        let mut modulus = F::MODULUS.to_bytes_le();
+        if size < modulus.len() {
+            return Err(CircomError::FieldMismatch);
+        }
+        modulus.resize(size, 0);
+        if self.take(size)? != modulus {
+            return Err(CircomError::FieldMismatch);
+        }
+        Ok(size)
+    }
+}
+
+// Checks magic and version, and splits the file into (type, payload) sections.
+fn sections<'a>(bytes: &'a [u8], magic: &[u8; 4], versions: &[u32]) -> Result<Vec<(u32, &'a [u8])>, CircomError> {
+    let mut reader = Reader { bytes };
+    if reader.take(4).map_err(|_| CircomError::InvalidMagic)? != magic {
+        return Err(CircomError::InvalidMagic);
+    }
+    let version = reader.u32()?;
+    if !versions.contains(&version) {
+        return Err(CircomError::UnsupportedVersion(version));
+    }
+    let count = reader.u32()?;
+    let mut sections = Vec::new();
+    for _ in 0..count {
+        let kind = reader.u32()?;
+        let size = usize::try_from(reader.u64()?).map_err(|_| CircomError::Truncated)?;
+        sections.push((kind, reader.take(size)?));
+    }
+    Ok(sections)
+}
+
+fn section<'a>(sections: &[(u32, &'a [u8])], kind: u32) -> Result<Reader<'a>, CircomError> {
+    sections
+        .iter()
+        .find(|(k, _)| *k == kind)
+        .map(|(_, bytes)| Reader { bytes })
+        .ok_or(CircomError::MissingSection(kind))
+}
+
+pub fn parse_r1cs<F: PrimeField>(bytes: &[u8]) -> Result<R1cs<F>, CircomError> {
+    let sections = sections(bytes, R1CS_MAGIC, &[1])?;
+
+    let mut header = section(&sections, R1CS_HEADER)?;
+    let size = header.field::<F>()?;
+    let num_wires = header.usize()?;
+    let num_public_outputs = header.usize()?;
+    let num_public_inputs = header.usize()?;
+    let num_private_inputs = header.usize()?;
+    let _num_labels = header.u64()?;
+    let num_constraints = header.usize()?;
+    if 1 + num_public_outputs + num_public_inputs + num_private_inputs > num_wires {
+        return Err(CircomError::WireOutOfRange(num_wires));
+    }
+
+    let mut body = section(&sections, R1CS_CONSTRAINTS)?;
+    // The count comes from the file: allocate no more than the section could
+    // hold, at 12 bytes for the three term counts of an empty constraint.
+    let mut constraints = Vec::with_capacity(num_constraints.min(body.bytes.len() / 12));
+    let mut terms = || -> Result<Terms<F>, CircomError> {
+        let n = body.usize()?;
+        (0..n)
+            .map(|_| {
+                let wire = body.usize()?;
+                if wire >= num_wires {
+                    return Err(CircomError::WireOutOfRange(wire));
+                }
+                Ok((wire, body.element(size)?))
+            })
+            .collect()
+    };
+    for _ in 0..num_constraints {
+        constraints.push(Constraint { a: terms()?, b: terms()?, c: terms()? });
+    }
+
+    Ok(R1cs { num_wires, num_public_outputs, num_public_inputs, num_private_inputs, constraints })
+}
+
+pub fn parse_witness<F: PrimeField>(bytes: &[u8]) -> Result<Vec<F>, CircomError> {
+    let sections = sections(bytes, WTNS_MAGIC, &[2])?;
+    let mut header = section(&sections, WTNS_HEADER)?;
+    let size = header.field::<F>()?;
+    let count = header.usize()?;
+    let mut values = section(&sections, WTNS_VALUES)?;
+    (0..count).map(|_| values.element(size)).collect()
+}
+
+pub fn read_r1cs<F: PrimeField>(path: impl AsRef<Path>) -> Result<R1cs<F>, CircomError> {
+    parse_r1cs(&fs::read(path)?)
+}
+
+pub fn read_witness<F: PrimeField>(path: impl AsRef<Path>) -> Result<Vec<F>, CircomError> {
+    parse_witness(&fs::read(path)?)
+}
+
+// A circom circuit as a `ConstraintSynthesizer`, with or without a witness
+// (without one for the setup).
+#[derive(Debug, Clone)]
+pub struct CircomCircuit<F: PrimeField> {
+    pub r1cs: R1cs<F>,
+    pub witness: Option<Vec<F>>,
+}
+
+impl<F: PrimeField> CircomCircuit<F> {
+    pub fn new(r1cs: R1cs<F>, witness: Option<Vec<F>>) -> Result<Self, CircomError> {
+        if let Some(w) = &witness {
+            if w.len() != r1cs.num_wires {
+                return Err(CircomError::WitnessLength { expected: r1cs.num_wires, found: w.len() });
+            }
+        }
+        Ok(CircomCircuit { r1cs, witness })
+    }
+
+    // The public inputs of a proof: outputs, then public inputs.
+    pub fn public_inputs(&self) -> Option<Vec<F>> {
+        self.witness.as_ref().map(|w| w[1..=self.r1cs.num_public()].to_vec())
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for CircomCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let value = |wire: usize| {
+            self.witness.as_ref().map(|w| w[wire]).ok_or(SynthesisError::AssignmentMissing)
+        };
+        let mut wires = vec![Variable::One];
+        for wire in 1..self.r1cs.num_wires {
+            wires.push(if wire <= self.r1cs.num_public() {
+                cs.new_input_variable(|| value(wire))?
+            } else {
+                cs.new_witness_variable(|| value(wire))?
+            });
+        }
+
+        let lc = |terms: &Terms<F>| {
+            LinearCombination(terms.iter().map(|(wire, coeff)| (*coeff, wires[*wire])).collect())
+        };
+        for constraint in &self.r1cs.constraints {
+            cs.enforce_constraint(lc(&constraint.a), lc(&constraint.b), lc(&constraint.c))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groth16::{create_proof, generate_parameters, verify_proof};
+    use ark_bls12_381::Fr as BlsFr;
+    use ark_bn254::{Bn254, Fr};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::test_rng;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/circom");
+
+    fn load(name: &str) -> CircomCircuit<Fr> {
+        let r1cs = read_r1cs(format!("{}/{}.r1cs", FIXTURES, name)).unwrap();
+        let witness = read_witness(format!("{}/{}.wtns", FIXTURES, name)).unwrap();
+        CircomCircuit::new(r1cs, Some(witness)).unwrap()
+    }
+
+    fn is_satisfied(circuit: CircomCircuit<Fr>) -> bool {
+        let cs = ConstraintSystem::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    #[test]
+    fn parses_the_multiplier_fixture() {
+        let circuit = load("mycircuit");
+        let r1cs = &circuit.r1cs;
+        assert_eq!((r1cs.num_wires, r1cs.num_public_outputs, r1cs.num_public_inputs, r1cs.num_private_inputs), (4, 1, 0, 2));
+        assert_eq!(r1cs.constraints.len(), 1);
+        assert_eq!(circuit.witness.as_deref(), Some(&[1u64, 33, 3, 11].map(Fr::from)[..]));
+        assert_eq!(circuit.public_inputs(), Some(vec![Fr::from(33u64)]));
+        assert!(is_satisfied(circuit));
+
+        let circuit = load("circuit2");
+        assert_eq!((circuit.r1cs.num_wires, circuit.r1cs.constraints.len()), (132, 131));
+        assert!(is_satisfied(circuit));
+    }
+
+    #[test]
+    fn proves_and_verifies_circom_circuits() {
+        let mut rng = test_rng();
+        for name in ["mycircuit", "circuit2"] {
+            let circuit = load(name);
+            assert_eq!(circuit.public_inputs(), Some(vec![Fr::from(33u64)]));
+
+            let setup = CircomCircuit { witness: None, ..circuit.clone() };
+            let pk = generate_parameters::<Bn254, _, _>(setup, &mut rng).unwrap();
+            let proof = create_proof(&pk, circuit, &mut rng).unwrap();
+            assert!(verify_proof(&pk.vk, &[Fr::from(33u64)], &proof).unwrap(), "{}", name);
+            assert!(!verify_proof(&pk.vk, &[Fr::from(34u64)], &proof).unwrap(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn wrong_witness_is_unsatisfied() {
+        let mut circuit = load("mycircuit");
+        circuit.witness.as_mut().unwrap()[1] = Fr::from(34u64);
+        assert!(!is_satisfied(circuit));
+
+        // a = 1 passes the bit checks but has no inverse for a - 1.
+        let mut circuit = load("circuit2");
+        let witness = circuit.witness.as_mut().unwrap();
+        witness[1] = Fr::from(11u64);
+        witness[2] = Fr::from(1u64);
+        assert!(!is_satisfied(circuit));
+
+        let r1cs = load("mycircuit").r1cs;
+        assert!(matches!(
+            CircomCircuit::new(r1cs, Some(vec![Fr::from(1u64); 3])),
+            Err(CircomError::WitnessLength { expected: 4, found: 3 })
+        ));
+    }
+
+    #[test]
+    fn malformed_files_are_rejected() {
+        let r1cs = fs::read(format!("{}/mycircuit.r1cs", FIXTURES)).unwrap();
+        let wtns = fs::read(format!("{}/mycircuit.wtns", FIXTURES)).unwrap();
+
+        assert!(matches!(parse_r1cs::<Fr>(&wtns), Err(CircomError::InvalidMagic)));
+        assert!(matches!(parse_witness::<Fr>(&r1cs), Err(CircomError::InvalidMagic)));
+        assert!(matches!(parse_r1cs::<Fr>(&r1cs[..r1cs.len() - 1]), Err(CircomError::Truncated)));
+        // BN254 files do not load over another field.
+        assert!(matches!(parse_r1cs::<BlsFr>(&r1cs), Err(CircomError::FieldMismatch)));
+        assert!(matches!(parse_witness::<BlsFr>(&wtns), Err(CircomError::FieldMismatch)));
+
+        // A constraint count far beyond what the file holds: the header
+        // payload (at 12 + 12 + 120 + 12, after the constraints section) ends
+        // with it.
+        let mut count = r1cs.clone();
+        let at = 12 + 12 + 120 + 12 + 4 + 32 + 24;
+        assert_eq!(count[at..at + 4], 1u32.to_le_bytes());
+        count[at..at + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(parse_r1cs::<Fr>(&count), Err(CircomError::Truncated)));
+
+        let mut version = r1cs.clone();
+        version[4] = 9;
+        assert!(matches!(parse_r1cs::<Fr>(&version), Err(CircomError::UnsupportedVersion(9))));
+
+        // The witness values follow the two section headers and the 44-byte header payload.
+        let first_value = 12 + 12 + 40 + 12;
+        let mut value = wtns.clone();
+        value[first_value + 31] = 0xff;
+        assert!(matches!(parse_witness::<Fr>(&value), Err(CircomError::NonCanonicalElement)));
+    }
+}
@@ -0,0 +1,88 @@
+// Import necessary modules from arkworks
+use ark_ff::Field; // For working with finite fields
+use ark_relations::{
+    lc, // For linear combinations
+    ns, // For naming the constraints, shown by `debug::check`
+    r1cs::{ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError}, // For R1CS-based constraint systems
+};
+
+// Struct representing the cubic equation circuit
+// The circuit will prove knowledge of an 'x' such that x^3 + x + 5 = 35 (or a public value)
+#[derive(Clone, Copy)]
+pub struct CubicDemoCircuit<F: Field> {
+    pub x: Option<F>, // The secret value 'x' (Option because it's not assigned during setup)
+}
+
+// The same circuit with every wire given explicitly instead of computed from 'x'.
+// This makes inconsistent witnesses expressible, e.g. to see what `debug::check` reports.
+#[derive(Clone, Copy, Debug)]
+pub struct CubicWitness<F: Field> {
+    pub x: F,
+    pub x_squared: F,
+    pub x_cubed: F,
+    pub out: F, // The public output
+}
+
+impl<F: Field> CubicWitness<F> {
+    // The only assignment that satisfies the circuit for this 'x'
+    pub fn new(x: F) -> Self {
+        let x_squared = x.square();
+        let x_cubed = x_squared * x;
+        CubicWitness { x, x_squared, x_cubed, out: x_cubed + x + F::from(5u32) }
+    }
+}
+
+// Implement the `ConstraintSynthesizer` trait for the CubicDemoCircuit to define constraints
+impl<F: Field> ConstraintSynthesizer<F> for CubicDemoCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        // All wires follow from 'x'; during setup there are none
+        enforce_cubic(cs, self.x.map(CubicWitness::new))
+    }
+}
+
+impl<F: Field> ConstraintSynthesizer<F> for CubicWitness<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        enforce_cubic(cs, Some(self))
+    }
+}
+
+// The constraints shared by both circuits. Each step runs in its own namespace,
+// so that a failing constraint can be traced back to it.
+fn enforce_cubic<F: Field>(cs: ConstraintSystemRef<F>, w: Option<CubicWitness<F>>) -> Result<(), SynthesisError> {
+    // Allocate witness variable 'x' for the secret value
+    let x = cs.new_witness_variable(|| w.map(|w| w.x).ok_or(SynthesisError::AssignmentMissing))?;
+
+    // Step 1: Create 'tmp_1' to represent x^2 (x * x)
+    let tmp_1 = {
+        let ns = ns!(cs, "x_squared");
+        let cs = ns.cs();
+        let tmp_1 = cs.new_witness_variable(|| w.map(|w| w.x_squared).ok_or(SynthesisError::AssignmentMissing))?;
+        // Enforce the constraint: x * x = tmp_1
+        cs.enforce_constraint(lc!() + x, lc!() + x, lc!() + tmp_1)?;
+        tmp_1
+    };
+
+    // Step 2: Create 'x_cubed' to represent x^3 (tmp_1 * x)
+    let x_cubed = {
+        let ns = ns!(cs, "x_cubed");
+        let cs = ns.cs();
+        let x_cubed = cs.new_witness_variable(|| w.map(|w| w.x_cubed).ok_or(SynthesisError::AssignmentMissing))?;
+        // Enforce the constraint: tmp_1 * x = x_cubed
+        cs.enforce_constraint(lc!() + tmp_1, lc!() + x, lc!() + x_cubed)?;
+        x_cubed
+    };
+
+    // Step 3: Allocate 'out' as the public output variable for x^3 + x + 5
+    let ns = ns!(cs, "out");
+    let cs = ns.cs();
+    let out = cs.new_input_variable(|| w.map(|w| w.out).ok_or(SynthesisError::AssignmentMissing))?;
+
+    // Enforce the final constraint: x^3 + x + 5 = out
+    cs.enforce_constraint(
+        lc!() + x_cubed + x + (F::from(5u32), ConstraintSystem::<F>::one()), // x^3 + x + 5
+        lc!() + ConstraintSystem::<F>::one(), // Identity term for the left-hand side
+        lc!() + out, // Right-hand side is the output variable
+    )?;
+
+    Ok(())
+}
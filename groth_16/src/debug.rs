@@ -0,0 +1,204 @@
+// Dev-mode checking of circuits and their witnesses.
+//
+// Neither Groth16 prover looks at whether the witness satisfies the constraints
+// (ark-groth16 only asserts it in debug builds), so an inconsistent witness
+// still gives a proof, which then just fails to verify with no hint why.
+// `check` runs the circuit in a fresh `ConstraintSystem` under ark-relations'
+// `ConstraintLayer`, which records the `ns!` namespaces (and gadget functions)
+// each constraint was created in, and reports the first unsatisfied constraint
+// with that trace and the values of its A, B and C sides. `statistics` gives
+// the size of the system without a witness.
+
+use std::fmt;
+
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{
+    ConstraintLayer, ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError,
+    SynthesisMode, TracingMode,
+};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::Registry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub constraints: usize,
+    pub witnesses: usize,
+    // Not counting the constant 1.
+    pub public_inputs: usize,
+    // Non-zero entries of the A, B and C matrices.
+    pub a_non_zero: usize,
+    pub b_non_zero: usize,
+    pub c_non_zero: usize,
+}
+
+impl Stats {
+    fn new<F: PrimeField>(matrices: &ConstraintMatrices<F>) -> Self {
+        Stats {
+            constraints: matrices.num_constraints,
+            witnesses: matrices.num_witness_variables,
+            public_inputs: matrices.num_instance_variables - 1,
+            a_non_zero: matrices.a_num_non_zero,
+            b_non_zero: matrices.b_num_non_zero,
+            c_non_zero: matrices.c_num_non_zero,
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "constraints:   {}", self.constraints)?;
+        writeln!(f, "witnesses:     {}", self.witnesses)?;
+        writeln!(f, "public inputs: {}", self.public_inputs)?;
+        write!(f, "non-zero A/B/C entries: {} / {} / {}", self.a_non_zero, self.b_non_zero, self.c_non_zero)
+    }
+}
+
+// A constraint A * B = C that the witness violates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unsatisfied<F: PrimeField> {
+    pub index: usize,
+    // The namespaces it was enforced in, e.g. "/cubic::x_cubed".
+    pub trace: String,
+    pub a: F,
+    pub b: F,
+    pub c: F,
+}
+
+impl<F: PrimeField> fmt::Display for Unsatisfied<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let trace = if self.trace.is_empty() { "(no namespace)" } else { &self.trace };
+        write!(f, "constraint {} at {} is unsatisfied: {} * {} != {}", self.index, trace, self.a, self.b, self.c)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report<F: PrimeField> {
+    pub stats: Stats,
+    pub unsatisfied: Option<Unsatisfied<F>>,
+}
+
+impl<F: PrimeField> Report<F> {
+    pub fn is_satisfied(&self) -> bool {
+        self.unsatisfied.is_none()
+    }
+}
+
+// Runs the circuit with tracing on; all linear combinations are inlined after.
+fn synthesize<F: PrimeField, C: ConstraintSynthesizer<F>>(
+    circuit: C,
+    mode: SynthesisMode,
+) -> Result<ConstraintSystemRef<F>, SynthesisError> {
+    let subscriber = Registry::default().with(ConstraintLayer::new(TracingMode::OnlyConstraints));
+    tracing::subscriber::with_default(subscriber, || {
+        let cs = ConstraintSystem::new_ref();
+        cs.set_mode(mode);
+        circuit.generate_constraints(cs.clone())?;
+        cs.finalize();
+        Ok(cs)
+    })
+}
+
+fn matrices<F: PrimeField>(cs: &ConstraintSystemRef<F>) -> Result<ConstraintMatrices<F>, SynthesisError> {
+    cs.to_matrices().ok_or(SynthesisError::MissingCS)
+}
+
+// The size of the circuit's constraint system; no witness is needed.
+pub fn statistics<F: PrimeField, C: ConstraintSynthesizer<F>>(circuit: C) -> Result<Stats, SynthesisError> {
+    let cs = synthesize(circuit, SynthesisMode::Setup)?;
+    Ok(Stats::new(&matrices(&cs)?))
+}
+
+// Synthesizes the circuit with its witness and evaluates every constraint.
+pub fn check<F: PrimeField, C: ConstraintSynthesizer<F>>(circuit: C) -> Result<Report<F>, SynthesisError> {
+    let cs = synthesize(circuit, SynthesisMode::Prove { construct_matrices: true })?;
+    let matrices = matrices(&cs)?;
+    let assignment: Vec<F> = {
+        let inner = cs.borrow().ok_or(SynthesisError::MissingCS)?;
+        inner.instance_assignment.iter().chain(&inner.witness_assignment).copied().collect()
+    };
+    let evaluate = |row: &[(F, usize)]| row.iter().map(|(coeff, j)| *coeff * assignment[*j]).sum::<F>();
+
+    let unsatisfied = (0..matrices.num_constraints)
+        .map(|i| (i, evaluate(&matrices.a[i]), evaluate(&matrices.b[i]), evaluate(&matrices.c[i])))
+        .find(|(_, a, b, c)| *a * b != *c)
+        .map(|(index, a, b, c)| Unsatisfied {
+            index,
+            trace: cs.constraint_names().and_then(|names| names.get(index).cloned()).unwrap_or_default(),
+            a,
+            b,
+            c,
+        });
+    Ok(Report { stats: Stats::new(&matrices), unsatisfied })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{CubicDemoCircuit, CubicWitness};
+    use crate::gadgets::poseidon::{hash, poseidon_config};
+    use ark_bls12_381::{Bls12_381, Fr};
+
+    #[test]
+    fn cubic_statistics() {
+        let stats = statistics(CubicDemoCircuit::<Fr> { x: None }).unwrap();
+        let expected = Stats { constraints: 3, witnesses: 3, public_inputs: 1, a_non_zero: 5, b_non_zero: 3, c_non_zero: 3 };
+        assert_eq!(stats, expected);
+        let report = check(CubicDemoCircuit { x: Some(Fr::from(3u64)) }).unwrap();
+        assert!(report.is_satisfied());
+        assert_eq!(report.stats, expected);
+    }
+
+    #[test]
+    fn first_unsatisfied_constraint_is_traced() {
+        // x^3 off by one: the x_cubed constraint fails first, the output one holds.
+        let mut witness = CubicWitness::new(Fr::from(3u64));
+        witness.x_cubed += Fr::from(1u64);
+        witness.out += Fr::from(1u64);
+        let failure = check(witness).unwrap().unsatisfied.unwrap();
+        assert_eq!(failure.index, 1);
+        assert!(failure.trace.ends_with("x_cubed"), "{}", failure.trace);
+        assert_eq!((failure.a, failure.b, failure.c), (Fr::from(9u64), Fr::from(3u64), Fr::from(28u64)));
+
+        let mut witness = CubicWitness::new(Fr::from(3u64));
+        witness.out = Fr::from(36u64);
+        let failure = check(witness).unwrap().unsatisfied.unwrap();
+        assert!(failure.trace.ends_with("out"), "{}", failure);
+        assert_eq!(failure.index, 2);
+    }
+
+    #[test]
+    fn inconsistent_witness_still_proves_but_does_not_verify() {
+        use crate::groth16::{create_proof, generate_parameters, verify_proof};
+
+        let mut rng = ark_std::test_rng();
+        let pk = generate_parameters::<Bls12_381, _, _>(CubicDemoCircuit::<Fr> { x: None }, &mut rng).unwrap();
+        let mut witness = CubicWitness::new(Fr::from(3u64));
+        witness.x_squared = Fr::from(10u64);
+        let proof = create_proof(&pk, witness, &mut rng).unwrap();
+        assert!(!verify_proof(&pk.vk, &[witness.out], &proof).unwrap());
+        assert!(check(witness).unwrap().unsatisfied.unwrap().trace.ends_with("x_squared"));
+    }
+
+    #[test]
+    fn gadget_traces_name_the_gadget() {
+        use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+
+        struct HashCircuit(Fr, Fr);
+        impl ConstraintSynthesizer<Fr> for HashCircuit {
+            fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+                let input = FpVar::new_witness(cs.clone(), || Ok(self.0))?;
+                let digest = FpVar::new_input(cs.clone(), || Ok(self.1))?;
+                crate::gadgets::poseidon::hash_var(cs, &poseidon_config(), &[input])?.enforce_equal(&digest)
+            }
+        }
+
+        let config = poseidon_config::<Fr>();
+        let x = Fr::from(7u64);
+        let report = check(HashCircuit(x, hash(&config, &[x]))).unwrap();
+        assert!(report.is_satisfied());
+        assert!(report.stats.constraints > 200);
+        let failure = check(HashCircuit(x, Fr::from(0u64))).unwrap().unsatisfied.unwrap();
+        assert_eq!(failure.index, report.stats.constraints - 1);
+        assert!(failure.trace.contains("enforce_equal"), "{}", failure.trace);
+    }
+}
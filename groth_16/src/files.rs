@@ -0,0 +1,287 @@
+// Proving keys, verifying keys and proofs on disk.
+//
+// Every file is a 4-byte tag naming what it holds, one byte saying whether the
+// points are compressed, then the `CanonicalSerialize` encoding:
+//
+//     "G16P" proving key      "G16V" verifying key
+//     "G16R" proof            "G16C" prepared verifying key (cache)
+//     "G16T" phase-1 ceremony transcript
+//     "G16D" phase-2 ceremony transcript
+//
+// Compressed points are about half the size but cost a square root each to
+// decode; either form can be read back without knowing which was written.
+// Files are fully validated (on curve, in the subgroup) when read, all but the
+// prepared-key cache.
+//
+// The prepared-key cache holds e(alpha, beta) and the G2 line coefficients of
+// -gamma and -delta, which are what verification actually uses, after the
+// SHA-256 of the verifying-key file it was prepared from. A cache whose digest
+// matches is read without validation and without recomputing the pairing; any
+// other is replaced. The digest catches a stale cache, not a malicious one:
+// whoever can write the cache next to a key can as well replace the key.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use ark_ec::pairing::Pairing;
+use ark_groth16::{prepare_verifying_key, PreparedVerifyingKey, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Validate};
+use sha2::{Digest, Sha256};
+
+pub const PROVING_KEY: [u8; 4] = *b"G16P";
+pub const VERIFYING_KEY: [u8; 4] = *b"G16V";
+pub const PROOF: [u8; 4] = *b"G16R";
+pub const PREPARED_VERIFYING_KEY: [u8; 4] = *b"G16C";
+pub const PHASE1_TRANSCRIPT: [u8; 4] = *b"G16T";
+pub const PHASE2_TRANSCRIPT: [u8; 4] = *b"G16D";
+
+const COMPRESSED: u8 = 1;
+const UNCOMPRESSED: u8 = 0;
+
+#[derive(Debug)]
+pub enum FileError {
+    Io(io::Error),
+    // The file holds something else, e.g. a proof where a key was expected.
+    WrongKind { expected: [u8; 4], found: [u8; 4] },
+    UnknownCompression(u8),
+    Serialization(SerializationError),
+    // Bytes left over after the value was decoded.
+    TrailingData,
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileError::Io(e) => write!(f, "I/O error: {}", e),
+            FileError::WrongKind { expected, found } => write!(
+                f,
+                "expected a {} file, found {}",
+                String::from_utf8_lossy(expected),
+                String::from_utf8_lossy(found)
+            ),
+            FileError::UnknownCompression(b) => write!(f, "unknown compression flag {}", b),
+            FileError::Serialization(e) => write!(f, "decoding error: {}", e),
+            FileError::TrailingData => write!(f, "trailing bytes after the encoded value"),
+        }
+    }
+}
+
+impl std::error::Error for FileError {}
+
+impl From<io::Error> for FileError {
+    fn from(e: io::Error) -> Self {
+        FileError::Io(e)
+    }
+}
+
+impl From<SerializationError> for FileError {
+    fn from(e: SerializationError) -> Self {
+        FileError::Serialization(e)
+    }
+}
+
+pub fn to_bytes<T: CanonicalSerialize>(kind: [u8; 4], value: &T, compress: Compress) -> Result<Vec<u8>, FileError> {
+    let mut bytes = kind.to_vec();
+    bytes.push(match compress {
+        Compress::Yes => COMPRESSED,
+        Compress::No => UNCOMPRESSED,
+    });
+    value.serialize_with_mode(&mut bytes, compress)?;
+    Ok(bytes)
+}
+
+pub fn from_bytes<T: CanonicalDeserialize>(kind: [u8; 4], bytes: &[u8], validate: Validate) -> Result<T, FileError> {
+    if bytes.len() < 5 {
+        return Err(FileError::Serialization(SerializationError::InvalidData));
+    }
+    let found = [bytes[0], bytes[1], bytes[2], bytes[3]];
+    if found != kind {
+        return Err(FileError::WrongKind { expected: kind, found });
+    }
+    let compress = match bytes[4] {
+        COMPRESSED => Compress::Yes,
+        UNCOMPRESSED => Compress::No,
+        b => return Err(FileError::UnknownCompression(b)),
+    };
+    let mut rest = &bytes[5..];
+    let value = T::deserialize_with_mode(&mut rest, compress, validate)?;
+    if !rest.is_empty() {
+        return Err(FileError::TrailingData);
+    }
+    Ok(value)
+}
+
+pub fn write<T: CanonicalSerialize>(path: impl AsRef<Path>, kind: [u8; 4], value: &T, compress: Compress) -> Result<(), FileError> {
+    Ok(fs::write(path, to_bytes(kind, value, compress)?)?)
+}
+
+pub fn read<T: CanonicalDeserialize>(path: impl AsRef<Path>, kind: [u8; 4]) -> Result<T, FileError> {
+    from_bytes(kind, &fs::read(path)?, Validate::Yes)
+}
+
+// Where the prepared form of the verifying key at `vk_path` is cached.
+pub fn prepared_cache_path(vk_path: &Path) -> PathBuf {
+    let mut name = vk_path.as_os_str().to_owned();
+    name.push(".prepared");
+    PathBuf::from(name)
+}
+
+// Loads the verifying key at `vk_path` in prepared form, from the cache next
+// to it if that was prepared from the same file and otherwise rewriting it.
+// The flag tells whether the cache was used.
+pub fn load_prepared_verifying_key<E: Pairing>(vk_path: &Path) -> Result<(PreparedVerifyingKey<E>, bool), FileError> {
+    let vk_bytes = fs::read(vk_path)?;
+    let digest: [u8; 32] = Sha256::digest(&vk_bytes).into();
+    let cache_path = prepared_cache_path(vk_path);
+    if let Ok(cache) = fs::read(&cache_path) {
+        if let Ok((cached, pvk)) = from_bytes::<([u8; 32], PreparedVerifyingKey<E>)>(PREPARED_VERIFYING_KEY, &cache, Validate::No) {
+            if cached == digest {
+                return Ok((pvk, true));
+            }
+        }
+    }
+
+    let vk: VerifyingKey<E> = from_bytes(VERIFYING_KEY, &vk_bytes, Validate::Yes)?;
+    let pvk = prepare_verifying_key(&vk);
+    // A cache that cannot be written (read-only directory) is simply left stale.
+    let _ = write(&cache_path, PREPARED_VERIFYING_KEY, &(digest, pvk.clone()), Compress::No);
+    Ok((pvk, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::CubicDemoCircuit;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_groth16::{Groth16, Proof, ProvingKey};
+    use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
+    use ark_std::rand::{rngs::StdRng, RngCore, SeedableRng};
+    use ark_std::test_rng;
+
+    fn setup(rng: &mut StdRng) -> (ProvingKey<Bls12_381>, VerifyingKey<Bls12_381>) {
+        Groth16::<Bls12_381>::setup(CubicDemoCircuit::<Fr> { x: None }, rng).unwrap()
+    }
+
+    // A fresh directory under the system temp dir.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("groth_16-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn keys_and_proofs_round_trip_in_both_forms() {
+        let mut rng = StdRng::seed_from_u64(test_rng().next_u64());
+        let (pk, vk) = setup(&mut rng);
+        let proof = Groth16::<Bls12_381>::prove(&pk, CubicDemoCircuit { x: Some(Fr::from(3u64)) }, &mut rng).unwrap();
+
+        for compress in [Compress::Yes, Compress::No] {
+            let pk2: ProvingKey<Bls12_381> =
+                from_bytes(PROVING_KEY, &to_bytes(PROVING_KEY, &pk, compress).unwrap(), Validate::Yes).unwrap();
+            let vk2: VerifyingKey<Bls12_381> =
+                from_bytes(VERIFYING_KEY, &to_bytes(VERIFYING_KEY, &vk, compress).unwrap(), Validate::Yes).unwrap();
+            let proof2: Proof<Bls12_381> =
+                from_bytes(PROOF, &to_bytes(PROOF, &proof, compress).unwrap(), Validate::Yes).unwrap();
+            assert_eq!((&pk2, &vk2, &proof2), (&pk, &vk, &proof));
+            assert!(Groth16::<Bls12_381>::verify(&vk2, &[Fr::from(35u64)], &proof2).unwrap());
+        }
+
+        // 48 + 96 + 48 bytes against 96 + 192 + 96.
+        assert_eq!(to_bytes(PROOF, &proof, Compress::Yes).unwrap().len(), 5 + 192);
+        assert_eq!(to_bytes(PROOF, &proof, Compress::No).unwrap().len(), 5 + 384);
+    }
+
+    #[test]
+    fn malformed_files_are_rejected() {
+        let mut rng = StdRng::seed_from_u64(test_rng().next_u64());
+        let (_, vk) = setup(&mut rng);
+        let bytes = to_bytes(VERIFYING_KEY, &vk, Compress::Yes).unwrap();
+
+        assert!(matches!(
+            from_bytes::<VerifyingKey<Bls12_381>>(PROVING_KEY, &bytes, Validate::Yes),
+            Err(FileError::WrongKind { .. })
+        ));
+        assert!(from_bytes::<VerifyingKey<Bls12_381>>(VERIFYING_KEY, &bytes[..bytes.len() - 1], Validate::Yes).is_err());
+        let mut extra = bytes.clone();
+        extra.push(0);
+        assert!(matches!(
+            from_bytes::<VerifyingKey<Bls12_381>>(VERIFYING_KEY, &extra, Validate::Yes),
+            Err(FileError::TrailingData)
+        ));
+        let mut flag = bytes.clone();
+        flag[4] = 7;
+        assert!(matches!(
+            from_bytes::<VerifyingKey<Bls12_381>>(VERIFYING_KEY, &flag, Validate::Yes),
+            Err(FileError::UnknownCompression(7))
+        ));
+        // A corrupted point.
+        let mut point = bytes.clone();
+        point[10] ^= 0x01;
+        assert!(from_bytes::<VerifyingKey<Bls12_381>>(VERIFYING_KEY, &point, Validate::Yes).is_err());
+    }
+
+    #[test]
+    fn prepared_key_cache_is_reused_and_refreshed() {
+        let mut rng = StdRng::seed_from_u64(test_rng().next_u64());
+        let dir = temp_dir("pvk-cache");
+        let vk_path = dir.join("vk.bin");
+
+        let (pk, vk) = setup(&mut rng);
+        write(&vk_path, VERIFYING_KEY, &vk, Compress::Yes).unwrap();
+        let (pvk, cached) = load_prepared_verifying_key::<Bls12_381>(&vk_path).unwrap();
+        assert!(!cached);
+        assert!(prepared_cache_path(&vk_path).is_file());
+        let (pvk2, cached) = load_prepared_verifying_key::<Bls12_381>(&vk_path).unwrap();
+        assert!(cached);
+        assert_eq!(pvk, pvk2);
+
+        let proof = Groth16::<Bls12_381>::prove(&pk, CubicDemoCircuit { x: Some(Fr::from(4u64)) }, &mut rng).unwrap();
+        assert!(Groth16::<Bls12_381>::verify_with_processed_vk(&pvk2, &[Fr::from(73u64)], &proof).unwrap());
+
+        // A new key (here written uncompressed) makes the cache stale.
+        let (_, other_vk) = setup(&mut rng);
+        write(&vk_path, VERIFYING_KEY, &other_vk, Compress::No).unwrap();
+        let (pvk3, cached) = load_prepared_verifying_key::<Bls12_381>(&vk_path).unwrap();
+        assert!(!cached);
+        assert_eq!(pvk3.vk, other_vk);
+        assert!(load_prepared_verifying_key::<Bls12_381>(&vk_path).unwrap().1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn cache_of_another_key_is_not_used() {
+        let mut rng = StdRng::seed_from_u64(test_rng().next_u64());
+        let dir = temp_dir("pvk-other");
+        let (a, b) = (dir.join("a.bin"), dir.join("b.bin"));
+
+        let (pk, vk) = setup(&mut rng);
+        let (_, other_vk) = setup(&mut rng);
+        write(&a, VERIFYING_KEY, &vk, Compress::Yes).unwrap();
+        write(&b, VERIFYING_KEY, &other_vk, Compress::Yes).unwrap();
+        load_prepared_verifying_key::<Bls12_381>(&b).unwrap();
+        let honest = prepare_verifying_key(&vk);
+
+        // b's cache, and one truncated, next to a.
+        let cache = fs::read(prepared_cache_path(&b)).unwrap();
+        for planted in [&cache[..], &cache[..cache.len() - 1]] {
+            fs::write(prepared_cache_path(&a), planted).unwrap();
+            let (pvk, cached) = load_prepared_verifying_key::<Bls12_381>(&a).unwrap();
+            assert!(!cached);
+            assert_eq!(pvk, honest);
+        }
+        let (pvk, cached) = load_prepared_verifying_key::<Bls12_381>(&a).unwrap();
+        assert!(cached);
+        let proof = Groth16::<Bls12_381>::prove(&pk, CubicDemoCircuit { x: Some(Fr::from(3u64)) }, &mut rng).unwrap();
+        assert!(Groth16::<Bls12_381>::verify_with_processed_vk(&pvk, &[Fr::from(35u64)], &proof).unwrap());
+
+        // The same key re-encoded is another file, so the cache is refreshed.
+        write(&a, VERIFYING_KEY, &vk, Compress::No).unwrap();
+        assert!(!load_prepared_verifying_key::<Bls12_381>(&a).unwrap().1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,95 @@
+// Boolean decomposition of field elements.
+//
+// `to_bits_le` allocates n boolean witnesses b_i and enforces
+//
+//     sum_i b_i * 2^i == value
+//
+// so the bits are unique and `value` is proved to be below 2^n. Every other
+// gadget that needs to look at the binary form of a number goes through here.
+// n must stay below the field size: with 2^n >= p the sum could wrap around
+// and two different bit strings would encode the same value.
+
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::SynthesisError;
+
+// The n low bits of `value`, least significant first.
+pub fn to_bits_le<F: PrimeField>(value: &FpVar<F>, n: usize) -> Result<Vec<Boolean<F>>, SynthesisError> {
+    assert!(n < F::MODULUS_BIT_SIZE as usize, "decomposition would wrap around the field");
+
+    if let FpVar::Constant(c) = value {
+        let bits = c.into_bigint().to_bits_le();
+        if bits[n..].iter().any(|b| *b) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        return Ok(bits[..n].iter().map(|b| Boolean::constant(*b)).collect());
+    }
+
+    let cs = value.cs();
+    let native = value.value().map(|v| v.into_bigint());
+    let bits = (0..n)
+        .map(|i| Boolean::new_witness(cs.clone(), || native.map(|v| v.get_bit(i))))
+        .collect::<Result<Vec<_>, _>>()?;
+    Boolean::le_bits_to_fp(&bits)?.enforce_equal(value)?;
+    Ok(bits)
+}
+
+// The field element with the given little-endian bits.
+pub fn from_bits_le<F: PrimeField>(bits: &[Boolean<F>]) -> Result<FpVar<F>, SynthesisError> {
+    Boolean::le_bits_to_fp(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadgets::test_utils::{is_satisfied, prove_and_verify};
+    use ark_bls12_381::Fr;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef};
+
+    // Proves knowledge of the 8-bit decomposition of a public byte, with the
+    // secret bits exposed as public outputs in reverse order.
+    #[derive(Clone, Copy)]
+    struct ByteCircuit {
+        value: Option<Fr>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for ByteCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let value = FpVar::new_input(cs.clone(), || self.value.ok_or(SynthesisError::AssignmentMissing))?;
+            let bits = to_bits_le(&value, 8)?;
+            let reversed: Vec<_> = bits.iter().rev().cloned().collect();
+            let mirrored = FpVar::new_input(cs, || {
+                let mut mirrored = 0u64;
+                for (i, bit) in reversed.iter().enumerate() {
+                    mirrored |= (bit.value()? as u64) << i;
+                }
+                Ok(Fr::from(mirrored))
+            })?;
+            from_bits_le(&reversed)?.enforce_equal(&mirrored)
+        }
+    }
+
+    #[test]
+    fn byte_decomposition_proves() {
+        // 0b1011_0001 reversed is 0b1000_1101.
+        let circuit = ByteCircuit { value: Some(Fr::from(0b1011_0001u64)) };
+        assert!(prove_and_verify(circuit, &[Fr::from(0b1011_0001u64), Fr::from(0b1000_1101u64)]));
+        assert!(!prove_and_verify(circuit, &[Fr::from(0b1011_0001u64), Fr::from(0b1011_0001u64)]));
+    }
+
+    #[test]
+    fn values_too_wide_for_the_bits_are_unsatisfiable() {
+        assert!(is_satisfied(ByteCircuit { value: Some(Fr::from(255u64)) }));
+        // 256 has no 8-bit decomposition: the witnessed low bits do not sum to it.
+        assert!(!is_satisfied(ByteCircuit { value: Some(Fr::from(256u64)) }));
+        assert!(!is_satisfied(ByteCircuit { value: Some(-Fr::from(1u64)) }));
+    }
+
+    #[test]
+    fn constants_decompose_without_constraints() {
+        let bits = to_bits_le(&FpVar::Constant(Fr::from(5u64)), 4).unwrap();
+        assert_eq!(bits.iter().map(|b| b.value().unwrap()).collect::<Vec<_>>(), vec![true, false, true, false]);
+        assert!(to_bits_le(&FpVar::Constant(Fr::from(16u64)), 4).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+// Comparison of n-bit values.
+//
+// For a, b in [0, 2^n), the number 2^n + a - b lies in [1, 2^(n+1)) and its
+// bit n is set exactly when a >= b. Decomposing it into n + 1 bits therefore
+// yields a < b as a single boolean, for n + 2 constraints.
+//
+// The inputs must already be known to fit in n bits (see `range`); otherwise
+// the difference may wrap around the field and the answer is meaningless.
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::SynthesisError;
+
+use super::bits::to_bits_le;
+
+pub fn is_less_than<F: PrimeField>(a: &FpVar<F>, b: &FpVar<F>, n: usize) -> Result<Boolean<F>, SynthesisError> {
+    let shifted = a + FpVar::Constant(F::from(2u64).pow([n as u64])) - b;
+    let bits = to_bits_le(&shifted, n + 1)?;
+    Ok(!&bits[n])
+}
+
+pub fn is_less_or_equal<F: PrimeField>(a: &FpVar<F>, b: &FpVar<F>, n: usize) -> Result<Boolean<F>, SynthesisError> {
+    Ok(!&is_less_than(b, a, n)?)
+}
+
+pub fn enforce_less_than<F: PrimeField>(a: &FpVar<F>, b: &FpVar<F>, n: usize) -> Result<(), SynthesisError> {
+    is_less_than(a, b, n)?.enforce_equal(&Boolean::TRUE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadgets::range::enforce_bit_length;
+    use crate::gadgets::test_utils::{is_satisfied, prove_and_verify};
+    use ark_bls12_381::Fr;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef};
+
+    // A sealed-bid check: the secret bid is below the public reserve price or
+    // not, and the circuit outputs which one it is as a public 0/1 value.
+    #[derive(Clone, Copy)]
+    struct BidCircuit {
+        bid: Option<Fr>,
+        reserve: Fr,
+        below: bool,
+    }
+
+    impl ConstraintSynthesizer<Fr> for BidCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let reserve = FpVar::new_input(cs.clone(), || Ok(self.reserve))?;
+            let below = Boolean::new_input(cs.clone(), || Ok(self.below))?;
+            let bid = FpVar::new_witness(cs, || self.bid.ok_or(SynthesisError::AssignmentMissing))?;
+            enforce_bit_length(&bid, 32)?;
+            enforce_bit_length(&reserve, 32)?;
+            is_less_than(&bid, &reserve, 32)?.enforce_equal(&below)
+        }
+    }
+
+    fn circuit(bid: u64, below: bool) -> BidCircuit {
+        BidCircuit { bid: Some(Fr::from(bid)), reserve: Fr::from(1000u64), below }
+    }
+
+    #[test]
+    fn comparison_proves() {
+        let reserve = Fr::from(1000u64);
+        assert!(prove_and_verify(circuit(999, true), &[reserve, Fr::from(1u64)]));
+        assert!(prove_and_verify(circuit(1500, false), &[reserve, Fr::from(0u64)]));
+        assert!(!prove_and_verify(circuit(999, true), &[reserve, Fr::from(0u64)]));
+    }
+
+    #[test]
+    fn comparison_results_are_correct() {
+        assert!(is_satisfied(circuit(0, true)));
+        assert!(is_satisfied(circuit(999, true)));
+        assert!(is_satisfied(circuit(1000, false)));
+        assert!(is_satisfied(circuit(u32::MAX as u64, false)));
+        assert!(!is_satisfied(circuit(999, false)));
+        assert!(!is_satisfied(circuit(1000, true)));
+    }
+
+    #[test]
+    fn less_or_equal_and_enforce() {
+        let cs = ark_relations::r1cs::ConstraintSystem::<Fr>::new_ref();
+        let a = FpVar::new_witness(cs.clone(), || Ok(Fr::from(7u64))).unwrap();
+        let b = FpVar::new_witness(cs.clone(), || Ok(Fr::from(7u64))).unwrap();
+        assert!(is_less_or_equal(&a, &b, 4).unwrap().value().unwrap());
+        assert!(!is_less_than(&a, &b, 4).unwrap().value().unwrap());
+        enforce_less_than(&a, &b, 4).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
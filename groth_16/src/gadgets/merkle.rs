@@ -0,0 +1,183 @@
+// Merkle-tree membership with Poseidon as the two-to-one hash.
+//
+// A tree of 2^depth leaves stores every level; `path` returns the siblings
+// from the leaf up to the root. In-circuit, the leaf index is given as
+// little-endian bits, bit i telling whether the node at level i is a right
+// child. `swap_if` orders each (node, sibling) pair by that bit, so the proof
+// reveals neither the leaf nor its position, only the root.
+//
+// Leaves are field elements, typically themselves Poseidon commitments.
+
+use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use super::poseidon::{hash, hash_var};
+use super::select::swap_if;
+
+pub struct MerkleTree<F: PrimeField> {
+    // levels[0] are the leaves, the last level is the root alone.
+    levels: Vec<Vec<F>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerklePath<F: PrimeField> {
+    pub leaf_index: usize,
+    // Sibling at each level, from the leaves up.
+    pub siblings: Vec<F>,
+}
+
+impl<F: PrimeField + Absorb> MerkleTree<F> {
+    // The number of leaves must be a non-zero power of two.
+    pub fn new(config: &PoseidonConfig<F>, leaves: Vec<F>) -> Self {
+        assert!(leaves.len().is_power_of_two(), "leaf count must be a power of two");
+        let mut levels = vec![leaves];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels.last().unwrap().chunks(2).map(|pair| hash(config, pair)).collect();
+            levels.push(next);
+        }
+        MerkleTree { levels }
+    }
+
+    pub fn root(&self) -> F {
+        self.levels.last().unwrap()[0]
+    }
+
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    pub fn path(&self, leaf_index: usize) -> MerklePath<F> {
+        let siblings = (0..self.depth()).map(|level| self.levels[level][(leaf_index >> level) ^ 1]).collect();
+        MerklePath { leaf_index, siblings }
+    }
+}
+
+impl<F: PrimeField + Absorb> MerklePath<F> {
+    pub fn compute_root(&self, config: &PoseidonConfig<F>, leaf: F) -> F {
+        self.siblings.iter().enumerate().fold(leaf, |node, (level, sibling)| {
+            if (self.leaf_index >> level) & 1 == 1 {
+                hash(config, &[*sibling, node])
+            } else {
+                hash(config, &[node, *sibling])
+            }
+        })
+    }
+
+    pub fn verify(&self, config: &PoseidonConfig<F>, leaf: F, root: F) -> bool {
+        self.compute_root(config, leaf) == root
+    }
+}
+
+// Recomputes the root from a leaf, its siblings and the bits of its index.
+pub fn compute_root_var<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    config: &PoseidonConfig<F>,
+    leaf: &FpVar<F>,
+    siblings: &[FpVar<F>],
+    index_bits: &[Boolean<F>],
+) -> Result<FpVar<F>, SynthesisError> {
+    assert_eq!(siblings.len(), index_bits.len());
+    let mut node = leaf.clone();
+    for (sibling, is_right) in siblings.iter().zip(index_bits) {
+        let (left, right) = swap_if(is_right, &node, sibling)?;
+        node = hash_var(cs.clone(), config, &[left, right])?;
+    }
+    Ok(node)
+}
+
+// Enforces that `leaf` is in the tree with the given root.
+pub fn enforce_membership<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    config: &PoseidonConfig<F>,
+    leaf: &FpVar<F>,
+    siblings: &[FpVar<F>],
+    index_bits: &[Boolean<F>],
+    root: &FpVar<F>,
+) -> Result<(), SynthesisError> {
+    compute_root_var(cs, config, leaf, siblings, index_bits)?.enforce_equal(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadgets::poseidon::poseidon_config;
+    use crate::gadgets::test_utils::{is_satisfied, prove_and_verify};
+    use ark_bls12_381::Fr;
+    use ark_relations::r1cs::ConstraintSynthesizer;
+
+    const DEPTH: usize = 3;
+
+    // Membership of a secret leaf in a public root, e.g. an allow-list.
+    #[derive(Clone)]
+    struct MembershipCircuit {
+        config: PoseidonConfig<Fr>,
+        root: Fr,
+        leaf: Option<Fr>,
+        path: Option<MerklePath<Fr>>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for MembershipCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let root = FpVar::new_input(cs.clone(), || Ok(self.root))?;
+            let leaf = FpVar::new_witness(cs.clone(), || self.leaf.ok_or(SynthesisError::AssignmentMissing))?;
+            let siblings = (0..DEPTH)
+                .map(|i| {
+                    FpVar::new_witness(cs.clone(), || {
+                        self.path.as_ref().map(|p| p.siblings[i]).ok_or(SynthesisError::AssignmentMissing)
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let index_bits = (0..DEPTH)
+                .map(|i| {
+                    Boolean::new_witness(cs.clone(), || {
+                        self.path.as_ref().map(|p| (p.leaf_index >> i) & 1 == 1).ok_or(SynthesisError::AssignmentMissing)
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            enforce_membership(cs, &self.config, &leaf, &siblings, &index_bits, &root)
+        }
+    }
+
+    fn tree(config: &PoseidonConfig<Fr>) -> MerkleTree<Fr> {
+        MerkleTree::new(config, (0..1u64 << DEPTH).map(|i| Fr::from(100 + i)).collect())
+    }
+
+    #[test]
+    fn native_paths_verify() {
+        let config = poseidon_config::<Fr>();
+        let tree = tree(&config);
+        assert_eq!(tree.depth(), DEPTH);
+        for i in 0..1 << DEPTH {
+            assert!(tree.path(i).verify(&config, Fr::from(100 + i as u64), tree.root()));
+        }
+        assert!(!tree.path(2).verify(&config, Fr::from(100u64), tree.root()));
+    }
+
+    #[test]
+    fn membership_proves() {
+        let config = poseidon_config::<Fr>();
+        let tree = tree(&config);
+        let root = tree.root();
+        let circuit = MembershipCircuit { config, root, leaf: Some(Fr::from(105u64)), path: Some(tree.path(5)) };
+        assert!(prove_and_verify(circuit.clone(), &[root]));
+        assert!(!prove_and_verify(circuit, &[root + Fr::from(1u64)]));
+    }
+
+    #[test]
+    fn non_members_and_wrong_positions_are_unsatisfiable() {
+        let config = poseidon_config::<Fr>();
+        let tree = tree(&config);
+        let root = tree.root();
+        let member = MembershipCircuit { config: config.clone(), root, leaf: Some(Fr::from(101u64)), path: Some(tree.path(1)) };
+        assert!(is_satisfied(member.clone()));
+
+        let outsider = MembershipCircuit { leaf: Some(Fr::from(999u64)), ..member.clone() };
+        assert!(!is_satisfied(outsider));
+        let wrong_position = MembershipCircuit { path: Some(MerklePath { leaf_index: 0, ..tree.path(1) }), ..member };
+        assert!(!is_satisfied(wrong_position));
+    }
+}
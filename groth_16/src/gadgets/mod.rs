@@ -0,0 +1,38 @@
+// Reusable R1CS gadgets over any prime field, written against ark-r1cs-std's
+// `FpVar` and `Boolean`.
+//
+// Each module comes with a small circuit in its tests that is proved and
+// verified with Groth16 over BLS12-381.
+
+pub mod bits;
+pub mod comparison;
+pub mod merkle;
+pub mod poseidon;
+pub mod range;
+pub mod select;
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_groth16::Groth16;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_snark::SNARK;
+    use ark_std::rand::{rngs::StdRng, RngCore, SeedableRng};
+    use ark_std::test_rng;
+
+    // Runs setup, prove and verify for `circuit` against the given public inputs.
+    pub fn prove_and_verify<C: ConstraintSynthesizer<Fr> + Clone>(circuit: C, public_inputs: &[Fr]) -> bool {
+        let mut rng = StdRng::seed_from_u64(test_rng().next_u64());
+        let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
+        let proof = Groth16::<Bls12_381>::prove(&pk, circuit, &mut rng).unwrap();
+        Groth16::<Bls12_381>::verify(&vk, public_inputs, &proof).unwrap()
+    }
+
+    // Whether the circuit's witness satisfies its constraints; a prover cannot
+    // produce a valid proof otherwise.
+    pub fn is_satisfied<C: ConstraintSynthesizer<Fr>>(circuit: C) -> bool {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        cs.is_satisfied().unwrap()
+    }
+}
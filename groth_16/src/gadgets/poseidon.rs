@@ -0,0 +1,130 @@
+// The Poseidon hash, natively and in-circuit.
+//
+// Poseidon is a sponge over the scalar field itself, so hashing inside a
+// circuit costs a few hundred constraints instead of the tens of thousands a
+// bit-oriented hash like SHA-256 needs. Both sides use ark-crypto-primitives'
+// sponge with the same parameters, which is what makes a native hash and its
+// in-circuit recomputation agree.
+//
+// Parameters: state width 3 (rate 2, capacity 1), S-box x^5, 8 full and 57
+// partial rounds, as recommended for ~255-bit fields at 128-bit security. x^5 is
+// a permutation only when gcd(5, p - 1) = 1, which holds for the scalar fields
+// of BLS12-381 and BN254. Round constants and the MDS matrix come from the
+// Grain LFSR of the Poseidon reference implementation.
+//
+// A sponge alone pads short inputs with zeros, so [x] and [x, 0] would hash
+// alike. As the Poseidon paper suggests for variable-length hashing, the
+// capacity element starts as the input length instead of zero; that separates
+// the lengths without an extra permutation.
+
+use ark_crypto_primitives::sponge::constraints::CryptographicSpongeVar;
+use ark_crypto_primitives::sponge::poseidon::constraints::PoseidonSpongeVar;
+use ark_crypto_primitives::sponge::poseidon::{find_poseidon_ark_and_mds, PoseidonConfig, PoseidonSponge};
+use ark_crypto_primitives::sponge::{Absorb, CryptographicSponge};
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+const RATE: usize = 2;
+const FULL_ROUNDS: usize = 8;
+const PARTIAL_ROUNDS: usize = 57;
+const ALPHA: u64 = 5;
+
+pub fn poseidon_config<F: PrimeField>() -> PoseidonConfig<F> {
+    let (ark, mds) =
+        find_poseidon_ark_and_mds::<F>(F::MODULUS_BIT_SIZE as u64, RATE, FULL_ROUNDS as u64, PARTIAL_ROUNDS as u64, 0);
+    PoseidonConfig::new(FULL_ROUNDS, PARTIAL_ROUNDS, ALPHA, mds, ark, RATE, 1)
+}
+
+pub fn hash<F: PrimeField + Absorb>(config: &PoseidonConfig<F>, inputs: &[F]) -> F {
+    let mut sponge = PoseidonSponge::new(config);
+    sponge.state[0] = F::from(inputs.len() as u64);
+    sponge.absorb(&inputs.to_vec());
+    sponge.squeeze_field_elements(1)[0]
+}
+
+pub fn hash_var<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    config: &PoseidonConfig<F>,
+    inputs: &[FpVar<F>],
+) -> Result<FpVar<F>, SynthesisError> {
+    let mut sponge = PoseidonSpongeVar::new(cs, config);
+    sponge.state[0] = FpVar::Constant(F::from(inputs.len() as u64));
+    sponge.absorb(&inputs)?;
+    Ok(sponge.squeeze_field_elements(1)?.remove(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadgets::test_utils::{is_satisfied, prove_and_verify};
+    use ark_bls12_381::Fr;
+    use ark_r1cs_std::prelude::*;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+
+    // Knowledge of a preimage (secret, nonce) of a public Poseidon digest.
+    #[derive(Clone)]
+    struct PreimageCircuit {
+        config: PoseidonConfig<Fr>,
+        preimage: Option<[Fr; 2]>,
+        digest: Fr,
+    }
+
+    impl ConstraintSynthesizer<Fr> for PreimageCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let digest = FpVar::new_input(cs.clone(), || Ok(self.digest))?;
+            let preimage = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || {
+                self.preimage.map(|p| p.to_vec()).ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            hash_var(cs, &self.config, &preimage)?.enforce_equal(&digest)
+        }
+    }
+
+    #[test]
+    fn preimage_knowledge_proves() {
+        let config = poseidon_config::<Fr>();
+        let preimage = [Fr::from(1234u64), Fr::from(5678u64)];
+        let digest = hash(&config, &preimage);
+        let circuit = PreimageCircuit { config, preimage: Some(preimage), digest };
+
+        assert!(prove_and_verify(circuit.clone(), &[digest]));
+        assert!(!prove_and_verify(circuit, &[digest + Fr::from(1u64)]));
+    }
+
+    #[test]
+    fn wrong_preimage_is_unsatisfiable() {
+        let config = poseidon_config::<Fr>();
+        let digest = hash(&config, &[Fr::from(1u64), Fr::from(2u64)]);
+        let circuit = PreimageCircuit { config, preimage: Some([Fr::from(2u64), Fr::from(1u64)]), digest };
+        assert!(!is_satisfied(circuit));
+    }
+
+    #[test]
+    fn native_and_circuit_hashes_agree() {
+        let config = poseidon_config::<Fr>();
+        let inputs: Vec<Fr> = (0..5u64).map(Fr::from).collect();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let vars = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(inputs.clone())).unwrap();
+        assert_eq!(hash_var(cs.clone(), &config, &vars).unwrap().value().unwrap(), hash(&config, &inputs));
+        assert_ne!(hash(&config, &inputs[..4]), hash(&config, &inputs));
+        // A two-element hash is one permutation: a few hundred constraints.
+        assert!(cs.num_constraints() < 1000, "{}", cs.num_constraints());
+    }
+
+    #[test]
+    fn inputs_of_different_lengths_do_not_collide() {
+        let config = poseidon_config::<Fr>();
+        let x = Fr::from(7u64);
+        let zero = Fr::from(0u64);
+        assert_ne!(hash(&config, &[x]), hash(&config, &[x, zero]));
+        assert_ne!(hash(&config, &[]), hash(&config, &[zero]));
+        assert_ne!(hash(&config, &[x, zero]), hash(&config, &[x, zero, zero]));
+
+        // The circuit separates them the same way.
+        for inputs in [vec![x], vec![x, zero], vec![x, zero, zero]] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let vars = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(inputs.clone())).unwrap();
+            assert_eq!(hash_var(cs, &config, &vars).unwrap().value().unwrap(), hash(&config, &inputs));
+        }
+    }
+}
@@ -0,0 +1,76 @@
+// Range checks.
+//
+// A value lies in [0, 2^n) exactly when it has an n-bit decomposition, which
+// costs n + 1 constraints. Intervals [lower, upper] with lower <= upper < 2^n
+// are two such checks on value - lower and upper - value: if value were outside
+// the interval one of the differences would wrap around to a huge field element.
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::SynthesisError;
+
+use super::bits::to_bits_le;
+
+// Enforces 0 <= value < 2^n.
+pub fn enforce_bit_length<F: PrimeField>(value: &FpVar<F>, n: usize) -> Result<(), SynthesisError> {
+    to_bits_le(value, n).map(|_| ())
+}
+
+// Enforces lower <= value <= upper, for bounds known to fit in n bits.
+pub fn enforce_in_range<F: PrimeField>(
+    value: &FpVar<F>,
+    lower: &FpVar<F>,
+    upper: &FpVar<F>,
+    n: usize,
+) -> Result<(), SynthesisError> {
+    enforce_bit_length(&(value - lower), n)?;
+    enforce_bit_length(&(upper - value), n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadgets::test_utils::{is_satisfied, prove_and_verify};
+    use ark_bls12_381::Fr;
+    use ark_r1cs_std::prelude::*;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef};
+
+    // Proves that a secret age lies within public bounds, e.g. 18..=65.
+    #[derive(Clone, Copy)]
+    struct AgeCircuit {
+        age: Option<Fr>,
+        lower: Fr,
+        upper: Fr,
+    }
+
+    impl ConstraintSynthesizer<Fr> for AgeCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let lower = FpVar::new_input(cs.clone(), || Ok(self.lower))?;
+            let upper = FpVar::new_input(cs.clone(), || Ok(self.upper))?;
+            let age = FpVar::new_witness(cs, || self.age.ok_or(SynthesisError::AssignmentMissing))?;
+            enforce_bit_length(&age, 8)?;
+            enforce_in_range(&age, &lower, &upper, 8)
+        }
+    }
+
+    fn circuit(age: u64) -> AgeCircuit {
+        AgeCircuit { age: Some(Fr::from(age)), lower: Fr::from(18u64), upper: Fr::from(65u64) }
+    }
+
+    #[test]
+    fn age_in_range_proves() {
+        let bounds = [Fr::from(18u64), Fr::from(65u64)];
+        assert!(prove_and_verify(circuit(30), &bounds));
+        // The same proof does not hold for tighter public bounds.
+        assert!(!prove_and_verify(circuit(30), &[Fr::from(40u64), Fr::from(65u64)]));
+    }
+
+    #[test]
+    fn bounds_are_inclusive_and_enforced() {
+        assert!(is_satisfied(circuit(18)));
+        assert!(is_satisfied(circuit(65)));
+        assert!(!is_satisfied(circuit(17)));
+        assert!(!is_satisfied(circuit(66)));
+        assert!(!is_satisfied(circuit(300)));
+    }
+}
@@ -0,0 +1,103 @@
+// Conditional selection.
+//
+// `select` is cond ? a : b, computed as b + cond * (a - b) with a single
+// multiplication constraint. `swap_if` orders a pair by a secret bit, which is
+// how a Merkle path hides whether each node is a left or a right child, and
+// `select_by_index` picks one of 2^k values by the bits of a secret index.
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::SynthesisError;
+
+pub fn select<F: PrimeField>(cond: &Boolean<F>, a: &FpVar<F>, b: &FpVar<F>) -> Result<FpVar<F>, SynthesisError> {
+    FpVar::conditionally_select(cond, a, b)
+}
+
+// (b, a) if cond, else (a, b). The second output costs no extra constraint:
+// it is a + b minus the first.
+pub fn swap_if<F: PrimeField>(
+    cond: &Boolean<F>,
+    a: &FpVar<F>,
+    b: &FpVar<F>,
+) -> Result<(FpVar<F>, FpVar<F>), SynthesisError> {
+    let first = select(cond, b, a)?;
+    let second = a + b - &first;
+    Ok((first, second))
+}
+
+// values[index], with the index given as little-endian bits and values.len() == 2^bits.len().
+pub fn select_by_index<F: PrimeField>(index_bits: &[Boolean<F>], values: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+    assert_eq!(values.len(), 1 << index_bits.len(), "need exactly 2^k values");
+    // arkworks expects the most significant bit first.
+    let be_bits: Vec<_> = index_bits.iter().rev().cloned().collect();
+    FpVar::conditionally_select_power_of_two_vector(&be_bits, values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadgets::bits::to_bits_le;
+    use crate::gadgets::test_utils::{is_satisfied, prove_and_verify};
+    use ark_bls12_381::Fr;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef};
+
+    // A private table lookup: the output is table[index] for a secret index,
+    // while the table itself is public.
+    #[derive(Clone, Copy)]
+    struct LookupCircuit {
+        table: [Fr; 4],
+        index: Option<u64>,
+        output: Fr,
+    }
+
+    impl ConstraintSynthesizer<Fr> for LookupCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let table = Vec::<FpVar<Fr>>::new_input(cs.clone(), || Ok(self.table.to_vec()))?;
+            let output = FpVar::new_input(cs.clone(), || Ok(self.output))?;
+            let index = FpVar::new_witness(cs, || self.index.map(Fr::from).ok_or(SynthesisError::AssignmentMissing))?;
+            let bits = to_bits_le(&index, 2)?;
+            select_by_index(&bits, &table)?.enforce_equal(&output)
+        }
+    }
+
+    fn table() -> [Fr; 4] {
+        [10u64, 20, 30, 40].map(Fr::from)
+    }
+
+    #[test]
+    fn lookup_proves() {
+        let circuit = LookupCircuit { table: table(), index: Some(2), output: Fr::from(30u64) };
+        let mut inputs = table().to_vec();
+        inputs.push(Fr::from(30u64));
+        assert!(prove_and_verify(circuit, &inputs));
+        *inputs.last_mut().unwrap() = Fr::from(40u64);
+        assert!(!prove_and_verify(circuit, &inputs));
+    }
+
+    #[test]
+    fn lookup_rejects_wrong_outputs() {
+        for (index, value) in [(0, 10u64), (1, 20), (3, 40)] {
+            assert!(is_satisfied(LookupCircuit { table: table(), index: Some(index), output: Fr::from(value) }));
+        }
+        assert!(!is_satisfied(LookupCircuit { table: table(), index: Some(1), output: Fr::from(10u64) }));
+        // Out-of-range indices have no 2-bit decomposition.
+        assert!(!is_satisfied(LookupCircuit { table: table(), index: Some(4), output: Fr::from(10u64) }));
+    }
+
+    #[test]
+    fn select_and_swap() {
+        let cs = ark_relations::r1cs::ConstraintSystem::<Fr>::new_ref();
+        let a = FpVar::new_witness(cs.clone(), || Ok(Fr::from(1u64))).unwrap();
+        let b = FpVar::new_witness(cs.clone(), || Ok(Fr::from(2u64))).unwrap();
+        for bit in [false, true] {
+            let cond = Boolean::new_witness(cs.clone(), || Ok(bit)).unwrap();
+            let picked = select(&cond, &a, &b).unwrap().value().unwrap();
+            assert_eq!(picked, Fr::from(if bit { 1u64 } else { 2 }));
+            let (x, y) = swap_if(&cond, &a, &b).unwrap();
+            let expected = if bit { (2u64, 1u64) } else { (1, 2) };
+            assert_eq!((x.value().unwrap(), y.value().unwrap()), (Fr::from(expected.0), Fr::from(expected.1)));
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
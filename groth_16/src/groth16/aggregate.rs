@@ -0,0 +1,534 @@
+// SnarkPack-style aggregation of n Groth16 proofs under one verifying key
+// (Gailly, Maller, Nitulescu, "SnarkPack: Practical SNARK Aggregation", 2021).
+//
+// For a random r the n verification equations combine into one,
+//
+//     Z_AB = prod_i e(A_i, B_i)^(r^i) = e(alpha, beta)^(sum r^i) e(sum r^i IC(x_i), gamma) e(Z_C, delta)
+//     Z_C  = sum_i r^i C_i
+//
+// and the aggregator proves that Z_AB and Z_C are built from the proofs it
+// committed to before r was known. The commitments pair the vectors with keys
+// of powers of two secrets a and b,
+//
+//     com_AB = (prod e(A_i, v_i) e(w_i, B_i) for both key halves),   v_i = [a^i]_2 | [b^i]_2,  w_i = [a^(n+i)]_1 | [b^(n+i)]_1
+//     com_C  = (prod e(C_i, v_i) for both key halves)
+//
+// and a GIPA argument halves the vectors log n times: each round sends the
+// cross terms of the inner products and of the commitments, gets a challenge x
+// and folds A = A_L + x A_R, B = B_L + B_R / x, C = C_L + x C_R with the keys
+// folded to match. Scaling A and C by r^i is absorbed into the key for A and C
+// (v_i / r^i), so one key serves both. What remains are single elements and
+// the folded keys, whose correctness is a KZG opening of a polynomial the
+// verifier evaluates itself in O(log n).
+//
+// The proof has O(log n) group elements and verifies with O(log n) pairings
+// plus the O(n) public inputs. The aggregation key must come from a setup in
+// which nobody learns a or b, e.g. two powers-of-tau ceremonies;
+// `setup_aggregation` samples them locally, for tests and benchmarks.
+
+use std::fmt;
+
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ec::{AffineRepr, CurveGroup, PrimeGroup, VariableBaseMSM};
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::Rng;
+use ark_std::{One, UniformRand, Zero};
+
+use super::{Proof, VerifyingKey};
+use crate::transcript::Transcript;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregationError {
+    // SnarkPack halves the vectors, so n must be a power of two.
+    NotPowerOfTwo(usize),
+    KeyTooShort { needed: usize, available: usize },
+    // A proof's public inputs do not match the verifying key.
+    InputLength,
+    // The aggregate proof does not have log n rounds.
+    MalformedProof,
+}
+
+impl fmt::Display for AggregationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AggregationError::NotPowerOfTwo(n) => write!(f, "{} proofs; the count must be a power of two", n),
+            AggregationError::KeyTooShort { needed, available } => {
+                write!(f, "the aggregation key supports {} proofs, {} needed", available, needed)
+            }
+            AggregationError::InputLength => write!(f, "public inputs do not match the verifying key"),
+            AggregationError::MalformedProof => write!(f, "the aggregate proof has the wrong number of rounds"),
+        }
+    }
+}
+
+impl std::error::Error for AggregationError {}
+
+// [a^i]_1 and [b^i]_1 for i < 2N, [a^i]_2 and [b^i]_2 for i < N, for up to N proofs.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregationKey<E: Pairing> {
+    pub g1_a: Vec<E::G1Affine>,
+    pub g1_b: Vec<E::G1Affine>,
+    pub g2_a: Vec<E::G2Affine>,
+    pub g2_b: Vec<E::G2Affine>,
+}
+
+// What the verifier needs of the aggregation key.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregationVerifierKey<E: Pairing> {
+    pub g1_a: E::G1Affine,
+    pub g1_b: E::G1Affine,
+    pub g2_a: E::G2Affine,
+    pub g2_b: E::G2Affine,
+}
+
+impl<E: Pairing> AggregationKey<E> {
+    pub fn max_proofs(&self) -> usize {
+        self.g2_a.len()
+    }
+
+    pub fn verifier_key(&self) -> AggregationVerifierKey<E> {
+        AggregationVerifierKey { g1_a: self.g1_a[1], g1_b: self.g1_b[1], g2_a: self.g2_a[1], g2_b: self.g2_b[1] }
+    }
+}
+
+pub fn setup_aggregation<E: Pairing, R: Rng>(max_proofs: usize, rng: &mut R) -> AggregationKey<E> {
+    assert!(max_proofs.is_power_of_two() && max_proofs >= 2, "the key size must be a power of two, at least 2");
+    let (a, b) = (E::ScalarField::rand(rng), E::ScalarField::rand(rng));
+    let powers = |x: E::ScalarField, n: usize| {
+        let mut p = Vec::with_capacity(n);
+        let mut acc = E::ScalarField::one();
+        for _ in 0..n {
+            p.push(acc);
+            acc *= x;
+        }
+        p
+    };
+    let g1 = |s: Vec<E::ScalarField>| E::G1::normalize_batch(&s.iter().map(|s| E::G1::generator() * s).collect::<Vec<_>>());
+    let g2 = |s: Vec<E::ScalarField>| E::G2::normalize_batch(&s.iter().map(|s| E::G2::generator() * s).collect::<Vec<_>>());
+    AggregationKey {
+        g1_a: g1(powers(a, 2 * max_proofs)),
+        g1_b: g1(powers(b, 2 * max_proofs)),
+        g2_a: g2(powers(a, max_proofs)),
+        g2_b: g2(powers(b, max_proofs)),
+    }
+}
+
+// A commitment under the two key halves (a and b).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Commitment<E: Pairing> {
+    pub a: PairingOutput<E>,
+    pub b: PairingOutput<E>,
+}
+
+impl<E: Pairing> Commitment<E> {
+    // com_L^x com com_R^(1/x), the commitment to the folded vectors.
+    fn fold(&self, left: &Self, right: &Self, x: E::ScalarField, x_inv: E::ScalarField) -> Self {
+        Commitment { a: self.a + left.a * x + right.a * x_inv, b: self.b + left.b * x + right.b * x_inv }
+    }
+}
+
+// The cross terms of one halving round.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Round<E: Pairing> {
+    pub com_ab: (Commitment<E>, Commitment<E>),
+    pub z_ab: (PairingOutput<E>, PairingOutput<E>),
+    pub com_c: (Commitment<E>, Commitment<E>),
+    pub z_c: (E::G1Affine, E::G1Affine),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregateProof<E: Pairing> {
+    pub com_ab: Commitment<E>,
+    pub com_c: Commitment<E>,
+    pub z_ab: PairingOutput<E>,
+    pub z_c: E::G1Affine,
+    pub rounds: Vec<Round<E>>,
+    // The folded proofs and keys.
+    pub a: E::G1Affine,
+    pub b: E::G2Affine,
+    pub c: E::G1Affine,
+    pub v: (E::G2Affine, E::G2Affine),
+    pub w: (E::G1Affine, E::G1Affine),
+    // KZG openings showing v and w are the folded keys.
+    pub v_opening: (E::G2Affine, E::G2Affine),
+    pub w_opening: (E::G1Affine, E::G1Affine),
+}
+
+// The transcript starts from the statement: the verifying key and all inputs.
+fn transcript<E: Pairing>(vk: &VerifyingKey<E>, inputs: &[&[E::ScalarField]]) -> Transcript {
+    let mut t = Transcript::new(b"groth16-snarkpack");
+    t.append(vk);
+    t.append(&inputs.iter().map(|x| x.to_vec()).collect::<Vec<_>>());
+    t
+}
+
+fn split<T>(v: &[T]) -> (&[T], &[T]) {
+    v.split_at(v.len() / 2)
+}
+
+// e(g1, g2) summed over the pairs, i.e. the product of pairings.
+fn inner<E: Pairing>(g1: &[E::G1Affine], g2: &[E::G2Affine]) -> PairingOutput<E> {
+    E::multi_pairing(g1.iter().copied(), g2.iter().copied())
+}
+
+// Pair commitment to (A, B) under keys v and w.
+fn commit_ab<E: Pairing>(a: &[E::G1Affine], b: &[E::G2Affine], v: &[E::G2Affine], w: &[E::G1Affine]) -> PairingOutput<E> {
+    E::multi_pairing(a.iter().chain(w).copied(), v.iter().chain(b).copied())
+}
+
+fn fold_g1<G: CurveGroup>(v: &[G::Affine], x: G::ScalarField) -> Vec<G::Affine> {
+    let (l, r) = split(v);
+    G::normalize_batch(&l.iter().zip(r).map(|(l, r)| *r * x + l).collect::<Vec<_>>())
+}
+
+fn scale<G: CurveGroup>(v: &[G::Affine], scalars: &[G::ScalarField]) -> Vec<G::Affine> {
+    G::normalize_batch(&v.iter().zip(scalars).map(|(p, s)| *p * s).collect::<Vec<_>>())
+}
+
+fn powers<F: Field>(x: F, n: usize) -> Vec<F> {
+    std::iter::successors(Some(F::one()), |p| Some(*p * x)).take(n).collect()
+}
+
+// (f(X) - f(z)) / (X - z) by synthetic division, coefficients from the constant term up.
+pub(crate) fn kzg_quotient<F: Field>(f: &[F], z: F) -> Vec<F> {
+    let mut q = vec![F::zero(); f.len().saturating_sub(1)];
+    let mut carry = F::zero();
+    for i in (1..f.len()).rev() {
+        carry = f[i] + carry * z;
+        q[i - 1] = carry;
+    }
+    q
+}
+
+// The folded key for A and C is [f_v(a)]_2, f_v(X) = prod_j (1 + (X / r)^(m_j) / x_j),
+// with m_j = n / 2^j the offset of round j's right half. Likewise [f_w(a)]_1 for B,
+// f_w(X) = X^n prod_j (1 + x_j X^(m_j)).
+fn f_v<F: Field>(point: F, r_inv: F, challenges: &[(F, F)], n: usize) -> F {
+    let mut m = n;
+    challenges.iter().fold(F::one(), |acc, (_, x_inv)| {
+        m /= 2;
+        acc * (F::one() + *x_inv * (point * r_inv).pow([m as u64]))
+    })
+}
+
+fn f_w<F: Field>(point: F, challenges: &[(F, F)], n: usize) -> F {
+    let mut m = n;
+    challenges.iter().fold(point.pow([n as u64]), |acc, (x, _)| {
+        m /= 2;
+        acc * (F::one() + *x * point.pow([m as u64]))
+    })
+}
+
+// Coefficients of the same polynomials: index i collects the challenges of
+// the rounds in which i was in the right half (round 1 is the top bit).
+fn folding_coefficients<F: Field>(challenges: &[F]) -> Vec<F> {
+    let mut coeffs = vec![F::one()];
+    for c in challenges.iter().rev() {
+        let right: Vec<F> = coeffs.iter().map(|x| *x * c).collect();
+        coeffs.extend(right);
+    }
+    coeffs
+}
+
+fn check_count(n: usize, key_size: usize) -> Result<(), AggregationError> {
+    if !n.is_power_of_two() {
+        return Err(AggregationError::NotPowerOfTwo(n));
+    }
+    if n > key_size {
+        return Err(AggregationError::KeyTooShort { needed: n, available: key_size });
+    }
+    Ok(())
+}
+
+pub fn aggregate_proofs<E: Pairing>(
+    key: &AggregationKey<E>,
+    vk: &VerifyingKey<E>,
+    items: &[(&[E::ScalarField], &Proof<E>)],
+) -> Result<AggregateProof<E>, AggregationError> {
+    let n = items.len();
+    check_count(n, key.max_proofs())?;
+    if items.iter().any(|(x, _)| x.len() + 1 != vk.gamma_abc_g1.len()) {
+        return Err(AggregationError::InputLength);
+    }
+    let inputs: Vec<&[E::ScalarField]> = items.iter().map(|(x, _)| *x).collect();
+    let a: Vec<E::G1Affine> = items.iter().map(|(_, p)| p.a).collect();
+    let b: Vec<E::G2Affine> = items.iter().map(|(_, p)| p.b).collect();
+    let c: Vec<E::G1Affine> = items.iter().map(|(_, p)| p.c).collect();
+    let (v_a, v_b) = (&key.g2_a[..n], &key.g2_b[..n]);
+    let (w_a, w_b) = (&key.g1_a[n..2 * n], &key.g1_b[n..2 * n]);
+
+    let mut transcript = transcript(vk, &inputs);
+    let com_ab = Commitment { a: commit_ab(&a, &b, v_a, w_a), b: commit_ab(&a, &b, v_b, w_b) };
+    let com_c = Commitment { a: inner(&c, v_a), b: inner(&c, v_b) };
+    transcript.append(&(com_ab, com_c));
+    let r: E::ScalarField = transcript.challenge();
+    let r_inv = r.inverse().expect("challenges are non-zero");
+
+    // A_i r^i and C_i r^i, with the key scaled by r^-i to keep the commitments.
+    let mut a = scale::<E::G1>(&a, &powers(r, n));
+    let mut c = scale::<E::G1>(&c, &powers(r, n));
+    let mut b = b;
+    let mut v = (scale::<E::G2>(v_a, &powers(r_inv, n)), scale::<E::G2>(v_b, &powers(r_inv, n)));
+    let mut w = (w_a.to_vec(), w_b.to_vec());
+    let z_ab = inner(&a, &b);
+    let z_c = c.iter().fold(E::G1::zero(), |acc, p| acc + p).into_affine();
+    transcript.append(&(z_ab, z_c));
+
+    // All entries of the scalar vector of the C inner product stay equal: s = prod (1 + 1/x_j).
+    let mut s = E::ScalarField::one();
+    let mut rounds = Vec::new();
+    let mut challenges = Vec::new();
+    while a.len() > 1 {
+        let (a_l, a_r) = split(&a);
+        let (b_l, b_r) = split(&b);
+        let (c_l, c_r) = split(&c);
+        let (va_l, va_r) = split(&v.0);
+        let (vb_l, vb_r) = split(&v.1);
+        let (wa_l, wa_r) = split(&w.0);
+        let (wb_l, wb_r) = split(&w.1);
+        let sum = |points: &[E::G1Affine]| (points.iter().fold(E::G1::zero(), |acc, p| acc + p) * s).into_affine();
+        let round = Round {
+            com_ab: (
+                Commitment { a: commit_ab(a_r, b_l, va_l, wa_r), b: commit_ab(a_r, b_l, vb_l, wb_r) },
+                Commitment { a: commit_ab(a_l, b_r, va_r, wa_l), b: commit_ab(a_l, b_r, vb_r, wb_l) },
+            ),
+            z_ab: (inner(a_r, b_l), inner(a_l, b_r)),
+            com_c: (
+                Commitment { a: inner(c_r, va_l), b: inner(c_r, vb_l) },
+                Commitment { a: inner(c_l, va_r), b: inner(c_l, vb_r) },
+            ),
+            z_c: (sum(c_r), sum(c_l)),
+        };
+        transcript.append(&round);
+        rounds.push(round);
+        let x: E::ScalarField = transcript.challenge();
+        let x_inv = x.inverse().expect("challenges are non-zero");
+        challenges.push((x, x_inv));
+
+        a = fold_g1::<E::G1>(&a, x);
+        c = fold_g1::<E::G1>(&c, x);
+        b = fold_g1::<E::G2>(&b, x_inv);
+        v = (fold_g1::<E::G2>(&v.0, x_inv), fold_g1::<E::G2>(&v.1, x_inv));
+        w = (fold_g1::<E::G1>(&w.0, x), fold_g1::<E::G1>(&w.1, x));
+        s *= E::ScalarField::one() + x_inv;
+    }
+
+    let (v, w) = ((v.0[0], v.1[0]), (w.0[0], w.1[0]));
+    transcript.append(&(a[0], b[0], c[0], v, w));
+    let z: E::ScalarField = transcript.challenge();
+
+    // f_v has coefficients e_i r^-i, f_w has X^n times e'_i.
+    let x_invs: Vec<_> = challenges.iter().map(|(_, x_inv)| *x_inv).collect();
+    let xs: Vec<_> = challenges.iter().map(|(x, _)| *x).collect();
+    let coeffs_v: Vec<_> = folding_coefficients(&x_invs).iter().zip(powers(r_inv, n)).map(|(e, p)| *e * p).collect();
+    let mut coeffs_w = vec![E::ScalarField::zero(); n];
+    coeffs_w.extend(folding_coefficients(&xs));
+    let q_v = kzg_quotient(&coeffs_v, z);
+    let q_w = kzg_quotient(&coeffs_w, z);
+    let open_g2 = |powers: &[E::G2Affine]| E::G2::msm_unchecked(powers, &q_v).into_affine();
+    let open_g1 = |powers: &[E::G1Affine]| E::G1::msm_unchecked(powers, &q_w).into_affine();
+
+    Ok(AggregateProof {
+        com_ab,
+        com_c,
+        z_ab,
+        z_c,
+        rounds,
+        a: a[0],
+        b: b[0],
+        c: c[0],
+        v,
+        w,
+        v_opening: (open_g2(&key.g2_a), open_g2(&key.g2_b)),
+        w_opening: (open_g1(&key.g1_a), open_g1(&key.g1_b)),
+    })
+}
+
+pub fn verify_aggregate_proof<E: Pairing>(
+    key: &AggregationVerifierKey<E>,
+    vk: &VerifyingKey<E>,
+    inputs: &[&[E::ScalarField]],
+    proof: &AggregateProof<E>,
+) -> Result<bool, AggregationError> {
+    let n = inputs.len();
+    check_count(n, usize::MAX)?;
+    if proof.rounds.len() != n.trailing_zeros() as usize {
+        return Err(AggregationError::MalformedProof);
+    }
+    if inputs.iter().any(|x| x.len() + 1 != vk.gamma_abc_g1.len()) {
+        return Err(AggregationError::InputLength);
+    }
+
+    let mut transcript = transcript(vk, inputs);
+    transcript.append(&(proof.com_ab, proof.com_c));
+    let r: E::ScalarField = transcript.challenge();
+    transcript.append(&(proof.z_ab, proof.z_c));
+
+    // The combined Groth16 equation, with sum_i r^i IC(x_i) computed per input.
+    let r_powers = powers(r, n);
+    let mut ic_scalars = vec![E::ScalarField::zero(); vk.gamma_abc_g1.len()];
+    for (x, r_i) in inputs.iter().zip(&r_powers) {
+        ic_scalars[0] += r_i;
+        for (s, x_j) in ic_scalars[1..].iter_mut().zip(x.iter()) {
+            *s += *r_i * x_j;
+        }
+    }
+    let groth16 = E::multi_pairing(
+        [(vk.alpha_g1 * ic_scalars[0]).into_affine(), E::G1::msm_unchecked(&vk.gamma_abc_g1, &ic_scalars).into_affine(), proof.z_c],
+        [vk.beta_g2, vk.gamma_g2, vk.delta_g2],
+    );
+    if groth16 != proof.z_ab {
+        return Ok(false);
+    }
+
+    let (mut com_ab, mut com_c, mut z_ab, mut z_c) = (proof.com_ab, proof.com_c, proof.z_ab, proof.z_c.into_group());
+    let mut s = E::ScalarField::one();
+    let mut challenges = Vec::new();
+    for round in &proof.rounds {
+        transcript.append(round);
+        let x: E::ScalarField = transcript.challenge();
+        let x_inv = x.inverse().expect("challenges are non-zero");
+        com_ab = com_ab.fold(&round.com_ab.0, &round.com_ab.1, x, x_inv);
+        com_c = com_c.fold(&round.com_c.0, &round.com_c.1, x, x_inv);
+        z_ab = z_ab + round.z_ab.0 * x + round.z_ab.1 * x_inv;
+        z_c += round.z_c.0 * x + round.z_c.1 * x_inv;
+        s *= E::ScalarField::one() + x_inv;
+        challenges.push((x, x_inv));
+    }
+    transcript.append(&(proof.a, proof.b, proof.c, proof.v, proof.w));
+    let z: E::ScalarField = transcript.challenge();
+
+    // The folded elements against the folded commitments and inner products.
+    let (v, w) = (proof.v, proof.w);
+    let folded = z_ab == E::pairing(proof.a, proof.b)
+        && z_c == proof.c * s
+        && com_ab.a == commit_ab(&[proof.a], &[proof.b], &[v.0], &[w.0])
+        && com_ab.b == commit_ab(&[proof.a], &[proof.b], &[v.1], &[w.1])
+        && com_c.a == inner(&[proof.c], &[v.0])
+        && com_c.b == inner(&[proof.c], &[v.1]);
+    if !folded {
+        return Ok(false);
+    }
+
+    // KZG: e([s - z]_1, pi) = e(g, v - [f_v(z)]_2) and e(pi, [s - z]_2) = e(w - [f_w(z)]_1, h), s = a or b.
+    let r_inv = r.inverse().expect("challenges are non-zero");
+    let (fv, fw) = (f_v(z, r_inv, &challenges, n), f_w(z, &challenges, n));
+    let (g, h) = (E::G1::generator(), E::G2::generator());
+    let opens_v = |s_g1: E::G1Affine, pi: E::G2Affine, v: E::G2Affine| {
+        E::multi_pairing([(s_g1 - g * z).into_affine(), (-g).into_affine()], [pi, (v - h * fv).into_affine()]).is_zero()
+    };
+    let opens_w = |s_g2: E::G2Affine, pi: E::G1Affine, w: E::G1Affine| {
+        E::multi_pairing([pi, (g * fw - w).into_affine()], [(s_g2 - h * z).into_affine(), h.into_affine()]).is_zero()
+    };
+    Ok(opens_v(key.g1_a, proof.v_opening.0, v.0)
+        && opens_v(key.g1_b, proof.v_opening.1, v.1)
+        && opens_w(key.g2_a, proof.w_opening.0, w.0)
+        && opens_w(key.g2_b, proof.w_opening.1, w.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::CubicDemoCircuit;
+    use crate::groth16::{create_proof, generate_parameters, ProvingKey};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::test_rng;
+
+    type E = Bls12_381;
+
+    fn proofs(pk: &ProvingKey<E>, n: u64) -> Vec<(Vec<Fr>, Proof<E>)> {
+        let mut rng = test_rng();
+        (0..n)
+            .map(|x| {
+                let x = Fr::from(x + 1);
+                let proof = create_proof(pk, CubicDemoCircuit { x: Some(x) }, &mut rng).unwrap();
+                (vec![x * x * x + x + Fr::from(5u64)], proof)
+            })
+            .collect()
+    }
+
+    fn items(proofs: &[(Vec<Fr>, Proof<E>)]) -> Vec<(&[Fr], &Proof<E>)> {
+        proofs.iter().map(|(x, p)| (x.as_slice(), p)).collect()
+    }
+
+    fn inputs(proofs: &[(Vec<Fr>, Proof<E>)]) -> Vec<&[Fr]> {
+        proofs.iter().map(|(x, _)| x.as_slice()).collect()
+    }
+
+    fn setup() -> (ProvingKey<E>, AggregationKey<E>) {
+        let mut rng = test_rng();
+        let pk = generate_parameters::<E, _, _>(CubicDemoCircuit::<Fr> { x: None }, &mut rng).unwrap();
+        (pk, setup_aggregation(16, &mut rng))
+    }
+
+    #[test]
+    fn folding_polynomials_match_their_coefficients() {
+        let mut rng = test_rng();
+        let challenges: Vec<(Fr, Fr)> = (0..3).map(|_| Fr::rand(&mut rng)).map(|x| (x, x.inverse().unwrap())).collect();
+        let (r_inv, point) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        let x_invs: Vec<_> = challenges.iter().map(|c| c.1).collect();
+        let coeffs: Vec<_> = folding_coefficients(&x_invs).iter().zip(powers(r_inv, 8)).map(|(e, p)| *e * p).collect();
+        let poly = DensePolynomial::from_coefficients_vec(coeffs.clone());
+        assert_eq!(poly.evaluate(&point), f_v(point, r_inv, &challenges, 8));
+
+        let q = DensePolynomial::from_coefficients_vec(kzg_quotient(&coeffs, point));
+        let x = Fr::rand(&mut rng);
+        assert_eq!(q.evaluate(&x) * (x - point), poly.evaluate(&x) - poly.evaluate(&point));
+    }
+
+    #[test]
+    fn aggregate_proofs_verify_for_every_size() {
+        let (pk, key) = setup();
+        let all = proofs(&pk, 16);
+        for n in [1, 2, 4, 8, 16] {
+            let proof = aggregate_proofs(&key, &pk.vk, &items(&all[..n])).unwrap();
+            assert_eq!(proof.rounds.len(), n.trailing_zeros() as usize);
+            assert!(verify_aggregate_proof(&key.verifier_key(), &pk.vk, &inputs(&all[..n]), &proof).unwrap(), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn wrong_inputs_or_tampering_are_rejected() {
+        let (pk, key) = setup();
+        let mut all = proofs(&pk, 8);
+        let vkey = key.verifier_key();
+        let proof = aggregate_proofs(&key, &pk.vk, &items(&all)).unwrap();
+
+        // The outputs of two proofs exchanged.
+        let mut swapped = inputs(&all);
+        swapped.swap(2, 3);
+        assert!(!verify_aggregate_proof(&vkey, &pk.vk, &swapped, &proof).unwrap());
+
+        let mut bad = proof.clone();
+        bad.z_c = (bad.z_c + bad.c).into_affine();
+        assert!(!verify_aggregate_proof(&vkey, &pk.vk, &inputs(&all), &bad).unwrap());
+        let mut bad = proof.clone();
+        bad.rounds[1].z_c.0 = bad.rounds[1].z_c.1;
+        assert!(!verify_aggregate_proof(&vkey, &pk.vk, &inputs(&all), &bad).unwrap());
+        let mut bad = proof.clone();
+        bad.v_opening.1 = bad.v_opening.0;
+        assert!(!verify_aggregate_proof(&vkey, &pk.vk, &inputs(&all), &bad).unwrap());
+        let mut bad = proof.clone();
+        bad.rounds.pop();
+        assert_eq!(verify_aggregate_proof(&vkey, &pk.vk, &inputs(&all), &bad), Err(AggregationError::MalformedProof));
+
+        // One invalid proof among valid ones cannot be aggregated into a valid aggregate.
+        all[5].0[0] += Fr::one();
+        let proof = aggregate_proofs(&key, &pk.vk, &items(&all)).unwrap();
+        assert!(!verify_aggregate_proof(&vkey, &pk.vk, &inputs(&all), &proof).unwrap());
+    }
+
+    #[test]
+    fn sizes_are_checked() {
+        let (pk, key) = setup();
+        let all = proofs(&pk, 3);
+        assert_eq!(aggregate_proofs(&key, &pk.vk, &items(&all)), Err(AggregationError::NotPowerOfTwo(3)));
+        let small = setup_aggregation::<E, _>(2, &mut test_rng());
+        assert_eq!(
+            aggregate_proofs(&small, &pk.vk, &items(&proofs(&pk, 4))),
+            Err(AggregationError::KeyTooShort { needed: 4, available: 2 })
+        );
+    }
+}
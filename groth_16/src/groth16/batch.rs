@@ -0,0 +1,118 @@
+// Batch verification of many proofs under one verifying key.
+//
+// Proof i is weighted by a random r_i and all verification equations are
+// checked at once:
+//
+//     prod_i e(r_i A_i, B_i) = e(alpha, beta)^(sum r_i) e(sum r_i IC(x_i), gamma) e(sum r_i C_i, delta)
+//
+// n + 3 Miller loops and one final exponentiation instead of 4n loops and n
+// exponentiations. Without the weights, errors in two proofs could cancel out;
+// with 128-bit weights an invalid batch passes with probability about 2^-128.
+// A failed batch does not say which proof is bad; fall back to `verify_proof`.
+//
+// The bound holds only if the weights are unpredictable to whoever made the
+// proofs, hence the `CryptoRng` bound: with a known seed a forger can make the
+// errors cancel.
+
+use ark_ec::pairing::Pairing;
+use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_relations::r1cs::SynthesisError;
+use ark_std::rand::{CryptoRng, Rng};
+use ark_std::Zero;
+
+use super::{Proof, VerifyingKey};
+
+pub fn batch_verify<E: Pairing, R: Rng + CryptoRng>(
+    vk: &VerifyingKey<E>,
+    items: &[(&[E::ScalarField], &Proof<E>)],
+    rng: &mut R,
+) -> Result<bool, SynthesisError> {
+    if items.iter().any(|(inputs, _)| inputs.len() + 1 != vk.gamma_abc_g1.len()) {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+    if items.is_empty() {
+        return Ok(true);
+    }
+
+    // sum r_i IC(x_i) = (sum r_i) IC_0 + sum_j (sum_i r_i x_ij) IC_j.
+    let mut ic_scalars = vec![E::ScalarField::zero(); vk.gamma_abc_g1.len()];
+    let mut c_sum = E::G1::zero();
+    let mut g1 = Vec::with_capacity(items.len() + 3);
+    let mut g2 = Vec::with_capacity(items.len() + 3);
+    for (inputs, proof) in items {
+        let r = E::ScalarField::from(rng.gen::<u128>());
+        ic_scalars[0] += r;
+        for (s, x) in ic_scalars[1..].iter_mut().zip(inputs.iter()) {
+            *s += r * x;
+        }
+        c_sum += proof.c * r;
+        g1.push(proof.a * r);
+        g2.push(proof.b);
+    }
+    g1.push(-(vk.alpha_g1 * ic_scalars[0]));
+    g1.push(-E::G1::msm_unchecked(&vk.gamma_abc_g1, &ic_scalars));
+    g1.push(-c_sum);
+    g2.extend([vk.beta_g2, vk.gamma_g2, vk.delta_g2]);
+
+    Ok(E::multi_pairing(E::G1::normalize_batch(&g1), g2).is_zero())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::CubicDemoCircuit;
+    use crate::groth16::{create_proof, generate_parameters, ProvingKey};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::rand::{rngs::StdRng, RngCore, SeedableRng};
+    use ark_std::test_rng;
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(test_rng().next_u64())
+    }
+
+    fn proofs(pk: &ProvingKey<Bls12_381>, n: u64) -> Vec<(Vec<Fr>, Proof<Bls12_381>)> {
+        let mut rng = test_rng();
+        (0..n)
+            .map(|x| {
+                let x = Fr::from(x);
+                let proof = create_proof(pk, CubicDemoCircuit { x: Some(x) }, &mut rng).unwrap();
+                (vec![x * x * x + x + Fr::from(5u64)], proof)
+            })
+            .collect()
+    }
+
+    fn items(proofs: &[(Vec<Fr>, Proof<Bls12_381>)]) -> Vec<(&[Fr], &Proof<Bls12_381>)> {
+        proofs.iter().map(|(inputs, proof)| (inputs.as_slice(), proof)).collect()
+    }
+
+    #[test]
+    fn valid_batch_verifies() {
+        let mut rng = rng();
+        let pk = generate_parameters::<Bls12_381, _, _>(CubicDemoCircuit::<Fr> { x: None }, &mut rng).unwrap();
+        let proofs = proofs(&pk, 8);
+        assert!(batch_verify(&pk.vk, &items(&proofs), &mut rng).unwrap());
+        assert!(batch_verify(&pk.vk, &items(&proofs[..1]), &mut rng).unwrap());
+        assert!(batch_verify(&pk.vk, &[], &mut rng).unwrap());
+    }
+
+    #[test]
+    fn one_bad_proof_fails_the_batch() {
+        let mut rng = rng();
+        let pk = generate_parameters::<Bls12_381, _, _>(CubicDemoCircuit::<Fr> { x: None }, &mut rng).unwrap();
+        let mut proofs = proofs(&pk, 8);
+        // The x = 3 and x = 4 proofs with their outputs exchanged, as in `main`.
+        let (out3, out4) = (proofs[3].0.clone(), proofs[4].0.clone());
+        proofs[3].0 = out4;
+        assert!(!batch_verify(&pk.vk, &items(&proofs), &mut rng).unwrap());
+        proofs[4].0 = out3;
+        assert!(!batch_verify(&pk.vk, &items(&proofs), &mut rng).unwrap());
+
+        let mut proofs = super::tests::proofs(&pk, 4);
+        proofs[0].1.c = (proofs[0].1.c + proofs[0].1.a).into_affine();
+        assert!(!batch_verify(&pk.vk, &items(&proofs), &mut rng).unwrap());
+        assert!(batch_verify(&pk.vk, &items(&proofs[1..]), &mut rng).unwrap());
+
+        let wrong_length: &[Fr] = &[];
+        assert!(batch_verify(&pk.vk, &[(wrong_length, &proofs[1].1)], &mut rng).is_err());
+    }
+}
@@ -0,0 +1,272 @@
+// A multi-party setup ceremony for Groth16 (Bowe, Gabizon, Miers, "Scalable
+// Multi-party Computation for zk-SNARK Parameters", 2017).
+//
+// `generate_parameters` needs someone to know (tau, alpha, beta, gamma, delta)
+// and then forget them. Here the secrets are products of contributions:
+//
+//     phase 1 (`phase1`)  powers of tau, alpha and beta, for any circuit up to a size
+//     phase 2 (`phase2`)  delta, for one circuit (gamma is fixed to 1)
+//
+// Contributors take turns multiplying the current parameters by their own
+// random share and then discard it. The result is sound as long as a single
+// contributor in each phase was honest. Every contribution carries a
+// `KnowledgeProof` of its share, bound to the parameters it started from, and
+// anyone can replay the transcript and check each step with pairings.
+
+pub mod phase1;
+pub mod phase2;
+
+use std::fmt;
+
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, PrimeGroup};
+use ark_ff::PrimeField;
+use ark_relations::r1cs::SynthesisError;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::{rngs::StdRng, Rng, SeedableRng};
+use ark_std::{UniformRand, Zero};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CeremonyError {
+    // The proof of knowledge of contribution i (counted from 1) does not verify.
+    InvalidProof(usize),
+    // Contribution i is not its predecessor multiplied by the proven share.
+    InconsistentUpdate(usize),
+    // The powers of tau are too few for the circuit's QAP domain.
+    TooFewPowers { needed: usize, available: usize },
+    // The phase-2 starting point was not derived from this circuit and phase 1.
+    WrongInitialParameters,
+    // A share of zero, which would erase the secret instead of updating it.
+    ZeroContribution,
+    Synthesis(SynthesisError),
+}
+
+impl fmt::Display for CeremonyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CeremonyError::InvalidProof(i) => write!(f, "contribution {} has an invalid proof of knowledge", i),
+            CeremonyError::InconsistentUpdate(i) => write!(f, "contribution {} is not a consistent update", i),
+            CeremonyError::TooFewPowers { needed, available } => {
+                write!(f, "the circuit needs {} powers of tau, the ceremony has {}", needed, available)
+            }
+            CeremonyError::WrongInitialParameters => write!(f, "phase 2 does not start from this circuit and phase 1"),
+            CeremonyError::ZeroContribution => write!(f, "a contribution's share must be non-zero"),
+            CeremonyError::Synthesis(e) => write!(f, "synthesis error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CeremonyError {}
+
+impl From<SynthesisError> for CeremonyError {
+    fn from(e: SynthesisError) -> Self {
+        CeremonyError::Synthesis(e)
+    }
+}
+
+// Proof that the contributor knows x behind [x]_1 and [x]_2: a Schnorr proof
+// for [x]_1, made non-interactive with a hash of the parameters being updated,
+// so it cannot be replayed elsewhere in the transcript. A pairing ties [x]_2
+// to the same x.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct KnowledgeProof<E: Pairing> {
+    pub x_g1: E::G1Affine,
+    pub x_g2: E::G2Affine,
+    // R = [k]_1 and z = k + c x, c = H(context, [x]_1, R).
+    pub commitment: E::G1Affine,
+    pub response: E::ScalarField,
+}
+
+impl<E: Pairing> KnowledgeProof<E> {
+    pub fn new<R: Rng>(x: E::ScalarField, context: &[u8], rng: &mut R) -> Self {
+        let x_g1 = (E::G1::generator() * x).into_affine();
+        let k = E::ScalarField::rand(rng);
+        let commitment = (E::G1::generator() * k).into_affine();
+        let c = challenge::<E>(context, &x_g1, &commitment);
+        KnowledgeProof { x_g1, x_g2: (E::G2::generator() * x).into_affine(), commitment, response: k + c * x }
+    }
+
+    pub fn verify(&self, context: &[u8]) -> bool {
+        let c = challenge::<E>(context, &self.x_g1, &self.commitment);
+        !self.x_g1.is_zero()
+            && E::G1::generator() * self.response == self.commitment + self.x_g1 * c
+            && same_ratio::<E>((E::G1Affine::generator(), self.x_g1), (E::G2Affine::generator(), self.x_g2))
+    }
+}
+
+fn challenge<E: Pairing>(context: &[u8], x_g1: &E::G1Affine, commitment: &E::G1Affine) -> E::ScalarField {
+    let mut points = Vec::new();
+    x_g1.serialize_compressed(&mut points).expect("writing to a vector");
+    commitment.serialize_compressed(&mut points).expect("writing to a vector");
+    // 512 bits, so that reducing modulo r leaves no noticeable bias.
+    let wide: Vec<u8> = [0u8, 1]
+        .iter()
+        .flat_map(|i| Sha256::new().chain_update(b"groth16-ceremony-pok").chain_update([*i]).chain_update(context).chain_update(&points).finalize())
+        .collect();
+    E::ScalarField::from_le_bytes_mod_order(&wide)
+}
+
+// SHA-256 of the compressed encoding: what a contribution's proofs are bound to.
+pub fn digest<T: CanonicalSerialize>(value: &T) -> [u8; 32] {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).expect("writing to a vector");
+    Sha256::digest(bytes).into()
+}
+
+// Whether a -> b in G1 and c -> d in G2 are multiplication by the same scalar:
+// e(b, c) = e(a, d).
+pub(crate) fn same_ratio<E: Pairing>(g1: (E::G1Affine, E::G1Affine), g2: (E::G2Affine, E::G2Affine)) -> bool {
+    E::multi_pairing([g1.1, (-g1.0.into_group()).into_affine()], [g2.0, g2.1]).is_zero()
+}
+
+// Random combinations (sum r_i p_i, sum r_i q_i) of the pairs (p_i, q_i), so
+// that one `same_ratio` check covers them all: a single pair with a different
+// ratio makes the check fail except with probability 1/r. The r_i come from
+// `seed`, a digest of the data being checked, so they are fixed only after the
+// contributor has committed to it.
+pub(crate) fn combine_pairs<G: CurveGroup>(pairs: &[(G::Affine, G::Affine)], seed: [u8; 32]) -> (G::Affine, G::Affine) {
+    let mut rng = StdRng::from_seed(seed);
+    let r: Vec<G::ScalarField> = (0..pairs.len()).map(|_| G::ScalarField::rand(&mut rng)).collect();
+    let (p, q): (Vec<_>, Vec<_>) = pairs.iter().copied().unzip();
+    (G::msm_unchecked(&p, &r).into_affine(), G::msm_unchecked(&q, &r).into_affine())
+}
+
+// The pairs (v_i, v_{i+1}): a sequence of successive powers has the same ratio throughout.
+pub(crate) fn consecutive<A: Copy>(v: &[A]) -> Vec<(A, A)> {
+    v.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::phase1::Transcript;
+    use super::phase2::Phase2;
+    use super::*;
+    use crate::cubic::CubicDemoCircuit;
+    use crate::groth16::{create_proof, generate_parameters_with_toxic_waste, verify_proof, ToxicWaste};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::Field;
+    use ark_std::test_rng;
+
+    fn circuit(x: Option<u64>) -> CubicDemoCircuit<Fr> {
+        CubicDemoCircuit { x: x.map(Fr::from) }
+    }
+
+    #[test]
+    fn knowledge_proofs_are_bound_to_their_context() {
+        let mut rng = test_rng();
+        let proof = KnowledgeProof::<Bls12_381>::new(Fr::from(7u64), b"context", &mut rng);
+        assert!(proof.verify(b"context"));
+        assert!(!proof.verify(b"another context"));
+
+        let mut mismatched = proof.clone();
+        mismatched.x_g2 = (<Bls12_381 as Pairing>::G2::generator() * Fr::from(8u64)).into_affine();
+        assert!(!mismatched.verify(b"context"));
+        assert!(!KnowledgeProof::<Bls12_381>::new(Fr::zero(), b"context", &mut rng).verify(b"context"));
+    }
+
+    // Three contributors in each phase, then a proof with the resulting keys.
+    #[test]
+    fn end_to_end_ceremony() {
+        let mut rng = test_rng();
+        let mut transcript = Transcript::<Bls12_381>::new(8);
+        for _ in 0..3 {
+            transcript.contribute(&mut rng);
+        }
+        transcript.verify().unwrap();
+
+        let mut phase2 = Phase2::new(transcript.latest(), circuit(None)).unwrap();
+        for _ in 0..3 {
+            phase2.contribute(&mut rng);
+        }
+        phase2.verify(transcript.latest(), circuit(None)).unwrap();
+
+        let pk = phase2.parameters();
+        let proof = create_proof(pk, circuit(Some(3)), &mut rng).unwrap();
+        assert!(verify_proof(&pk.vk, &[Fr::from(35u64)], &proof).unwrap());
+        assert!(!verify_proof(&pk.vk, &[Fr::from(73u64)], &proof).unwrap());
+    }
+
+    // With the contributors' shares known, the keys are exactly those of a
+    // trusted setup with the products as toxic waste (and gamma = 1).
+    #[test]
+    fn ceremony_matches_setup_with_combined_toxic_waste() {
+        let mut rng = test_rng();
+        let shares: Vec<[Fr; 4]> = (0..2).map(|_| [(); 4].map(|_| Fr::rand(&mut rng))).collect();
+
+        let mut transcript = Transcript::<Bls12_381>::new(8);
+        for [tau, alpha, beta, _] in &shares {
+            transcript.contribute_with(*tau, *alpha, *beta, &mut rng).unwrap();
+        }
+        let mut phase2 = Phase2::new(transcript.latest(), circuit(None)).unwrap();
+        for [.., delta] in &shares {
+            phase2.contribute_with(*delta, &mut rng).unwrap();
+        }
+        phase2.verify(transcript.latest(), circuit(None)).unwrap();
+
+        let product = |k: usize| shares.iter().map(|s| s[k]).product::<Fr>();
+        let waste = ToxicWaste { tau: product(0), alpha: product(1), beta: product(2), gamma: Fr::ONE, delta: product(3) };
+        assert_eq!(phase2.parameters(), &generate_parameters_with_toxic_waste::<Bls12_381, _>(circuit(None), &waste).unwrap());
+    }
+
+    #[test]
+    fn tampered_transcripts_are_rejected() {
+        let mut rng = test_rng();
+        let mut transcript = Transcript::<Bls12_381>::new(8);
+        for _ in 0..3 {
+            transcript.contribute(&mut rng);
+        }
+
+        // A power of tau out of sequence.
+        let mut bad = transcript.clone();
+        let p = &mut bad.contributions[1].accumulator.tau_g1[3];
+        *p = (*p + <Bls12_381 as Pairing>::G1::generator()).into_affine();
+        assert_eq!(bad.verify(), Err(CeremonyError::InconsistentUpdate(2)));
+
+        // A contribution that does not build on its predecessor: a proof
+        // copied from elsewhere no longer matches.
+        let mut bad = transcript.clone();
+        bad.contributions.swap(1, 2);
+        assert_eq!(bad.verify(), Err(CeremonyError::InvalidProof(2)));
+
+        // A zero share is refused when it is contributed, not later by `verify`.
+        for shares in [[Fr::zero(), Fr::ONE, Fr::ONE], [Fr::ONE, Fr::zero(), Fr::ONE], [Fr::ONE, Fr::ONE, Fr::zero()]] {
+            let mut zero = transcript.clone();
+            assert_eq!(zero.contribute_with(shares[0], shares[1], shares[2], &mut rng), Err(CeremonyError::ZeroContribution));
+            assert_eq!(zero, transcript);
+        }
+
+        // Resetting the parameters to the start would erase the honest contributors.
+        let mut bad = transcript.clone();
+        bad.contributions[2].accumulator = bad.initial.clone();
+        assert!(bad.verify().is_err());
+
+        let mut phase2 = Phase2::new(transcript.latest(), circuit(None)).unwrap();
+        phase2.contribute(&mut rng);
+        phase2.contribute(&mut rng);
+
+        let mut bad = phase2.clone();
+        let l = &mut bad.contributions[0].parameters.l_query[0];
+        *l = (*l + <Bls12_381 as Pairing>::G1::generator()).into_affine();
+        assert_eq!(bad.verify(transcript.latest(), circuit(None)), Err(CeremonyError::InconsistentUpdate(1)));
+
+        // Phase 2 must not change anything but delta, L and H.
+        let mut bad = phase2.clone();
+        bad.contributions[1].parameters.vk.alpha_g1 = bad.contributions[1].parameters.beta_g1;
+        assert_eq!(bad.verify(transcript.latest(), circuit(None)), Err(CeremonyError::InconsistentUpdate(2)));
+
+        let mut bad = phase2.clone();
+        bad.initial.h_query.pop();
+        assert_eq!(bad.verify(transcript.latest(), circuit(None)), Err(CeremonyError::WrongInitialParameters));
+
+        // delta = 0 has no inverse; it is refused and nothing is added.
+        let mut zero = phase2.clone();
+        assert_eq!(zero.contribute_with(Fr::zero(), &mut rng), Err(CeremonyError::ZeroContribution));
+        assert_eq!(zero, phase2);
+
+        assert!(matches!(
+            Phase2::new(&Transcript::<Bls12_381>::new(2).initial, circuit(None)),
+            Err(CeremonyError::TooFewPowers { needed: 8, available: 2 })
+        ));
+    }
+}
@@ -0,0 +1,194 @@
+// Phase 1: Powers of Tau.
+//
+// For a maximum QAP domain size n the accumulator holds
+//
+//     [tau^i]_1         i = 0..2n-2      (the h query needs tau^i Z_H(tau), degree up to 2n-2)
+//     [tau^i]_2         i = 0..n-1
+//     [alpha tau^i]_1   i = 0..n-1
+//     [beta tau^i]_1    i = 0..n-1
+//     [beta]_2
+//
+// and a contribution (t, a, b) maps tau -> t tau, alpha -> a alpha, beta -> b beta
+// by scaling each element. It is independent of the circuit, so one ceremony
+// serves every circuit whose domain fits.
+
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::Rng;
+use ark_std::{One, UniformRand, Zero};
+
+use super::{combine_pairs, consecutive, digest, same_ratio, CeremonyError, KnowledgeProof};
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Accumulator<E: Pairing> {
+    pub tau_g1: Vec<E::G1Affine>,
+    pub tau_g2: Vec<E::G2Affine>,
+    pub alpha_tau_g1: Vec<E::G1Affine>,
+    pub beta_tau_g1: Vec<E::G1Affine>,
+    pub beta_g2: E::G2Affine,
+}
+
+impl<E: Pairing> Accumulator<E> {
+    // The starting point tau = alpha = beta = 1, for domains of up to `size` points.
+    pub fn new(size: usize) -> Self {
+        assert!(size >= 2, "the domain needs at least two points");
+        let (g1, g2) = (E::G1Affine::generator(), E::G2Affine::generator());
+        Accumulator {
+            tau_g1: vec![g1; 2 * size - 1],
+            tau_g2: vec![g2; size],
+            alpha_tau_g1: vec![g1; size],
+            beta_tau_g1: vec![g1; size],
+            beta_g2: g2,
+        }
+    }
+
+    // The largest QAP domain these powers support.
+    pub fn size(&self) -> usize {
+        self.tau_g2.len()
+    }
+
+    fn update(&self, tau: E::ScalarField, alpha: E::ScalarField, beta: E::ScalarField) -> Self {
+        let scale = |points: &[E::G1Affine], first: E::ScalarField| {
+            let mut factor = first;
+            let scaled: Vec<E::G1> = points
+                .iter()
+                .map(|p| {
+                    let q = *p * factor;
+                    factor *= tau;
+                    q
+                })
+                .collect();
+            E::G1::normalize_batch(&scaled)
+        };
+        let mut factor = E::ScalarField::one();
+        let tau_g2: Vec<E::G2> = self
+            .tau_g2
+            .iter()
+            .map(|p| {
+                let q = *p * factor;
+                factor *= tau;
+                q
+            })
+            .collect();
+        Accumulator {
+            tau_g1: scale(&self.tau_g1, E::ScalarField::one()),
+            tau_g2: E::G2::normalize_batch(&tau_g2),
+            alpha_tau_g1: scale(&self.alpha_tau_g1, alpha),
+            beta_tau_g1: scale(&self.beta_tau_g1, beta),
+            beta_g2: (self.beta_g2 * beta).into_affine(),
+        }
+    }
+
+    // Every list holds successive powers of one tau, consistent across groups.
+    fn is_well_formed(&self, size: usize) -> bool {
+        let (g1, g2) = (E::G1Affine::generator(), E::G2Affine::generator());
+        let seed = digest(self);
+        self.tau_g1.len() == 2 * size - 1
+            && self.tau_g2.len() == size
+            && self.alpha_tau_g1.len() == size
+            && self.beta_tau_g1.len() == size
+            && self.tau_g1[0] == g1
+            && self.tau_g2[0] == g2
+            && !self.tau_g1[1].is_zero()
+            && same_ratio::<E>(combine_pairs::<E::G1>(&consecutive(&self.tau_g1), seed), (g2, self.tau_g2[1]))
+            && same_ratio::<E>((g1, self.tau_g1[1]), combine_pairs::<E::G2>(&consecutive(&self.tau_g2), seed))
+            && same_ratio::<E>(combine_pairs::<E::G1>(&consecutive(&self.alpha_tau_g1), seed), (g2, self.tau_g2[1]))
+            && same_ratio::<E>(combine_pairs::<E::G1>(&consecutive(&self.beta_tau_g1), seed), (g2, self.tau_g2[1]))
+            && same_ratio::<E>((g1, self.beta_tau_g1[0]), (g2, self.beta_g2))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Contribution<E: Pairing> {
+    pub accumulator: Accumulator<E>,
+    pub tau: KnowledgeProof<E>,
+    pub alpha: KnowledgeProof<E>,
+    pub beta: KnowledgeProof<E>,
+}
+
+// The proofs of one contribution are bound to the digest of the accumulator it
+// updates, and to the secret they are about.
+fn context(label: &[u8], previous: &[u8; 32]) -> Vec<u8> {
+    [label, &previous[..]].concat()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Transcript<E: Pairing> {
+    pub initial: Accumulator<E>,
+    pub contributions: Vec<Contribution<E>>,
+}
+
+impl<E: Pairing> Transcript<E> {
+    pub fn new(size: usize) -> Self {
+        Transcript { initial: Accumulator::new(size), contributions: Vec::new() }
+    }
+
+    pub fn latest(&self) -> &Accumulator<E> {
+        self.contributions.last().map_or(&self.initial, |c| &c.accumulator)
+    }
+
+    // Adds a contribution with fresh random shares, which are dropped on return.
+    pub fn contribute<R: Rng>(&mut self, rng: &mut R) {
+        let mut share = || loop {
+            let x = E::ScalarField::rand(rng);
+            if !x.is_zero() {
+                return x;
+            }
+        };
+        let (tau, alpha, beta) = (share(), share(), share());
+        self.contribute_with(tau, alpha, beta, rng).expect("shares are non-zero");
+    }
+
+    pub fn contribute_with<R: Rng>(
+        &mut self,
+        tau: E::ScalarField,
+        alpha: E::ScalarField,
+        beta: E::ScalarField,
+        rng: &mut R,
+    ) -> Result<(), CeremonyError> {
+        if tau.is_zero() || alpha.is_zero() || beta.is_zero() {
+            return Err(CeremonyError::ZeroContribution);
+        }
+        let previous = self.latest();
+        let d = digest(previous);
+        let contribution = Contribution {
+            accumulator: previous.update(tau, alpha, beta),
+            tau: KnowledgeProof::new(tau, &context(b"tau", &d), rng),
+            alpha: KnowledgeProof::new(alpha, &context(b"alpha", &d), rng),
+            beta: KnowledgeProof::new(beta, &context(b"beta", &d), rng),
+        };
+        self.contributions.push(contribution);
+        Ok(())
+    }
+
+    // Replays the whole transcript.
+    pub fn verify(&self) -> Result<(), CeremonyError> {
+        let size = self.initial.size();
+        if self.initial != Accumulator::new(size) {
+            return Err(CeremonyError::InconsistentUpdate(0));
+        }
+        let g2 = E::G2Affine::generator();
+        let mut previous = &self.initial;
+        for (i, c) in self.contributions.iter().enumerate() {
+            let d = digest(previous);
+            if !(c.tau.verify(&context(b"tau", &d))
+                && c.alpha.verify(&context(b"alpha", &d))
+                && c.beta.verify(&context(b"beta", &d)))
+            {
+                return Err(CeremonyError::InvalidProof(i + 1));
+            }
+            let next = &c.accumulator;
+            if !(next.is_well_formed(size)
+                && same_ratio::<E>((previous.tau_g1[1], next.tau_g1[1]), (g2, c.tau.x_g2))
+                && same_ratio::<E>((previous.alpha_tau_g1[0], next.alpha_tau_g1[0]), (g2, c.alpha.x_g2))
+                && same_ratio::<E>((previous.beta_tau_g1[0], next.beta_tau_g1[0]), (g2, c.beta.x_g2)))
+            {
+                return Err(CeremonyError::InconsistentUpdate(i + 1));
+            }
+            previous = next;
+        }
+        Ok(())
+    }
+}
+
@@ -0,0 +1,162 @@
+// Phase 2: the circuit-specific part.
+//
+// The QAP columns are linear in the Lagrange basis L_i(tau) of the domain, and
+// L_i(tau) is a fixed combination of the powers tau^j: an inverse FFT over
+// group elements turns [tau^j] into [L_i(tau)] without anyone knowing tau.
+// That gives every key element except those divided by delta, which starts
+// at 1 (as does gamma). Contributors then multiply delta by a share d and
+// divide the L and H queries by it; nothing else may change.
+
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::Field;
+use ark_poly::EvaluationDomain;
+use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError, SynthesisMode};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::Rng;
+use ark_std::{UniformRand, Zero};
+
+use super::phase1::Accumulator;
+use super::{combine_pairs, digest, same_ratio, CeremonyError, KnowledgeProof};
+use crate::groth16::qap::{self, combine_columns};
+use crate::groth16::{synthesize, ProvingKey, VerifyingKey};
+
+// The keys with delta = gamma = 1, derived from the phase-1 result.
+pub fn initial_parameters<E: Pairing, C: ConstraintSynthesizer<E::ScalarField>>(
+    accumulator: &Accumulator<E>,
+    circuit: C,
+) -> Result<ProvingKey<E>, CeremonyError> {
+    let cs = synthesize(circuit, SynthesisMode::Setup)?;
+    let matrices = cs.to_matrices().ok_or(SynthesisError::MissingCS)?;
+    let domain = qap::domain(&matrices)?;
+    let n = domain.size();
+    if n > accumulator.size() {
+        return Err(CeremonyError::TooFewPowers { needed: n, available: accumulator.size() });
+    }
+
+    // [L_i(tau)], [alpha L_i(tau)] and [beta L_i(tau)].
+    let lagrange_g1 = |powers: &[E::G1Affine]| domain.ifft(&powers[..n].iter().map(|p| p.into_group()).collect::<Vec<_>>());
+    let l_g1 = lagrange_g1(&accumulator.tau_g1);
+    let l_g2 = domain.ifft(&accumulator.tau_g2[..n].iter().map(|p| p.into_group()).collect::<Vec<_>>());
+    let (u, v, w) = combine_columns(&matrices, &l_g1);
+    let (beta_u, _, _) = combine_columns(&matrices, &lagrange_g1(&accumulator.beta_tau_g1));
+    let (_, alpha_v, _) = combine_columns(&matrices, &lagrange_g1(&accumulator.alpha_tau_g1));
+    let (_, v_g2, _) = combine_columns(&matrices, &l_g2);
+
+    let abc: Vec<E::G1> = (0..u.len()).map(|j| beta_u[j] + alpha_v[j] + w[j]).collect();
+    // tau^i Z_H(tau) = tau^(i+n) - tau^i.
+    let h: Vec<E::G1> = (0..n - 1).map(|i| accumulator.tau_g1[i + n] - accumulator.tau_g1[i]).collect();
+    let num_instance = matrices.num_instance_variables;
+
+    let (g1, g2) = (E::G1Affine::generator(), E::G2Affine::generator());
+    Ok(ProvingKey {
+        vk: VerifyingKey {
+            alpha_g1: accumulator.alpha_tau_g1[0],
+            beta_g2: accumulator.beta_g2,
+            gamma_g2: g2,
+            delta_g2: g2,
+            gamma_abc_g1: E::G1::normalize_batch(&abc[..num_instance]),
+        },
+        beta_g1: accumulator.beta_tau_g1[0],
+        delta_g1: g1,
+        a_query: E::G1::normalize_batch(&u),
+        b_g1_query: E::G1::normalize_batch(&v),
+        b_g2_query: E::G2::normalize_batch(&v_g2),
+        h_query: E::G1::normalize_batch(&h),
+        l_query: E::G1::normalize_batch(&abc[num_instance..]),
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Contribution<E: Pairing> {
+    pub parameters: ProvingKey<E>,
+    pub delta: KnowledgeProof<E>,
+}
+
+// A contribution's proof is bound to the digest of the keys it updates.
+fn context<E: Pairing>(previous: &ProvingKey<E>) -> Vec<u8> {
+    [&b"delta"[..], &digest(previous)[..]].concat()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Phase2<E: Pairing> {
+    pub initial: ProvingKey<E>,
+    pub contributions: Vec<Contribution<E>>,
+}
+
+impl<E: Pairing> Phase2<E> {
+    pub fn new<C: ConstraintSynthesizer<E::ScalarField>>(accumulator: &Accumulator<E>, circuit: C) -> Result<Self, CeremonyError> {
+        Ok(Phase2 { initial: initial_parameters(accumulator, circuit)?, contributions: Vec::new() })
+    }
+
+    // The current keys, usable with `create_proof` and `verify_proof`.
+    pub fn parameters(&self) -> &ProvingKey<E> {
+        self.contributions.last().map_or(&self.initial, |c| &c.parameters)
+    }
+
+    pub fn contribute<R: Rng>(&mut self, rng: &mut R) {
+        let mut delta = E::ScalarField::rand(rng);
+        while delta.is_zero() {
+            delta = E::ScalarField::rand(rng);
+        }
+        self.contribute_with(delta, rng).expect("delta is non-zero");
+    }
+
+    pub fn contribute_with<R: Rng>(&mut self, delta: E::ScalarField, rng: &mut R) -> Result<(), CeremonyError> {
+        let previous = self.parameters();
+        let delta_inv = delta.inverse().ok_or(CeremonyError::ZeroContribution)?;
+        let divide = |points: &[E::G1Affine]| E::G1::normalize_batch(&points.iter().map(|p| *p * delta_inv).collect::<Vec<_>>());
+
+        let mut parameters = previous.clone();
+        parameters.delta_g1 = (previous.delta_g1 * delta).into_affine();
+        parameters.vk.delta_g2 = (previous.vk.delta_g2 * delta).into_affine();
+        parameters.l_query = divide(&previous.l_query);
+        parameters.h_query = divide(&previous.h_query);
+        let proof = KnowledgeProof::new(delta, &context(previous), rng);
+        self.contributions.push(Contribution { parameters, delta: proof });
+        Ok(())
+    }
+
+    // Replays phase 2 on top of a phase-1 result, which should itself have been
+    // checked with `Transcript::verify`.
+    pub fn verify<C: ConstraintSynthesizer<E::ScalarField>>(&self, accumulator: &Accumulator<E>, circuit: C) -> Result<(), CeremonyError> {
+        if self.initial != initial_parameters(accumulator, circuit)? {
+            return Err(CeremonyError::WrongInitialParameters);
+        }
+        let mut previous = &self.initial;
+        for (i, c) in self.contributions.iter().enumerate() {
+            if !c.delta.verify(&context(previous)) {
+                return Err(CeremonyError::InvalidProof(i + 1));
+            }
+            if !is_delta_update(previous, &c.parameters, &c.delta) {
+                return Err(CeremonyError::InconsistentUpdate(i + 1));
+            }
+            previous = &c.parameters;
+        }
+        Ok(())
+    }
+}
+
+// `next` is `previous` with delta multiplied by the proven share and the L and
+// H queries divided by it.
+fn is_delta_update<E: Pairing>(previous: &ProvingKey<E>, next: &ProvingKey<E>, proof: &KnowledgeProof<E>) -> bool {
+    let (g1, g2) = (E::G1Affine::generator(), E::G2Affine::generator());
+    let unchanged = previous.vk.alpha_g1 == next.vk.alpha_g1
+        && previous.vk.beta_g2 == next.vk.beta_g2
+        && previous.vk.gamma_g2 == next.vk.gamma_g2
+        && previous.vk.gamma_abc_g1 == next.vk.gamma_abc_g1
+        && previous.beta_g1 == next.beta_g1
+        && previous.a_query == next.a_query
+        && previous.b_g1_query == next.b_g1_query
+        && previous.b_g2_query == next.b_g2_query
+        && previous.l_query.len() == next.l_query.len()
+        && previous.h_query.len() == next.h_query.len();
+    if !unchanged {
+        return false;
+    }
+    // Each new L or H element times the new delta equals the old one times the old delta.
+    let pairs: Vec<_> = next.l_query.iter().chain(&next.h_query).copied().zip(previous.l_query.iter().chain(&previous.h_query).copied()).collect();
+    same_ratio::<E>((previous.delta_g1, next.delta_g1), (g2, proof.x_g2))
+        && same_ratio::<E>((g1, next.delta_g1), (g2, next.vk.delta_g2))
+        && same_ratio::<E>(combine_pairs::<E::G1>(&pairs, digest(next)), (previous.vk.delta_g2, next.vk.delta_g2))
+}
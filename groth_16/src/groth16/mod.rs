@@ -0,0 +1,259 @@
+// Groth16 implemented directly on arkworks' curve and constraint-system
+// primitives, without ark-groth16 (Groth, "On the Size of Pairing-based
+// Non-interactive Arguments", 2016).
+//
+//     qap        R1CS -> QAP over an FFT domain, and the quotient polynomial h
+//     setup      CRS from toxic waste (tau, alpha, beta, gamma, delta)
+//     prover     proof elements A, B, C
+//     verifier   e(A, B) = e(alpha, beta) e(sum x_j IC_j, gamma) e(C, delta)
+//     ceremony   the same keys from a multi-party computation, with no toxic waste
+//     batch      many proofs checked with one multi-pairing
+//     aggregate  many proofs folded into one O(log n) proof (SnarkPack)
+//
+// Any `ConstraintSynthesizer` works, including the `gadgets` circuits and
+// `CubicDemoCircuit`. The key layout mirrors ark-groth16's, so the two can be
+// compared step by step, and serialize to the same bytes.
+
+pub mod aggregate;
+pub mod batch;
+pub mod ceremony;
+pub mod prover;
+pub mod qap;
+pub mod setup;
+pub mod verifier;
+
+pub use prover::create_proof;
+pub use setup::{generate_parameters, generate_parameters_with_toxic_waste, ToxicWaste};
+pub use verifier::verify_proof;
+
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, OptimizationGoal, SynthesisError, SynthesisMode,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifyingKey<E: Pairing> {
+    pub alpha_g1: E::G1Affine,
+    pub beta_g2: E::G2Affine,
+    pub gamma_g2: E::G2Affine,
+    pub delta_g2: E::G2Affine,
+    // [(beta u_j(tau) + alpha v_j(tau) + w_j(tau)) / gamma]_1 for the constant 1 and each public input.
+    pub gamma_abc_g1: Vec<E::G1Affine>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProvingKey<E: Pairing> {
+    pub vk: VerifyingKey<E>,
+    pub beta_g1: E::G1Affine,
+    pub delta_g1: E::G1Affine,
+    // [u_j(tau)]_1, [v_j(tau)]_1 and [v_j(tau)]_2 for every variable.
+    pub a_query: Vec<E::G1Affine>,
+    pub b_g1_query: Vec<E::G1Affine>,
+    pub b_g2_query: Vec<E::G2Affine>,
+    // [tau^i Z_H(tau) / delta]_1 for i = 0..n-2.
+    pub h_query: Vec<E::G1Affine>,
+    // [(beta u_j(tau) + alpha v_j(tau) + w_j(tau)) / delta]_1 for each witness variable.
+    pub l_query: Vec<E::G1Affine>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<E: Pairing> {
+    pub a: E::G1Affine,
+    pub b: E::G2Affine,
+    pub c: E::G1Affine,
+}
+
+// Field-for-field conversions to and from ark-groth16, e.g. to hand our proofs
+// to its verifier gadget.
+impl<E: Pairing> From<VerifyingKey<E>> for ark_groth16::VerifyingKey<E> {
+    fn from(vk: VerifyingKey<E>) -> Self {
+        ark_groth16::VerifyingKey {
+            alpha_g1: vk.alpha_g1,
+            beta_g2: vk.beta_g2,
+            gamma_g2: vk.gamma_g2,
+            delta_g2: vk.delta_g2,
+            gamma_abc_g1: vk.gamma_abc_g1,
+        }
+    }
+}
+
+impl<E: Pairing> From<ark_groth16::VerifyingKey<E>> for VerifyingKey<E> {
+    fn from(vk: ark_groth16::VerifyingKey<E>) -> Self {
+        VerifyingKey {
+            alpha_g1: vk.alpha_g1,
+            beta_g2: vk.beta_g2,
+            gamma_g2: vk.gamma_g2,
+            delta_g2: vk.delta_g2,
+            gamma_abc_g1: vk.gamma_abc_g1,
+        }
+    }
+}
+
+impl<E: Pairing> From<Proof<E>> for ark_groth16::Proof<E> {
+    fn from(proof: Proof<E>) -> Self {
+        ark_groth16::Proof { a: proof.a, b: proof.b, c: proof.c }
+    }
+}
+
+impl<E: Pairing> From<ark_groth16::Proof<E>> for Proof<E> {
+    fn from(proof: ark_groth16::Proof<E>) -> Self {
+        Proof { a: proof.a, b: proof.b, c: proof.c }
+    }
+}
+
+// Runs the circuit in the given mode and inlines all linear combinations, so
+// that the constraint matrices can be read off.
+pub(crate) fn synthesize<F: PrimeField, C: ConstraintSynthesizer<F>>(
+    circuit: C,
+    mode: SynthesisMode,
+) -> Result<ConstraintSystemRef<F>, SynthesisError> {
+    let cs = ConstraintSystem::new_ref();
+    cs.set_optimization_goal(OptimizationGoal::Constraints);
+    cs.set_mode(mode);
+    circuit.generate_constraints(cs.clone())?;
+    cs.finalize();
+    Ok(cs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::CubicDemoCircuit;
+    use crate::gadgets::poseidon::{hash, hash_var, poseidon_config};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_bn254::{Bn254, Fr as BnFr};
+    use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
+    use ark_ff::{AdditiveGroup, UniformRand};
+    use ark_poly::{DenseUVPolynomial, Polynomial, univariate::DensePolynomial};
+    use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+    use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
+    use ark_std::rand::{rngs::StdRng, RngCore, SeedableRng};
+    use ark_std::test_rng;
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(test_rng().next_u64())
+    }
+
+    fn cubic(x: u64) -> CubicDemoCircuit<Fr> {
+        CubicDemoCircuit { x: Some(Fr::from(x)) }
+    }
+
+    // The accept/reject matrix of the original ark-groth16 demo.
+    #[test]
+    fn cubic_demo_cases_match_ark_groth16() {
+        let mut rng = rng();
+        let pk = generate_parameters::<Bls12_381, _, _>(CubicDemoCircuit::<Fr> { x: None }, &mut rng).unwrap();
+        let (ark_pk, ark_vk) =
+            ark_groth16::Groth16::<Bls12_381>::setup(CubicDemoCircuit::<Fr> { x: None }, &mut rng).unwrap();
+
+        for (x, out, expected) in [(3u64, 35u64, true), (4, 73, true), (4, 35, false), (3, 73, false)] {
+            let proof = create_proof(&pk, cubic(x), &mut rng).unwrap();
+            assert_eq!(verify_proof(&pk.vk, &[Fr::from(out)], &proof).unwrap(), expected, "x = {}, out = {}", x, out);
+
+            let ark_proof = ark_groth16::Groth16::<Bls12_381>::prove(&ark_pk, cubic(x), &mut rng).unwrap();
+            assert_eq!(ark_groth16::Groth16::<Bls12_381>::verify(&ark_vk, &[Fr::from(out)], &ark_proof).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn proofs_are_rerandomized_and_wrong_input_counts_are_errors() {
+        let mut rng = rng();
+        let pk = generate_parameters::<Bls12_381, _, _>(cubic(3), &mut rng).unwrap();
+        let p1 = create_proof(&pk, cubic(3), &mut rng).unwrap();
+        let p2 = create_proof(&pk, cubic(3), &mut rng).unwrap();
+        assert_ne!(p1, p2);
+        assert!(verify_proof(&pk.vk, &[Fr::from(35u64)], &p2).unwrap());
+        assert!(verify_proof(&pk.vk, &[], &p1).is_err());
+        assert!(verify_proof(&pk.vk, &[Fr::from(35u64), Fr::from(1u64)], &p1).is_err());
+    }
+
+    #[test]
+    fn keys_and_proofs_decode_as_ark_groth16_types() {
+        let mut rng = rng();
+        let pk = generate_parameters::<Bls12_381, _, _>(cubic(3), &mut rng).unwrap();
+        let proof = create_proof(&pk, cubic(3), &mut rng).unwrap();
+
+        let mut bytes = Vec::new();
+        pk.serialize_compressed(&mut bytes).unwrap();
+        let ark_pk = ark_groth16::ProvingKey::<Bls12_381>::deserialize_compressed(&bytes[..]).unwrap();
+        bytes.clear();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let ark_proof = ark_groth16::Proof::<Bls12_381>::deserialize_compressed(&bytes[..]).unwrap();
+        assert!(ark_groth16::Groth16::<Bls12_381>::verify(&ark_pk.vk, &[Fr::from(35u64)], &ark_proof).unwrap());
+
+        // And the other way round.
+        let ark_proof = ark_groth16::Groth16::<Bls12_381>::prove(&ark_pk, cubic(4), &mut rng).unwrap();
+        bytes.clear();
+        ark_proof.serialize_uncompressed(&mut bytes).unwrap();
+        let proof = Proof::<Bls12_381>::deserialize_uncompressed(&bytes[..]).unwrap();
+        assert!(verify_proof(&pk.vk, &[Fr::from(73u64)], &proof).unwrap());
+    }
+
+    #[test]
+    fn unsatisfied_witness_does_not_verify() {
+        // A circuit that claims x^3 + x + 5 = 35 with x = 4 (true output 73).
+        #[derive(Clone)]
+        struct Lying;
+        impl ConstraintSynthesizer<Fr> for Lying {
+            fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+                let x = FpVar::new_witness(cs.clone(), || Ok(Fr::from(4u64)))?;
+                let out = FpVar::new_input(cs, || Ok(Fr::from(35u64)))?;
+                (&x * &x * &x + &x + FpVar::Constant(Fr::from(5u64))).enforce_equal(&out)
+            }
+        }
+        let mut rng = rng();
+        let pk = generate_parameters::<Bls12_381, _, _>(Lying, &mut rng).unwrap();
+        let proof = create_proof(&pk, Lying, &mut rng).unwrap();
+        assert!(!verify_proof(&pk.vk, &[Fr::from(35u64)], &proof).unwrap());
+    }
+
+    // a(X) b(X) - c(X) = h(X) Z_H(X), checked at a random point.
+    #[test]
+    fn quotient_satisfies_the_qap_identity() {
+        let cs = synthesize(cubic(3), SynthesisMode::Prove { construct_matrices: true }).unwrap();
+        let matrices = cs.to_matrices().unwrap();
+        let cs = cs.borrow().unwrap();
+        let assignment = [cs.instance_assignment.clone(), cs.witness_assignment.clone()].concat();
+        let domain = qap::domain(&matrices).unwrap();
+        let h = qap::quotient(&matrices, &domain, &assignment).unwrap();
+
+        let tau = Fr::rand(&mut rng());
+        let evals = qap::evaluate_at(&matrices, &domain, tau);
+        let dot = |column: &[Fr]| column.iter().zip(&assignment).map(|(p, z)| *p * z).sum::<Fr>();
+        let h_at_tau = DensePolynomial::from_coefficients_vec(h).evaluate(&tau);
+        assert_eq!(dot(&evals.u) * dot(&evals.v) - dot(&evals.w), h_at_tau * evals.z_at_tau);
+        assert_ne!(evals.z_at_tau, Fr::ZERO);
+    }
+
+    // A gadget circuit on another curve.
+    #[derive(Clone)]
+    struct PoseidonPreimage {
+        config: PoseidonConfig<BnFr>,
+        preimage: [BnFr; 2],
+        digest: BnFr,
+    }
+
+    impl ConstraintSynthesizer<BnFr> for PoseidonPreimage {
+        fn generate_constraints(self, cs: ConstraintSystemRef<BnFr>) -> Result<(), SynthesisError> {
+            let digest = FpVar::new_input(cs.clone(), || Ok(self.digest))?;
+            let preimage = Vec::<FpVar<BnFr>>::new_witness(cs.clone(), || Ok(self.preimage.to_vec()))?;
+            hash_var(cs, &self.config, &preimage)?.enforce_equal(&digest)
+        }
+    }
+
+    #[test]
+    fn poseidon_circuit_on_bn254() {
+        let mut rng = rng();
+        let config = poseidon_config::<BnFr>();
+        let preimage = [BnFr::from(1u64), BnFr::from(2u64)];
+        let digest = hash(&config, &preimage);
+        let circuit = PoseidonPreimage { config, preimage, digest };
+
+        let pk = generate_parameters::<Bn254, _, _>(circuit.clone(), &mut rng).unwrap();
+        let proof = create_proof(&pk, circuit, &mut rng).unwrap();
+        assert!(verify_proof(&pk.vk, &[digest], &proof).unwrap());
+        assert!(!verify_proof(&pk.vk, &[digest.double()], &proof).unwrap());
+    }
+}
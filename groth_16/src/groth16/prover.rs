@@ -0,0 +1,56 @@
+// The prover. With r, s random and z = (1, x, w):
+//
+//     A = alpha + sum_j z_j u_j(tau) + r delta
+//     B = beta  + sum_j z_j v_j(tau) + s delta
+//     C = sum_{j witness} z_j L_j + h(tau) Z_H(tau) / delta + s A + r B - r s delta
+//
+// all computed "in the exponent" from the proving key with multi-scalar
+// multiplications. r and s make proofs zero-knowledge: each proof is a fresh
+// random-looking triple even for the same witness.
+
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::UniformRand;
+use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError, SynthesisMode};
+use ark_std::rand::Rng;
+
+use super::{qap, synthesize, Proof, ProvingKey};
+
+pub fn create_proof<E: Pairing, C: ConstraintSynthesizer<E::ScalarField>, R: Rng>(
+    pk: &ProvingKey<E>,
+    circuit: C,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError> {
+    let cs = synthesize(circuit, SynthesisMode::Prove { construct_matrices: true })?;
+    let matrices = cs.to_matrices().ok_or(SynthesisError::MissingCS)?;
+    let (assignment, num_instance) = {
+        let cs = cs.borrow().ok_or(SynthesisError::MissingCS)?;
+        ([cs.instance_assignment.clone(), cs.witness_assignment.clone()].concat(), cs.instance_assignment.len())
+    };
+    if assignment.len() != pk.a_query.len() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+    let domain = qap::domain(&matrices)?;
+    let h = qap::quotient(&matrices, &domain, &assignment)?;
+
+    let r = E::ScalarField::rand(rng);
+    let s = E::ScalarField::rand(rng);
+    let delta_g1 = pk.delta_g1.into_group();
+
+    let a = pk.vk.alpha_g1 + E::G1::msm_unchecked(&pk.a_query, &assignment) + delta_g1 * r;
+    let b_g1 = pk.beta_g1 + E::G1::msm_unchecked(&pk.b_g1_query, &assignment) + delta_g1 * s;
+    let b_g2 = pk.vk.beta_g2 + E::G2::msm_unchecked(&pk.b_g2_query, &assignment) + pk.delta_g2() * s;
+    let c = E::G1::msm_unchecked(&pk.l_query, &assignment[num_instance..])
+        + E::G1::msm_unchecked(&pk.h_query, &h)
+        + a * s
+        + b_g1 * r
+        - delta_g1 * (r * s);
+
+    Ok(Proof { a: a.into_affine(), b: b_g2.into_affine(), c: c.into_affine() })
+}
+
+impl<E: Pairing> ProvingKey<E> {
+    fn delta_g2(&self) -> E::G2 {
+        self.vk.delta_g2.into_group()
+    }
+}
@@ -0,0 +1,103 @@
+// R1CS to QAP reduction.
+//
+// An R1CS instance with m constraints over variables z = (1, x, w) is turned
+// into polynomials by interpolating each column of A, B and C over an FFT
+// domain H of size n >= m + l:
+//
+//     u_j(omega^i) = A[i][j],   v_j(omega^i) = B[i][j],   w_j(omega^i) = C[i][j]
+//
+// z satisfies the R1CS iff (sum z_j u_j)(sum z_j v_j) - (sum z_j w_j) vanishes
+// on H, i.e. is divisible by Z_H(X) = X^n - 1. The quotient h is what the
+// prover commits to.
+//
+// As in ark-groth16, l extra rows "x_i * 0 = 0" put a 1 in A for every public
+// input (including the constant 1). That keeps the u_j of the inputs linearly
+// independent, which the soundness proof relies on, and costs nothing.
+
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_relations::r1cs::{ConstraintMatrices, SynthesisError};
+use ark_std::ops::{AddAssign, Mul};
+use ark_std::Zero;
+
+pub type Domain<F> = GeneralEvaluationDomain<F>;
+
+pub fn domain<F: PrimeField>(matrices: &ConstraintMatrices<F>) -> Result<Domain<F>, SynthesisError> {
+    Domain::new(matrices.num_constraints + matrices.num_instance_variables)
+        .ok_or(SynthesisError::PolynomialDegreeTooLarge)
+}
+
+// Every column polynomial evaluated at tau: (u_j(tau), v_j(tau), w_j(tau)) for
+// all j, plus Z_H(tau). Used by the setup, which knows tau.
+pub struct QapEvaluations<F: PrimeField> {
+    pub u: Vec<F>,
+    pub v: Vec<F>,
+    pub w: Vec<F>,
+    pub z_at_tau: F,
+}
+
+pub fn evaluate_at<F: PrimeField>(matrices: &ConstraintMatrices<F>, domain: &Domain<F>, tau: F) -> QapEvaluations<F> {
+    let (u, v, w) = combine_columns(matrices, &domain.evaluate_all_lagrange_coefficients(tau));
+    QapEvaluations { u, v, w, z_at_tau: domain.evaluate_vanishing_polynomial(tau) }
+}
+
+// u_j = sum_i A[i][j] L_i (and likewise v_j, w_j) for every variable j, given
+// the Lagrange basis L_i of H at the secret point. The L_i are field elements
+// when tau is known, or group elements [L_i(tau)] in a ceremony where it is not.
+pub fn combine_columns<F, T>(matrices: &ConstraintMatrices<F>, lagrange: &[T]) -> (Vec<T>, Vec<T>, Vec<T>)
+where
+    F: PrimeField,
+    T: Copy + Zero + AddAssign + Mul<F, Output = T>,
+{
+    let num_variables = matrices.num_instance_variables + matrices.num_witness_variables;
+    let column_sums = |matrix: &[Vec<(F, usize)>]| {
+        let mut out = vec![T::zero(); num_variables];
+        for (row, l) in matrix.iter().zip(lagrange) {
+            for (coeff, j) in row {
+                out[*j] += *l * *coeff;
+            }
+        }
+        out
+    };
+
+    let mut u = column_sums(&matrices.a);
+    // The input rows come after the real constraints.
+    for (j, u_j) in u.iter_mut().take(matrices.num_instance_variables).enumerate() {
+        *u_j += lagrange[matrices.num_constraints + j];
+    }
+    (u, column_sums(&matrices.b), column_sums(&matrices.c))
+}
+
+// Coefficients of h(X) = (a(X) b(X) - c(X)) / Z_H(X) for the full assignment
+// z = (1, x, w). a, b, c are known by their evaluations on H; the division is
+// done pointwise on a coset of H, where Z_H is a non-zero constant.
+pub fn quotient<F: PrimeField>(
+    matrices: &ConstraintMatrices<F>,
+    domain: &Domain<F>,
+    assignment: &[F],
+) -> Result<Vec<F>, SynthesisError> {
+    let n = domain.size();
+    let evaluate_rows = |matrix: &[Vec<(F, usize)>]| {
+        let mut evals: Vec<F> = matrix.iter().map(|row| row.iter().map(|(coeff, j)| *coeff * assignment[*j]).sum()).collect();
+        evals.resize(n, F::zero());
+        evals
+    };
+    let mut a = evaluate_rows(&matrices.a);
+    a[matrices.num_constraints..matrices.num_constraints + matrices.num_instance_variables]
+        .copy_from_slice(&assignment[..matrices.num_instance_variables]);
+    let mut b = evaluate_rows(&matrices.b);
+    let mut c = evaluate_rows(&matrices.c);
+
+    let coset = domain.get_coset(F::GENERATOR).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+    for evals in [&mut a, &mut b, &mut c] {
+        domain.ifft_in_place(evals);
+        coset.fft_in_place(evals);
+    }
+
+    let z_inv = domain.evaluate_vanishing_polynomial(F::GENERATOR).inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
+    let mut h: Vec<F> = a.iter().zip(&b).zip(&c).map(|((a, b), c)| (*a * b - c) * z_inv).collect();
+    coset.ifft_in_place(&mut h);
+    // deg(h) <= n - 2.
+    h.truncate(n - 1);
+    Ok(h)
+}
@@ -0,0 +1,88 @@
+// Circuit-specific trusted setup.
+//
+// The CRS encodes the QAP polynomials evaluated at a secret point tau, blinded
+// by alpha and beta (binding A, B and C to the same assignment) and divided by
+// gamma or delta (separating the public inputs from the prover's part). These
+// five scalars are the toxic waste: anyone who keeps them can forge proofs.
+// `generate_parameters` samples and drops them; the explicit variant exists for
+// tests and for ceremonies that build the same keys without any single party
+// knowing them.
+
+use ark_ec::pairing::Pairing;
+use ark_ec::scalar_mul::ScalarMul;
+use ark_ec::PrimeGroup;
+use ark_ff::Field;
+use ark_poly::EvaluationDomain;
+use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError, SynthesisMode};
+use ark_std::rand::Rng;
+
+use super::{qap, synthesize, ProvingKey, VerifyingKey};
+
+pub struct ToxicWaste<F: Field> {
+    pub tau: F,
+    pub alpha: F,
+    pub beta: F,
+    pub gamma: F,
+    pub delta: F,
+}
+
+impl<F: Field> ToxicWaste<F> {
+    pub fn sample<R: Rng>(rng: &mut R) -> Self {
+        ToxicWaste { tau: F::rand(rng), alpha: F::rand(rng), beta: F::rand(rng), gamma: F::rand(rng), delta: F::rand(rng) }
+    }
+}
+
+pub fn generate_parameters<E: Pairing, C: ConstraintSynthesizer<E::ScalarField>, R: Rng>(
+    circuit: C,
+    rng: &mut R,
+) -> Result<ProvingKey<E>, SynthesisError> {
+    generate_parameters_with_toxic_waste(circuit, &ToxicWaste::sample(rng))
+}
+
+pub fn generate_parameters_with_toxic_waste<E: Pairing, C: ConstraintSynthesizer<E::ScalarField>>(
+    circuit: C,
+    waste: &ToxicWaste<E::ScalarField>,
+) -> Result<ProvingKey<E>, SynthesisError> {
+    let cs = synthesize(circuit, SynthesisMode::Setup)?;
+    let matrices = cs.to_matrices().ok_or(SynthesisError::MissingCS)?;
+    let domain = qap::domain(&matrices)?;
+    let evals = qap::evaluate_at(&matrices, &domain, waste.tau);
+    let num_instance = matrices.num_instance_variables;
+
+    let gamma_inv = waste.gamma.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
+    let delta_inv = waste.delta.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
+    // beta u_j + alpha v_j + w_j, the combination both the IC and L elements encode.
+    let abc: Vec<E::ScalarField> = (0..evals.u.len())
+        .map(|j| waste.beta * evals.u[j] + waste.alpha * evals.v[j] + evals.w[j])
+        .collect();
+    let gamma_abc: Vec<_> = abc[..num_instance].iter().map(|x| *x * gamma_inv).collect();
+    let l: Vec<_> = abc[num_instance..].iter().map(|x| *x * delta_inv).collect();
+
+    // tau^i Z_H(tau) / delta for i = 0..n-2, the degrees h can have.
+    let mut h = Vec::with_capacity(domain.size() - 1);
+    let mut power = evals.z_at_tau * delta_inv;
+    for _ in 0..domain.size() - 1 {
+        h.push(power);
+        power *= waste.tau;
+    }
+
+    let g1 = E::G1::generator();
+    let g2 = E::G2::generator();
+    let vk = VerifyingKey {
+        alpha_g1: (g1 * waste.alpha).into(),
+        beta_g2: (g2 * waste.beta).into(),
+        gamma_g2: (g2 * waste.gamma).into(),
+        delta_g2: (g2 * waste.delta).into(),
+        gamma_abc_g1: g1.batch_mul(&gamma_abc),
+    };
+    Ok(ProvingKey {
+        vk,
+        beta_g1: (g1 * waste.beta).into(),
+        delta_g1: (g1 * waste.delta).into(),
+        a_query: g1.batch_mul(&evals.u),
+        b_g1_query: g1.batch_mul(&evals.v),
+        b_g2_query: g2.batch_mul(&evals.v),
+        h_query: g1.batch_mul(&h),
+        l_query: g1.batch_mul(&l),
+    })
+}
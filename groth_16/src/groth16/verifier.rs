@@ -0,0 +1,30 @@
+// The verifier:
+//
+//     e(A, B) = e(alpha, beta) * e(IC, gamma) * e(C, delta),   IC = IC_0 + sum_j x_j IC_j
+//
+// checked as e(A, B) e(-IC, gamma) e(-C, delta) e(-alpha, beta) = 1, a single
+// multi-pairing with one final exponentiation.
+
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_relations::r1cs::SynthesisError;
+use ark_std::Zero;
+
+use super::{Proof, VerifyingKey};
+
+pub fn verify_proof<E: Pairing>(
+    vk: &VerifyingKey<E>,
+    public_inputs: &[E::ScalarField],
+    proof: &Proof<E>,
+) -> Result<bool, SynthesisError> {
+    if public_inputs.len() + 1 != vk.gamma_abc_g1.len() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+    let ic = vk.gamma_abc_g1[0].into_group() + E::G1::msm_unchecked(&vk.gamma_abc_g1[1..], public_inputs);
+
+    let result = E::multi_pairing(
+        [proof.a, (-ic).into_affine(), (-proof.c.into_group()).into_affine(), (-vk.alpha_g1.into_group()).into_affine()],
+        [proof.b, vk.gamma_g2, vk.delta_g2, vk.beta_g2],
+    );
+    Ok(result.is_zero())
+}
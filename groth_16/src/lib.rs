@@ -0,0 +1,28 @@
+// Groth16 experiments on top of arkworks.
+//
+// `cubic` holds the original x^3 + x + 5 = out demo circuit, written with raw
+// `enforce_constraint` calls. `gadgets` is a library of reusable circuit pieces
+// built on ark-r1cs-std: bit decomposition, range checks, comparisons,
+// conditional selection, the Poseidon hash and Merkle membership proofs.
+// `groth16` is the proving system itself, written from scratch: QAP reduction,
+// trusted setup, prover and verifier. `files` stores keys and proofs on disk.
+// `circom` loads circuits compiled by circom from their .r1cs and .wtns files,
+// and `solidity` generates on-chain verifiers for BN254 keys. `pipeline` runs
+// the cubic circuit on BN254, BLS12-377 or BLS12-381, and `recursion` proves
+// over BW6-761 that a BLS12-377 proof verifies. `debug` finds the first
+// unsatisfied constraint of a witness and sizes a circuit, `polynomial`
+// compiles equations given as strings into circuits, and `plonk` is a second
+// proving system with a universal setup and custom gates.
+
+pub mod circom;
+pub mod cubic;
+pub mod debug;
+pub mod files;
+pub mod gadgets;
+pub mod groth16;
+pub mod pipeline;
+pub mod plonk;
+pub mod polynomial;
+pub mod recursion;
+pub mod solidity;
+mod transcript;
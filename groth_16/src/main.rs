@@ -1,61 +1,435 @@
+/*
+Command-line usage, for the x^3 + x + 5 = out circuit:
+    setup <dir> [--uncompressed]                 write <dir>/proving.key and <dir>/verifying.key
+    prove <proving.key> <x> <proof> [--uncompressed]
+                                                 prove knowledge of x and print the public output
+    verify <verifying.key> <proof> <out>         check a proof, exiting with status 1 if it is invalid
+    ceremony <dir> <contributors> [--uncompressed]
+                                                 run a local MPC setup with simulated contributors and
+                                                 write both transcripts and the keys to <dir>
+    verify-ceremony <dir>                        replay the transcripts in <dir> and check they give its keys
+    check <x> [<out>]                            print the circuit's size and check the witness for x
+                                                 (and a claimed output), naming the first failing constraint
+    poly setup <equation> <dir> [--uncompressed] write keys for a polynomial equation, e.g. "x^2*y + 3y"
+                                                 (prove p(vars) = out) or "x^2 - 5x + 6 = 0" (prove a root)
+    poly prove <equation> <proving.key> <proof> <var>=<value>... [--uncompressed]
+                                                 prove knowledge of the values, printing out if there is one
+    poly verify <verifying.key> <proof> [<out>]  check a polynomial proof; root proofs take no <out>
+    recursion [<x>]                              prove x on BLS12-377, then prove over BW6-761 that
+                                                 the first proof verifies, and verify that
+    plonk [<x>]                                  prove x with PLONK in memory, both on the R1CS translation
+                                                 and with the cube gate, under one universal SRS
+
+Every command but `recursion` takes --curve bn254|bls12-377|bls12-381 (default
+bls12-381). Files do not record their curve, so pass the same --curve to every
+step. Keys and proofs are written compressed unless --uncompressed is given;
+either form is accepted when reading. `verify` caches the prepared verifying
+key next to the key file (<verifying.key>.prepared).
+Without arguments the original demo below is run.
+*/
+
 // Import necessary modules from arkworks
-use ark_ff::Field; // For working with finite fields
-use ark_relations::{
-    lc, // For linear combinations
-    r1cs::{ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError}, // For R1CS-based constraint systems
-};
 use ark_snark::CircuitSpecificSetupSNARK; // For setting up SNARKs
-use ark_std::rand::{Rng, RngCore, SeedableRng}; // Random number generation
+use ark_std::rand::{RngCore, SeedableRng}; // Random number generation
 use ark_std::test_rng; // A standard test RNG
+use groth_16::cubic::{CubicDemoCircuit, CubicWitness}; // The x^3 + x + 5 = out circuit
+
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+
+use ark_ec::pairing::Pairing;
+use ark_groth16::Groth16;
+use ark_relations::r1cs::SynthesisMode;
+use ark_serialize::{CanonicalSerialize, Compress};
+use groth_16::debug;
+use groth_16::files::{self, PHASE1_TRANSCRIPT, PHASE2_TRANSCRIPT, PROOF, PROVING_KEY, VERIFYING_KEY};
+use groth_16::groth16::ceremony::{phase1::Transcript, phase2::Phase2};
+use groth_16::groth16::{create_proof, generate_parameters, verify_proof, Proof, ProvingKey, VerifyingKey};
+use groth_16::on_curve;
+use groth_16::pipeline::{self, Curve};
+use groth_16::plonk::{self, cubic_circuit, kzg};
+use groth_16::polynomial::{Equation, PolynomialCircuit};
+use groth_16::recursion::{self, VerifierCircuit};
+
+// Exit status for a proof that does not verify.
+const EXIT_INVALID: u8 = 1;
+// Exit status for bad usage, unreadable files or malformed keys.
+const EXIT_ERROR: u8 = 2;
+
+const USAGE: &str = "usage:
+    groth_16 setup <dir> [--uncompressed] [--curve <curve>]
+    groth_16 prove <proving.key> <x> <proof> [--uncompressed] [--curve <curve>]
+    groth_16 verify <verifying.key> <proof> <out> [--curve <curve>]
+    groth_16 ceremony <dir> <contributors> [--uncompressed] [--curve <curve>]
+    groth_16 verify-ceremony <dir> [--curve <curve>]
+    groth_16 check <x> [<out>] [--curve <curve>]
+    groth_16 poly setup <equation> <dir> [--uncompressed] [--curve <curve>]
+    groth_16 poly prove <equation> <proving.key> <proof> <var>=<value>... [--uncompressed] [--curve <curve>]
+    groth_16 poly verify <verifying.key> <proof> [<out>] [--curve <curve>]
+    groth_16 recursion [<x>]
+    groth_16 plonk [<x>] [--curve <curve>]
+<curve> is bn254, bls12-377 or bls12-381 (the default)";
+
+// Splits off the --uncompressed and --curve options.
+fn options(args: &[String]) -> Result<(Vec<&String>, Compress, Curve), Box<dyn Error>> {
+    let mut positional = Vec::new();
+    let mut compress = Compress::Yes;
+    let mut curve = Curve::Bls12_381;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--uncompressed" => compress = Compress::No,
+            "--curve" => curve = iter.next().ok_or(USAGE)?.parse()?,
+            _ => positional.push(arg),
+        }
+    }
+    Ok((positional, compress, curve))
+}
+
+// A decimal field element below the scalar field modulus.
+fn parse_field<F: ark_ff::PrimeField>(s: &str) -> Result<F, Box<dyn Error>> {
+    pipeline::parse_scalar(s).ok_or_else(|| format!("not a field element: {}", s).into())
+}
+
+fn rng() -> ark_std::rand::rngs::StdRng {
+    ark_std::rand::rngs::StdRng::from_entropy()
+}
+
+fn setup(args: &[String]) -> Result<u8, Box<dyn Error>> {
+    let (args, compress, curve) = options(args)?;
+    let [dir] = args[..] else {
+        return Err(USAGE.into());
+    };
+    on_curve!(curve, setup_on(Path::new(dir), compress))
+}
+
+fn setup_on<E: Pairing>(dir: &Path, compress: Compress) -> Result<u8, Box<dyn Error>> {
+    fs::create_dir_all(dir)?;
+    let pk = pipeline::setup::<E, _>(&mut rng())?;
+    files::write(dir.join("proving.key"), PROVING_KEY, &pk, compress)?;
+    files::write(dir.join("verifying.key"), VERIFYING_KEY, &pk.vk, compress)?;
+    println!("wrote {0}/proving.key and {0}/verifying.key", dir.display());
+    Ok(0)
+}
+
+fn prove(args: &[String]) -> Result<u8, Box<dyn Error>> {
+    let (args, compress, curve) = options(args)?;
+    let [pk_path, x, proof_path] = args[..] else {
+        return Err(USAGE.into());
+    };
+    on_curve!(curve, prove_on(pk_path, x, proof_path, compress))
+}
+
+fn prove_on<E: Pairing>(pk_path: &str, x: &str, proof_path: &str, compress: Compress) -> Result<u8, Box<dyn Error>> {
+    let pk: ProvingKey<E> = files::read(pk_path, PROVING_KEY)?;
+    let (proof, out) = pipeline::prove(&pk, parse_field(x)?, &mut rng())?;
+    files::write(proof_path, PROOF, &proof, compress)?;
+    println!("{}", out);
+    Ok(0)
+}
+
+fn verify(args: &[String]) -> Result<u8, Box<dyn Error>> {
+    let (args, _, curve) = options(args)?;
+    let [vk_path, proof_path, out] = args[..] else {
+        return Err(USAGE.into());
+    };
+    on_curve!(curve, verify_on(vk_path, proof_path, out))
+}
+
+fn verify_on<E: Pairing>(vk_path: &str, proof_path: &str, out: &str) -> Result<u8, Box<dyn Error>> {
+    let (pvk, _) = files::load_prepared_verifying_key::<E>(Path::new(vk_path))?;
+    let proof: Proof<E> = files::read(proof_path, PROOF)?;
+    if Groth16::<E>::verify_proof(&pvk, &proof.into(), &[parse_field(out)?])? {
+        println!("Proof is valid!");
+        Ok(0)
+    } else {
+        eprintln!("Proof is INVALID");
+        Ok(EXIT_INVALID)
+    }
+}
+
+fn ceremony(args: &[String]) -> Result<u8, Box<dyn Error>> {
+    let (args, compress, curve) = options(args)?;
+    let [dir, contributors] = args[..] else {
+        return Err(USAGE.into());
+    };
+    let contributors: usize = contributors.parse().map_err(|_| USAGE)?;
+    on_curve!(curve, ceremony_on(Path::new(dir), contributors, compress))
+}
+
+fn ceremony_on<E: Pairing>(dir: &Path, contributors: usize, compress: Compress) -> Result<u8, Box<dyn Error>> {
+    fs::create_dir_all(dir)?;
+    let circuit = CubicDemoCircuit::<E::ScalarField> { x: None };
+
+    // Each contributor draws its own shares; nothing but the transcript is kept.
+    // Eight powers cover the cubic circuit (4 constraints and 2 inputs).
+    let mut phase1 = Transcript::<E>::new(8);
+    for i in 1..=contributors {
+        phase1.contribute(&mut rng());
+        println!("phase 1: contribution {} added", i);
+    }
+    phase1.verify()?;
+    let mut phase2 = Phase2::new(phase1.latest(), circuit)?;
+    for i in 1..=contributors {
+        phase2.contribute(&mut rng());
+        println!("phase 2: contribution {} added", i);
+    }
+    phase2.verify(phase1.latest(), circuit)?;
+
+    files::write(dir.join("phase1.transcript"), PHASE1_TRANSCRIPT, &phase1, compress)?;
+    files::write(dir.join("phase2.transcript"), PHASE2_TRANSCRIPT, &phase2, compress)?;
+    files::write(dir.join("proving.key"), PROVING_KEY, phase2.parameters(), compress)?;
+    files::write(dir.join("verifying.key"), VERIFYING_KEY, &phase2.parameters().vk, compress)?;
+    println!("wrote the transcripts, {0}/proving.key and {0}/verifying.key", dir.display());
+    Ok(0)
+}
+
+fn verify_ceremony(args: &[String]) -> Result<u8, Box<dyn Error>> {
+    let (args, _, curve) = options(args)?;
+    let [dir] = args[..] else {
+        return Err(USAGE.into());
+    };
+    on_curve!(curve, verify_ceremony_on(Path::new(dir)))
+}
+
+fn verify_ceremony_on<E: Pairing>(dir: &Path) -> Result<u8, Box<dyn Error>> {
+    let phase1: Transcript<E> = files::read(dir.join("phase1.transcript"), PHASE1_TRANSCRIPT)?;
+    let phase2: Phase2<E> = files::read(dir.join("phase2.transcript"), PHASE2_TRANSCRIPT)?;
+    let pk: ProvingKey<E> = files::read(dir.join("proving.key"), PROVING_KEY)?;
+    let vk: VerifyingKey<E> = files::read(dir.join("verifying.key"), VERIFYING_KEY)?;
+
+    let checked = phase1
+        .verify()
+        .and_then(|_| phase2.verify(phase1.latest(), CubicDemoCircuit::<E::ScalarField> { x: None }));
+    match checked {
+        Ok(()) if phase2.parameters() == &pk && pk.vk == vk => {
+            println!(
+                "Ceremony is valid: {} + {} contributions",
+                phase1.contributions.len(),
+                phase2.contributions.len()
+            );
+            Ok(0)
+        }
+        Ok(()) => {
+            eprintln!("Ceremony is valid but does not produce these keys");
+            Ok(EXIT_INVALID)
+        }
+        Err(e) => {
+            eprintln!("Ceremony is INVALID: {}", e);
+            Ok(EXIT_INVALID)
+        }
+    }
+}
+
+fn check(args: &[String]) -> Result<u8, Box<dyn Error>> {
+    let (args, _, curve) = options(args)?;
+    let (x, out) = match args[..] {
+        [x] => (x, None),
+        [x, out] => (x, Some(out)),
+        _ => return Err(USAGE.into()),
+    };
+    on_curve!(curve, check_on(x, out))
+}
+
+fn check_on<E: Pairing>(x: &str, out: Option<&String>) -> Result<u8, Box<dyn Error>> {
+    let mut witness = CubicWitness::<E::ScalarField>::new(parse_field(x)?);
+    if let Some(out) = out {
+        witness.out = parse_field(out)?;
+    }
+    let report = debug::check(witness)?;
+    println!("{}", report.stats);
+    match report.unsatisfied {
+        None => {
+            println!("All constraints are satisfied");
+            Ok(0)
+        }
+        Some(failure) => {
+            eprintln!("{}", failure);
+            Ok(EXIT_INVALID)
+        }
+    }
+}
+
+fn poly(args: &[String]) -> Result<u8, Box<dyn Error>> {
+    let (args, compress, curve) = options(args)?;
+    match args[..] {
+        [command, equation, dir] if command == "setup" => on_curve!(curve, poly_setup(equation, Path::new(dir), compress)),
+        [command, equation, pk_path, proof_path, ref values @ ..] if command == "prove" => {
+            on_curve!(curve, poly_prove(equation, pk_path, proof_path, values, compress))
+        }
+        [command, vk_path, proof_path, ref out @ ..] if command == "verify" && out.len() <= 1 => {
+            on_curve!(curve, poly_verify(vk_path, proof_path, out))
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn poly_setup<E: Pairing>(equation: &str, dir: &Path, compress: Compress) -> Result<u8, Box<dyn Error>> {
+    let equation = Equation::<E::ScalarField>::parse(equation)?;
+    let circuit = PolynomialCircuit::new(equation.clone(), None);
+    let stats = debug::statistics(circuit.clone())?;
+    fs::create_dir_all(dir)?;
+    let pk = generate_parameters::<E, _, _>(circuit, &mut rng())?;
+    files::write(dir.join("proving.key"), PROVING_KEY, &pk, compress)?;
+    files::write(dir.join("verifying.key"), VERIFYING_KEY, &pk.vk, compress)?;
+    println!("{}: {} constraints", equation, stats.constraints);
+    println!("wrote {0}/proving.key and {0}/verifying.key", dir.display());
+    Ok(0)
+}
+
+fn poly_prove<E: Pairing>(
+    equation: &str,
+    pk_path: &str,
+    proof_path: &str,
+    values: &[&String],
+    compress: Compress,
+) -> Result<u8, Box<dyn Error>> {
+    let equation = Equation::<E::ScalarField>::parse(equation)?;
+    let values = values
+        .iter()
+        .map(|arg| {
+            let (name, value) = arg.split_once('=').ok_or(USAGE)?;
+            Ok((name.trim().to_string(), parse_field(value.trim())?))
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+    let circuit = PolynomialCircuit::new(equation.clone(), Some(equation.assignment(&values)?));
+    // A proof of a wrong assignment would only fail at verification.
+    if let Some(failure) = debug::check(circuit.clone())?.unsatisfied {
+        return Err(format!("the values do not satisfy {}: {}", equation, failure).into());
+    }
+
+    let pk: ProvingKey<E> = files::read(pk_path, PROVING_KEY)?;
+    let inputs = circuit.public_inputs().expect("the assignment is given");
+    let proof = create_proof(&pk, circuit, &mut rng())?;
+    files::write(proof_path, PROOF, &proof, compress)?;
+    match inputs.first() {
+        Some(out) => println!("{}", out),
+        None => println!("root"),
+    }
+    Ok(0)
+}
+
+fn poly_verify<E: Pairing>(vk_path: &str, proof_path: &str, out: &[&String]) -> Result<u8, Box<dyn Error>> {
+    let vk: VerifyingKey<E> = files::read(vk_path, VERIFYING_KEY)?;
+    let proof: Proof<E> = files::read(proof_path, PROOF)?;
+    let inputs = out.iter().map(|x| parse_field(x)).collect::<Result<Vec<E::ScalarField>, _>>()?;
+    if inputs.len() + 1 != vk.gamma_abc_g1.len() {
+        return Err(format!("this key takes {} public outputs", vk.gamma_abc_g1.len() - 1).into());
+    }
+    if verify_proof(&vk, &inputs, &proof)? {
+        println!("Proof is valid!");
+        Ok(0)
+    } else {
+        eprintln!("Proof is INVALID");
+        Ok(EXIT_INVALID)
+    }
+}
+
+fn recursion(args: &[String]) -> Result<u8, Box<dyn Error>> {
+    use ark_bls12_377::Bls12_377;
+
+    let x = match args {
+        [] => 3u64.into(),
+        [x] => parse_field(x)?,
+        _ => return Err(USAGE.into()),
+    };
+    let pk = pipeline::setup::<Bls12_377, _>(&mut rng())?;
+    let (proof, out) = pipeline::prove(&pk, x, &mut rng())?;
+    println!("BLS12-377 proof of x^3 + x + 5 = {}", out);
+
+    let circuit = VerifierCircuit { vk: pk.vk.clone(), inputs: vec![out], proof: Some(proof) };
+    let (satisfied, constraints) = recursion::check(circuit.clone())?;
+    println!("BW6-761 verifier circuit: {} constraints, public input {}", constraints, recursion::outer_inputs(&[out])[0]);
+    if !satisfied {
+        eprintln!("Verifier circuit is NOT satisfied");
+        return Ok(EXIT_INVALID);
+    }
+
+    let outer_pk = recursion::setup(&pk.vk, 1, &mut rng())?;
+    let outer_proof = recursion::prove(&outer_pk, circuit, &mut rng())?;
+    if recursion::verify(&outer_pk.vk, &[out], &outer_proof)? {
+        println!("BW6-761 proof of the BLS12-377 proof verified!");
+        Ok(0)
+    } else {
+        eprintln!("BW6-761 proof did NOT verify");
+        Ok(EXIT_INVALID)
+    }
+}
+
+fn plonk(args: &[String]) -> Result<u8, Box<dyn Error>> {
+    let (args, _, curve) = options(args)?;
+    let x = match args[..] {
+        [] => "3",
+        [x] => x,
+        _ => return Err(USAGE.into()),
+    };
+    on_curve!(curve, plonk_on(x))
+}
+
+fn plonk_on<E: Pairing>(x: &str) -> Result<u8, Box<dyn Error>> {
+    let x: E::ScalarField = parse_field(x)?;
+    let srs = kzg::setup::<E, _>(64, &mut rng());
+    println!("SRS with {} powers of tau", srs.powers_g1.len());
+
+    let r1cs = |x: Option<E::ScalarField>| {
+        let mode = if x.is_some() { SynthesisMode::Prove { construct_matrices: true } } else { SynthesisMode::Setup };
+        plonk::Circuit::from_r1cs(CubicDemoCircuit { x }, mode)
+    };
+    let circuits = [("R1CS translation", r1cs(None)?, r1cs(Some(x))?), ("cube gate", cubic_circuit(None), cubic_circuit(Some(x)))];
+    let mut code = 0;
+    for (name, setup, witness) in &circuits {
+        let pk = plonk::preprocess(&srs, setup)?;
+        let proof = plonk::create_proof(&pk, witness, &mut rng())?;
+        let inputs = witness.public_values().ok_or("missing public input")?;
+        let verified = plonk::verify_proof(&pk.vk, &inputs, &proof)?;
+        println!(
+            "{}: {} rows, {}-byte proof of x^3 + x + 5 = {}, {}",
+            name,
+            pk.vk.n,
+            proof.compressed_size(),
+            inputs[0],
+            if verified { "verified" } else { "INVALID" }
+        );
+        if !verified {
+            code = EXIT_INVALID;
+        }
+    }
+    Ok(code)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        None => {
+            demo();
+            Ok(0)
+        }
+        Some("setup") => setup(&args[1..]),
+        Some("prove") => prove(&args[1..]),
+        Some("verify") => verify(&args[1..]),
+        Some("ceremony") => ceremony(&args[1..]),
+        Some("verify-ceremony") => verify_ceremony(&args[1..]),
+        Some("check") => check(&args[1..]),
+        Some("poly") => poly(&args[1..]),
+        Some("recursion") => recursion(&args[1..]),
+        Some("plonk") => plonk(&args[1..]),
+        Some(_) => Err(USAGE.into()),
+    };
+
+    match result {
+        Ok(code) => ExitCode::from(code),
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
 
-// Struct representing the cubic equation circuit
-// The circuit will prove knowledge of an 'x' such that x^3 + x + 5 = 35 (or a public value)
-struct CubicDemoCircuit<F: Field> {
-    pub x: Option<F>, // The secret value 'x' (Option because it's not assigned during setup)
-}
-
-// Implement the `ConstraintSynthesizer` trait for the CubicDemoCircuit to define constraints
-impl<F: Field> ConstraintSynthesizer<F> for CubicDemoCircuit<F> {
-    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
-        // Allocate witness variable 'x' for the secret value
-        let x_val = self.x;
-        let x = cs.new_witness_variable(|| x_val.ok_or(SynthesisError::AssignmentMissing))?;
-
-        // Step 1: Create 'tmp_1' to represent x^2 (x * x)
-        let tmp_1_val = x_val.map(|e| e.square()); // tmp_1 = x^2
-        let tmp_1 = cs.new_witness_variable(|| tmp_1_val.ok_or(SynthesisError::AssignmentMissing))?;
-        // Enforce the constraint: x * x = tmp_1
-        cs.enforce_constraint(lc!() + x, lc!() + x, lc!() + tmp_1)?;
-
-        // Step 2: Create 'x_cubed' to represent x^3 (tmp_1 * x)
-        let x_cubed_val = tmp_1_val.map(|mut e| {
-            e.mul_assign(&x_val.unwrap()); // x_cubed = tmp_1 * x
-            e
-        });
-        let x_cubed = cs.new_witness_variable(|| x_cubed_val.ok_or(SynthesisError::AssignmentMissing))?;
-        // Enforce the constraint: tmp_1 * x = x_cubed
-        cs.enforce_constraint(lc!() + tmp_1, lc!() + x, lc!() + x_cubed)?;
-
-        // Step 3: Allocate 'out' as the public output variable for x^3 + x + 5
-        let out = cs.new_input_variable(|| {
-            let mut tmp = x_cubed_val.unwrap();
-            tmp.add_assign(&x_val.unwrap()); // tmp = x_cubed + x
-            tmp.add_assign(F::from(5u32)); // tmp = x_cubed + x + 5
-            Ok(tmp) // This will be the public output
-        })?;
-        
-        // Enforce the final constraint: x^3 + x + 5 = out
-        cs.enforce_constraint(
-            lc!() + x_cubed + x + (F::from(5u32), ConstraintSystem::<F>::one()), // x^3 + x + 5
-            lc!() + ConstraintSystem::<F>::one(), // Identity term for the left-hand side
-            lc!() + out, // Right-hand side is the output variable
-        )?;
-
-        Ok(())
-    }
-}
-
-fn main() {
+// The original demo: prove and verify both cases in memory.
+fn demo() {
     use ark_bls12_381::{Bls12_381, Fr as BlsFr}; // Using the BLS12-381 curve and field for cryptography
     use ark_groth16::Groth16; // The Groth16 zk-SNARK construction
     use ark_snark::SNARK; // Generic SNARK traits
@@ -0,0 +1,119 @@
+// The cubic circuit's setup / prove / verify pipeline, generic over the pairing.
+//
+// Everything below works for any `ark_ec::pairing::Pairing`; `Curve` names the
+// three the CLI offers and `on_curve!` turns that runtime choice back into a
+// type parameter. Keys and proofs carry no curve tag, so a file must be read
+// with the curve it was written for; with the wrong one, decoding fails as the
+// points are not on the curve (or the lengths differ).
+
+use std::fmt;
+use std::str::FromStr;
+
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_relations::r1cs::SynthesisError;
+use ark_std::rand::Rng;
+
+use crate::cubic::CubicDemoCircuit;
+use crate::groth16::{create_proof, generate_parameters, verify_proof, Proof, ProvingKey, VerifyingKey};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Curve {
+    Bn254,
+    Bls12_377,
+    Bls12_381,
+}
+
+impl Curve {
+    pub const ALL: [Curve; 3] = [Curve::Bn254, Curve::Bls12_377, Curve::Bls12_381];
+}
+
+impl fmt::Display for Curve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Curve::Bn254 => "bn254",
+            Curve::Bls12_377 => "bls12-377",
+            Curve::Bls12_381 => "bls12-381",
+        })
+    }
+}
+
+impl FromStr for Curve {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Curve::ALL
+            .into_iter()
+            .find(|c| c.to_string().eq_ignore_ascii_case(s) || c.to_string().replace('-', "_").eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown curve {} (expected bn254, bls12-377 or bls12-381)", s))
+    }
+}
+
+// Calls `$f::<E>($args...)` with E the pairing engine of a runtime `Curve`.
+#[macro_export]
+macro_rules! on_curve {
+    ($curve:expr, $f:ident ( $($arg:expr),* $(,)? )) => {
+        match $curve {
+            $crate::pipeline::Curve::Bn254 => $f::<ark_bn254::Bn254>($($arg),*),
+            $crate::pipeline::Curve::Bls12_377 => $f::<ark_bls12_377::Bls12_377>($($arg),*),
+            $crate::pipeline::Curve::Bls12_381 => $f::<ark_bls12_381::Bls12_381>($($arg),*),
+        }
+    };
+}
+
+// A decimal scalar below the curve's group order.
+pub fn parse_scalar<F: PrimeField>(s: &str) -> Option<F> {
+    F::from_str(s).ok()
+}
+
+// x^3 + x + 5, the public output the cubic circuit proves.
+pub fn cubic_output<F: PrimeField>(x: F) -> F {
+    x * x * x + x + F::from(5u64)
+}
+
+pub fn setup<E: Pairing, R: Rng>(rng: &mut R) -> Result<ProvingKey<E>, SynthesisError> {
+    generate_parameters::<E, _, _>(CubicDemoCircuit::<E::ScalarField> { x: None }, rng)
+}
+
+// A proof of knowledge of x, and the output it proves.
+pub fn prove<E: Pairing, R: Rng>(
+    pk: &ProvingKey<E>,
+    x: E::ScalarField,
+    rng: &mut R,
+) -> Result<(Proof<E>, E::ScalarField), SynthesisError> {
+    Ok((create_proof(pk, CubicDemoCircuit { x: Some(x) }, rng)?, cubic_output(x)))
+}
+
+pub fn verify<E: Pairing>(vk: &VerifyingKey<E>, output: E::ScalarField, proof: &Proof<E>) -> Result<bool, SynthesisError> {
+    verify_proof(vk, &[output], proof)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_std::test_rng;
+
+    fn round_trip<E: Pairing>() -> bool {
+        let mut rng = test_rng();
+        let pk = setup::<E, _>(&mut rng).unwrap();
+        let (proof, out) = prove(&pk, E::ScalarField::from(3u64), &mut rng).unwrap();
+        assert_eq!(out, E::ScalarField::from(35u64));
+        verify(&pk.vk, out, &proof).unwrap() && !verify(&pk.vk, out + E::ScalarField::from(1u64), &proof).unwrap()
+    }
+
+    #[test]
+    fn pipeline_runs_on_every_curve() {
+        for curve in Curve::ALL {
+            assert!(on_curve!(curve, round_trip()), "{}", curve);
+        }
+    }
+
+    #[test]
+    fn curve_names_parse() {
+        for curve in Curve::ALL {
+            assert_eq!(curve.to_string().parse::<Curve>(), Ok(curve));
+        }
+        assert_eq!("BLS12_381".parse::<Curve>(), Ok(Curve::Bls12_381));
+        assert!("secp256k1".parse::<Curve>().is_err());
+    }
+}
@@ -0,0 +1,239 @@
+// PLONK circuits: rows of gates over three wires a, b, c, each row satisfying
+//
+//     q_M a b + q_L a + q_R b + q_O c + q_C + q_H a^3 = 0
+//
+// with the selectors q_* fixed per row. q_H is a custom gate: a cube costs one
+// row instead of two multiplications. Wires are tied to variables, and every
+// use of a variable is one position in the copy constraints the permutation
+// argument enforces. Public inputs get the first rows, a = x_i with q_L = 1,
+// balanced by the public input polynomial at proving time.
+//
+// `from_r1cs` translates any `ConstraintSynthesizer`, so PLONK proves the same
+// circuits as Groth16; `cubic_circuit` is x^3 + x + 5 = out written natively
+// with the custom gate.
+
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError, SynthesisMode};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::groth16::synthesize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Var(pub usize);
+
+// One value per selector; also used for the selector polynomials and their commitments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Selectors<T: CanonicalSerialize + CanonicalDeserialize> {
+    pub q_m: T,
+    pub q_l: T,
+    pub q_r: T,
+    pub q_o: T,
+    pub q_c: T,
+    pub q_h: T,
+}
+
+impl<T: CanonicalSerialize + CanonicalDeserialize> Selectors<T> {
+    pub fn map<U: CanonicalSerialize + CanonicalDeserialize>(&self, mut f: impl FnMut(&T) -> U) -> Selectors<U> {
+        Selectors { q_m: f(&self.q_m), q_l: f(&self.q_l), q_r: f(&self.q_r), q_o: f(&self.q_o), q_c: f(&self.q_c), q_h: f(&self.q_h) }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gate<F: PrimeField> {
+    pub selectors: Selectors<F>,
+    // a, b and c; an unused wire is None and takes no part in the copy constraints.
+    pub wires: [Option<Var>; 3],
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Circuit<F: PrimeField> {
+    // One per variable; None without a witness.
+    pub values: Vec<Option<F>>,
+    pub public_inputs: Vec<Var>,
+    pub gates: Vec<Gate<F>>,
+}
+
+impl<F: PrimeField> Circuit<F> {
+    pub fn new() -> Self {
+        Circuit { values: Vec::new(), public_inputs: Vec::new(), gates: Vec::new() }
+    }
+
+    pub fn witness(&mut self, value: Option<F>) -> Var {
+        self.values.push(value);
+        Var(self.values.len() - 1)
+    }
+
+    pub fn input(&mut self, value: Option<F>) -> Var {
+        let v = self.witness(value);
+        self.public_inputs.push(v);
+        v
+    }
+
+    pub fn gate(&mut self, selectors: Selectors<F>, wires: [Option<Var>; 3]) {
+        self.gates.push(Gate { selectors, wires });
+    }
+
+    // Public input rows first, then the gates.
+    pub fn num_rows(&self) -> usize {
+        self.public_inputs.len() + self.gates.len()
+    }
+
+    pub fn public_values(&self) -> Option<Vec<F>> {
+        self.public_inputs.iter().map(|v| self.values[v.0]).collect()
+    }
+
+    // A new variable equal to sum c_i x_i + constant, with one addition gate
+    // per two terms.
+    fn linear(&mut self, terms: &[(F, Var)], constant: F) -> Var {
+        let value = |circuit: &Self, terms: &[(F, Var)]| {
+            terms.iter().try_fold(constant, |acc, (c, v)| circuit.values[v.0].map(|x| acc + *c * x))
+        };
+        match terms {
+            [(c, v)] if c.is_one() && constant.is_zero() => *v,
+            [] | [_] => {
+                let w = self.witness(value(self, terms));
+                let (q_l, a) = terms.first().map_or((F::zero(), None), |(c, v)| (*c, Some(*v)));
+                self.gate(Selectors { q_l, q_o: -F::one(), q_c: constant, ..Default::default() }, [a, None, Some(w)]);
+                w
+            }
+            [(c1, v1), (c2, v2), rest @ ..] => {
+                let w = self.witness(value(self, &terms[..2]));
+                let selectors = Selectors { q_l: *c1, q_r: *c2, q_o: -F::one(), q_c: constant, ..Default::default() };
+                self.gate(selectors, [Some(*v1), Some(*v2), Some(w)]);
+                let mut remaining = vec![(F::one(), w)];
+                remaining.extend_from_slice(rest);
+                self.linear(&remaining, F::zero())
+            }
+        }
+    }
+
+    // sum c_i x_i + constant = 0; the last gate takes three terms.
+    fn enforce_zero(&mut self, terms: &[(F, Var)], constant: F) {
+        if terms.len() > 3 {
+            let w = self.linear(&terms[..2], F::zero());
+            let mut remaining = vec![(F::one(), w)];
+            remaining.extend_from_slice(&terms[2..]);
+            return self.enforce_zero(&remaining, constant);
+        }
+        let coefficient = |i: usize| terms.get(i).map_or(F::zero(), |(c, _)| *c);
+        let wire = |i: usize| terms.get(i).map(|(_, v)| *v);
+        let selectors = Selectors { q_l: coefficient(0), q_r: coefficient(1), q_o: coefficient(2), q_c: constant, ..Default::default() };
+        self.gate(selectors, [wire(0), wire(1), wire(2)]);
+    }
+
+    // The circuit's R1CS constraints as PLONK gates. A * B = C becomes one
+    // multiplication gate on the three combinations, each reduced to a single
+    // wire by addition gates; when A or B is a constant the constraint is
+    // linear and needs no multiplication.
+    pub fn from_r1cs<C: ConstraintSynthesizer<F>>(circuit: C, mode: SynthesisMode) -> Result<Self, SynthesisError> {
+        let cs = synthesize(circuit, mode)?;
+        let matrices = cs.to_matrices().ok_or(SynthesisError::MissingCS)?;
+        let (instance, witness) = {
+            let inner = cs.borrow().ok_or(SynthesisError::MissingCS)?;
+            (inner.instance_assignment.clone(), inner.witness_assignment.clone())
+        };
+        let value = |assignment: &[F], i: usize| assignment.get(i).copied();
+
+        let mut plonk = Circuit::new();
+        // vars[j] for R1CS variable j >= 1; j = 0 is the constant 1.
+        let mut vars = vec![Var(usize::MAX)];
+        for j in 1..matrices.num_instance_variables {
+            vars.push(plonk.input(value(&instance, j)));
+        }
+        for j in 0..matrices.num_witness_variables {
+            vars.push(plonk.witness(value(&witness, j)));
+        }
+
+        // (terms, constant) of a matrix row.
+        let split = |row: &[(F, usize)]| {
+            let constant = row.iter().filter(|(_, j)| *j == 0).map(|(c, _)| *c).sum::<F>();
+            let terms: Vec<(F, Var)> = row.iter().filter(|(_, j)| *j != 0).map(|(c, j)| (*c, vars[*j])).collect();
+            (terms, constant)
+        };
+        let scaled = |terms: &[(F, Var)], k: F| terms.iter().map(|(c, v)| (*c * k, *v)).collect::<Vec<_>>();
+
+        for i in 0..matrices.num_constraints {
+            let (a, a_const) = split(&matrices.a[i]);
+            let (b, b_const) = split(&matrices.b[i]);
+            let (c, c_const) = split(&matrices.c[i]);
+            if a.is_empty() || b.is_empty() {
+                // k * L - C = 0 with L the other side.
+                let (k, l, l_const) = if a.is_empty() { (a_const, b, b_const) } else { (b_const, a, a_const) };
+                let mut terms = scaled(&l, k);
+                terms.extend(scaled(&c, -F::one()));
+                plonk.enforce_zero(&terms, k * l_const - c_const);
+                continue;
+            }
+            let a = plonk.linear(&a, a_const);
+            let b = plonk.linear(&b, b_const);
+            let (q_o, c) = match c.as_slice() {
+                [] => (F::zero(), None),
+                [(coefficient, v)] => (-*coefficient, Some(*v)),
+                _ => (-F::one(), Some(plonk.linear(&c, F::zero()))),
+            };
+            plonk.gate(Selectors { q_m: F::one(), q_o, q_c: -c_const, ..Default::default() }, [Some(a), Some(b), c]);
+        }
+        Ok(plonk)
+    }
+}
+
+// x^3 + x + 5 = out in one row with the cube gate: x^3 + x + 5 - out = 0.
+pub fn cubic_circuit<F: PrimeField>(x: Option<F>) -> Circuit<F> {
+    let mut circuit = Circuit::new();
+    let out = circuit.input(x.map(|x| x * x * x + x + F::from(5u64)));
+    let x = circuit.witness(x);
+    let selectors = Selectors { q_h: F::one(), q_l: F::one(), q_c: F::from(5u64), q_o: -F::one(), ..Default::default() };
+    circuit.gate(selectors, [Some(x), None, Some(out)]);
+    circuit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::CubicDemoCircuit;
+    use ark_bls12_381::Fr;
+
+    // Every gate holds for the circuit's own values.
+    fn satisfied(circuit: &Circuit<Fr>) -> bool {
+        let value = |w: Option<Var>| w.map_or(Fr::from(0u64), |v| circuit.values[v.0].unwrap());
+        circuit.gates.iter().all(|gate| {
+            let (a, b, c) = (value(gate.wires[0]), value(gate.wires[1]), value(gate.wires[2]));
+            let q = &gate.selectors;
+            q.q_m * a * b + q.q_l * a + q.q_r * b + q.q_o * c + q.q_c + q.q_h * a * a * a == Fr::from(0u64)
+        })
+    }
+
+    #[test]
+    fn cubic_demo_circuit_translates_to_three_gates() {
+        let circuit = Circuit::from_r1cs(CubicDemoCircuit { x: Some(Fr::from(3u64)) }, SynthesisMode::Prove { construct_matrices: true }).unwrap();
+        assert_eq!((circuit.public_inputs.len(), circuit.gates.len()), (1, 3));
+        assert_eq!(circuit.public_values(), Some(vec![Fr::from(35u64)]));
+        assert!(satisfied(&circuit));
+
+        let setup = Circuit::<Fr>::from_r1cs(CubicDemoCircuit { x: None }, SynthesisMode::Setup).unwrap();
+        assert_eq!(setup.gates, circuit.gates);
+        assert_eq!(setup.public_values(), None);
+    }
+
+    #[test]
+    fn long_linear_combinations_are_split() {
+        use crate::polynomial::{Equation, PolynomialCircuit};
+
+        // Products with a constant, sums of many terms and constants on every side.
+        let equation = Equation::parse("3x^2*y + 2x*y^2 - 7x + 5y - z + 11").unwrap();
+        let values = vec![Fr::from(2u64), Fr::from(3u64), Fr::from(4u64)];
+        let r1cs = PolynomialCircuit::new(equation, Some(values));
+        let circuit = Circuit::from_r1cs(r1cs, SynthesisMode::Prove { construct_matrices: true }).unwrap();
+        assert!(satisfied(&circuit));
+        // 36 + 36 - 14 + 15 - 4 + 11.
+        assert_eq!(circuit.public_values(), Some(vec![Fr::from(80u64)]));
+    }
+
+    #[test]
+    fn custom_gate_needs_one_row() {
+        let circuit = cubic_circuit(Some(Fr::from(3u64)));
+        assert_eq!(circuit.num_rows(), 2);
+        assert!(satisfied(&circuit));
+        assert_eq!(circuit.public_values(), Some(vec![Fr::from(35u64)]));
+    }
+}
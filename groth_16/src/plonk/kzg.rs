@@ -0,0 +1,74 @@
+// KZG polynomial commitments (Kate, Zaverucha, Goldberg, 2010).
+//
+// The structured reference string holds [tau^i]_1 up to some degree and
+// [tau]_2. A polynomial p commits to [p(tau)]_1; an opening at z is the
+// commitment to q(X) = (p(X) - p(z)) / (X - z), checked with
+//
+//     e([q], [tau]_2 - [z]_2) = e([p] - [p(z)]_1, [1]_2)
+//
+// Unlike the Groth16 CRS, the SRS does not depend on the circuit: one
+// powers-of-tau serves every PLONK circuit up to its degree. `setup` samples
+// tau locally, for tests and benchmarks.
+
+use ark_ec::pairing::Pairing;
+use ark_ec::scalar_mul::ScalarMul;
+use ark_ec::{CurveGroup, PrimeGroup, VariableBaseMSM};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::Rng;
+use ark_std::UniformRand;
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Srs<E: Pairing> {
+    // [tau^i]_1 for i = 0..=max_degree.
+    pub powers_g1: Vec<E::G1Affine>,
+    pub g2: E::G2Affine,
+    pub tau_g2: E::G2Affine,
+}
+
+impl<E: Pairing> Srs<E> {
+    pub fn max_degree(&self) -> usize {
+        self.powers_g1.len().saturating_sub(1)
+    }
+}
+
+pub fn setup<E: Pairing, R: Rng>(max_degree: usize, rng: &mut R) -> Srs<E> {
+    let tau = E::ScalarField::rand(rng);
+    let powers: Vec<E::ScalarField> =
+        std::iter::successors(Some(E::ScalarField::from(1u64)), |p| Some(*p * tau)).take(max_degree + 1).collect();
+    let g2 = E::G2::generator();
+    Srs { powers_g1: E::G1::generator().batch_mul(&powers), g2: g2.into_affine(), tau_g2: (g2 * tau).into_affine() }
+}
+
+// [p(tau)]_1 for p given by its coefficients; the caller makes sure the SRS is long enough.
+pub fn commit<E: Pairing>(powers: &[E::G1Affine], coeffs: &[E::ScalarField]) -> E::G1Affine {
+    E::G1::msm_unchecked(&powers[..coeffs.len()], coeffs).into_affine()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groth16::aggregate::kzg_quotient;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective};
+    use ark_ec::AffineRepr;
+    use ark_poly::{DenseUVPolynomial, Polynomial};
+    use ark_std::{test_rng, Zero};
+
+    #[test]
+    fn opening_verifies() {
+        let mut rng = test_rng();
+        let srs = setup::<Bls12_381, _>(16, &mut rng);
+        assert_eq!(srs.max_degree(), 16);
+
+        let p = ark_poly::univariate::DensePolynomial::<Fr>::rand(16, &mut rng);
+        let z = Fr::rand(&mut rng);
+        let commitment = commit::<Bls12_381>(&srs.powers_g1, &p.coeffs);
+        let opening = commit::<Bls12_381>(&srs.powers_g1, &kzg_quotient(&p.coeffs, z));
+        let check = |value: Fr| {
+            let lhs = (G1Projective::from(commitment) - G1Projective::generator() * value).into_affine();
+            let rhs_g2 = (srs.tau_g2.into_group() - srs.g2 * z).into_affine();
+            Bls12_381::multi_pairing([opening, (-lhs.into_group()).into_affine()], [rhs_g2, srs.g2]).is_zero()
+        };
+        assert!(check(p.evaluate(&z)));
+        assert!(!check(p.evaluate(&z) + Fr::from(1u64)));
+    }
+}
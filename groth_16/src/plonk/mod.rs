@@ -0,0 +1,274 @@
+// PLONK as an alternative backend to Groth16 (Gabizon, Williamson, Ciobotaru,
+// "PLONK: Permutations over Lagrange-bases for Oecumenical Noninteractive
+// arguments of Knowledge", 2019), in the paper's latest version, where the
+// verifier rebuilds the linearization's opening itself: a proof is six G1
+// elements, the quotient pieces and six field elements.
+//
+//     kzg         universal SRS, commitments and openings
+//     circuit     gates with selectors q_M, q_L, q_R, q_O, q_C and the cube gate q_H
+//     preprocess  selector and permutation polynomials, committed under the SRS
+//     prover      wire, grand product and quotient commitments, opened at zeta
+//     verifier    one MSM and two pairings
+//
+// Against Groth16 the trade is a circuit-independent setup (any circuit up to
+// the SRS degree, no per-circuit ceremony) for larger proofs and slower
+// verification; `benches/plonk.rs` puts numbers on both for the cubic.
+
+pub mod circuit;
+pub mod kzg;
+pub mod preprocess;
+pub mod prover;
+pub mod verifier;
+
+pub use circuit::{cubic_circuit, Circuit, Gate, Selectors, Var};
+pub use preprocess::preprocess;
+pub use prover::create_proof;
+pub use verifier::verify_proof;
+
+use std::fmt;
+
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::EvaluationDomain;
+use ark_relations::r1cs::SynthesisError;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::groth16::qap::Domain;
+use crate::transcript::Transcript;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlonkError {
+    SrsTooSmall { needed: usize, available: usize },
+    // The witness violates a gate, so the quotient is not a polynomial.
+    Unsatisfied,
+    PublicInputs { expected: usize, found: usize },
+    Synthesis(SynthesisError),
+}
+
+impl fmt::Display for PlonkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlonkError::SrsTooSmall { needed, available } => {
+                write!(f, "the SRS has {} powers, the circuit needs {}", available, needed)
+            }
+            PlonkError::Unsatisfied => write!(f, "the witness does not satisfy the circuit"),
+            PlonkError::PublicInputs { expected, found } => {
+                write!(f, "expected {} public inputs, found {}", expected, found)
+            }
+            PlonkError::Synthesis(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for PlonkError {}
+
+impl From<SynthesisError> for PlonkError {
+    fn from(e: SynthesisError) -> Self {
+        PlonkError::Synthesis(e)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifyingKey<E: Pairing> {
+    // Rows in the domain, a power of two.
+    pub n: usize,
+    pub num_public: usize,
+    pub selectors: Selectors<E::G1Affine>,
+    // [sigma_1], [sigma_2], [sigma_3].
+    pub sigmas: Vec<E::G1Affine>,
+    pub g1: E::G1Affine,
+    pub g2: E::G2Affine,
+    pub tau_g2: E::G2Affine,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProvingKey<E: Pairing> {
+    pub vk: VerifyingKey<E>,
+    // The SRS cut to the n + 3 powers the prover uses.
+    pub powers_g1: Vec<E::G1Affine>,
+    pub selectors: Selectors<DensePolynomial<E::ScalarField>>,
+    pub sigmas: Vec<DensePolynomial<E::ScalarField>>,
+    // sigma_j(omega^i), as used by the grand product.
+    pub sigma_evals: Vec<Vec<E::ScalarField>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Evaluations<F: PrimeField> {
+    pub a: F,
+    pub b: F,
+    pub c: F,
+    pub s1: F,
+    pub s2: F,
+    // z(zeta omega).
+    pub z_omega: F,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<E: Pairing> {
+    pub a: E::G1Affine,
+    pub b: E::G1Affine,
+    pub c: E::G1Affine,
+    pub z: E::G1Affine,
+    // t(X) = sum_i X^(i n) t_i(X).
+    pub t: Vec<E::G1Affine>,
+    pub evaluations: Evaluations<E::ScalarField>,
+    pub w_zeta: E::G1Affine,
+    pub w_zeta_omega: E::G1Affine,
+}
+
+// The circuit's domain: at least one row per public input and gate, and two
+// rows so that omega != 1.
+pub(crate) fn domain<F: PrimeField>(rows: usize) -> Result<Domain<F>, PlonkError> {
+    Domain::new(rows.max(2)).ok_or(PlonkError::Synthesis(SynthesisError::PolynomialDegreeTooLarge))
+}
+
+// Column j of the wiring is the coset k_j H; k_1 = g and k_2 = g^2 for the
+// multiplicative generator g, which is in neither H nor g H.
+pub(crate) fn shifts<F: PrimeField>() -> [F; 3] {
+    [F::one(), F::GENERATOR, F::GENERATOR.square()]
+}
+
+// Powers of tau the prover needs: the grand product has degree n + 2.
+pub(crate) fn srs_size(n: usize) -> usize {
+    n + 3
+}
+
+// Quotient pieces: t has degree at most 3n + 5, cut into pieces of n coefficients.
+pub(crate) fn num_pieces(n: usize) -> usize {
+    (3 * n + 6).div_ceil(n)
+}
+
+pub(crate) fn transcript<E: Pairing>(vk: &VerifyingKey<E>, inputs: &[E::ScalarField]) -> Transcript {
+    let mut transcript = Transcript::new(b"plonk");
+    transcript.append(vk);
+    transcript.append(&inputs.to_vec());
+    transcript
+}
+
+pub(crate) struct Challenges<F> {
+    pub beta: F,
+    pub gamma: F,
+    pub alpha: F,
+    pub zeta: F,
+    pub v: F,
+    pub u: F,
+}
+
+// Replays the transcript of a finished proof; the prover derives the same
+// challenges round by round.
+pub(crate) fn challenges<E: Pairing>(vk: &VerifyingKey<E>, inputs: &[E::ScalarField], proof: &Proof<E>) -> Challenges<E::ScalarField> {
+    let mut transcript = transcript(vk, inputs);
+    transcript.append(&[proof.a, proof.b, proof.c]);
+    let beta = transcript.challenge();
+    let gamma = transcript.challenge();
+    transcript.append(&proof.z);
+    let alpha = transcript.challenge();
+    transcript.append(&proof.t);
+    let zeta = transcript.challenge();
+    transcript.append(&proof.evaluations);
+    let v = transcript.challenge();
+    transcript.append(&[proof.w_zeta, proof.w_zeta_omega]);
+    let u = transcript.challenge();
+    Challenges { beta, gamma, alpha, zeta, v, u }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::CubicDemoCircuit;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_relations::r1cs::SynthesisMode;
+    use ark_std::test_rng;
+
+    fn cubic(x: Option<u64>) -> Circuit<Fr> {
+        let mode = match x {
+            Some(_) => SynthesisMode::Prove { construct_matrices: true },
+            None => SynthesisMode::Setup,
+        };
+        Circuit::from_r1cs(CubicDemoCircuit { x: x.map(Fr::from) }, mode).unwrap()
+    }
+
+    #[test]
+    fn cubic_demo_circuit_proves_and_verifies() {
+        let mut rng = test_rng();
+        let srs = kzg::setup::<Bls12_381, _>(32, &mut rng);
+        let pk = preprocess(&srs, &cubic(None)).unwrap();
+
+        let proof = create_proof(&pk, &cubic(Some(3)), &mut rng).unwrap();
+        assert!(verify_proof(&pk.vk, &[Fr::from(35u64)], &proof).unwrap());
+        assert!(!verify_proof(&pk.vk, &[Fr::from(36u64)], &proof).unwrap());
+        assert_eq!(
+            verify_proof(&pk.vk, &[], &proof),
+            Err(PlonkError::PublicInputs { expected: 1, found: 0 })
+        );
+
+        let mut bytes = Vec::new();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(Proof::<Bls12_381>::deserialize_compressed(bytes.as_slice()).unwrap(), proof);
+    }
+
+    #[test]
+    fn one_srs_serves_several_circuits() {
+        use crate::polynomial::{Equation, PolynomialCircuit};
+
+        let mut rng = test_rng();
+        let srs = kzg::setup::<Bls12_381, _>(64, &mut rng);
+
+        let native = preprocess(&srs, &cubic_circuit::<Fr>(None)).unwrap();
+        let proof = create_proof(&native, &cubic_circuit(Some(Fr::from(3u64))), &mut rng).unwrap();
+        assert!(verify_proof(&native.vk, &[Fr::from(35u64)], &proof).unwrap());
+        // The custom gate needs fewer rows than the translated R1CS.
+        assert!(native.vk.n < preprocess(&srs, &cubic(None)).unwrap().vk.n);
+
+        let equation = Equation::parse("x^5 - 3x*y + y^2 + 7").unwrap();
+        let setup = Circuit::from_r1cs(PolynomialCircuit::<Fr>::new(equation.clone(), None), SynthesisMode::Setup).unwrap();
+        let pk = preprocess(&srs, &setup).unwrap();
+        let values = Some(vec![Fr::from(2u64), Fr::from(5u64)]);
+        let circuit = Circuit::from_r1cs(PolynomialCircuit::new(equation, values), SynthesisMode::Prove { construct_matrices: true }).unwrap();
+        let proof = create_proof(&pk, &circuit, &mut rng).unwrap();
+        assert!(verify_proof(&pk.vk, &circuit.public_values().unwrap(), &proof).unwrap());
+        assert!(!verify_proof(&native.vk, &[Fr::from(35u64)], &proof).unwrap());
+
+        let small = kzg::setup::<Bls12_381, _>(4, &mut rng);
+        assert_eq!(preprocess(&small, &setup), Err(PlonkError::SrsTooSmall { needed: pk.vk.n + 3, available: 5 }));
+    }
+
+    #[test]
+    fn tampered_proofs_are_rejected() {
+        let mut rng = test_rng();
+        let srs = kzg::setup::<Bls12_381, _>(32, &mut rng);
+        let pk = preprocess(&srs, &cubic(None)).unwrap();
+        let proof = create_proof(&pk, &cubic(Some(3)), &mut rng).unwrap();
+        let inputs = [Fr::from(35u64)];
+
+        let mut bad = proof.clone();
+        bad.evaluations.a += Fr::from(1u64);
+        assert!(!verify_proof(&pk.vk, &inputs, &bad).unwrap());
+        let mut bad = proof.clone();
+        bad.t.swap(0, 1);
+        assert!(!verify_proof(&pk.vk, &inputs, &bad).unwrap());
+        let mut bad = proof.clone();
+        bad.t.pop();
+        assert!(!verify_proof(&pk.vk, &inputs, &bad).unwrap());
+        let mut bad = proof;
+        std::mem::swap(&mut bad.w_zeta, &mut bad.w_zeta_omega);
+        assert!(!verify_proof(&pk.vk, &inputs, &bad).unwrap());
+    }
+
+    #[test]
+    fn unsatisfied_witness_is_an_error() {
+        let mut rng = test_rng();
+        let srs = kzg::setup::<Bls12_381, _>(32, &mut rng);
+        let pk = preprocess(&srs, &cubic_circuit::<Fr>(None)).unwrap();
+
+        // out = 36 for x = 3.
+        let mut circuit = cubic_circuit(Some(Fr::from(3u64)));
+        circuit.values[circuit.public_inputs[0].0] = Some(Fr::from(36u64));
+        assert_eq!(create_proof(&pk, &circuit, &mut rng).err(), Some(PlonkError::Unsatisfied));
+        assert_eq!(
+            create_proof(&pk, &cubic_circuit(None), &mut rng).err(),
+            Some(PlonkError::Synthesis(SynthesisError::AssignmentMissing))
+        );
+    }
+}
@@ -0,0 +1,123 @@
+// Preprocessing: the circuit's fixed polynomials, committed under the SRS.
+//
+// Row i of the domain H = <omega> holds public input i for i < l, then gate
+// i - l, then padding rows with every selector zero. The selector q_*(X)
+// interpolates the row values. For the copy constraints each wire position
+// (column j, row i) is named by k_j omega^i; sigma_j(omega^i) is the name of
+// the next position in the cycle of positions holding the same variable, so
+// the permutation sigma fixes exactly the assignments that agree along wires.
+//
+// Nothing here is secret: anyone can rerun `preprocess` from the SRS and the
+// circuit and check the verifying key.
+
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{DenseUVPolynomial, EvaluationDomain};
+
+use super::circuit::{Circuit, Selectors, Var};
+use super::kzg::{commit, Srs};
+use super::{domain, shifts, srs_size, PlonkError, ProvingKey, VerifyingKey};
+
+// The variable on each wire position, columns a, b, c of n rows; None for
+// unused positions and padding.
+pub(crate) fn wiring<F: PrimeField>(circuit: &Circuit<F>, n: usize) -> [Vec<Option<Var>>; 3] {
+    let mut columns = [vec![None; n], vec![None; n], vec![None; n]];
+    for (i, v) in circuit.public_inputs.iter().enumerate() {
+        columns[0][i] = Some(*v);
+    }
+    let l = circuit.public_inputs.len();
+    for (i, gate) in circuit.gates.iter().enumerate() {
+        for (column, wire) in columns.iter_mut().zip(gate.wires) {
+            column[l + i] = wire;
+        }
+    }
+    columns
+}
+
+pub fn preprocess<E: Pairing>(srs: &Srs<E>, circuit: &Circuit<E::ScalarField>) -> Result<ProvingKey<E>, PlonkError> {
+    let domain = domain::<E::ScalarField>(circuit.num_rows())?;
+    let n = domain.size();
+    if srs.powers_g1.len() < srs_size(n) {
+        return Err(PlonkError::SrsTooSmall { needed: srs_size(n), available: srs.powers_g1.len() });
+    }
+    let powers_g1 = srs.powers_g1[..srs_size(n)].to_vec();
+    let l = circuit.public_inputs.len();
+
+    let mut rows = vec![Selectors::default(); n];
+    for row in rows.iter_mut().take(l) {
+        row.q_l = E::ScalarField::from(1u64);
+    }
+    for (row, gate) in rows[l..].iter_mut().zip(&circuit.gates) {
+        *row = gate.selectors;
+    }
+    let interpolate = |evals: Vec<E::ScalarField>| DensePolynomial::from_coefficients_vec(domain.ifft(&evals));
+    let column = |f: fn(&Selectors<E::ScalarField>) -> E::ScalarField| interpolate(rows.iter().map(f).collect());
+    let selectors = Selectors {
+        q_m: column(|s| s.q_m),
+        q_l: column(|s| s.q_l),
+        q_r: column(|s| s.q_r),
+        q_o: column(|s| s.q_o),
+        q_c: column(|s| s.q_c),
+        q_h: column(|s| s.q_h),
+    };
+
+    // Positions (j, i) of each variable, in order, linked into cycles.
+    let wires = wiring(circuit, n);
+    let mut positions = vec![Vec::new(); circuit.values.len()];
+    for (j, column) in wires.iter().enumerate() {
+        for (i, wire) in column.iter().enumerate() {
+            if let Some(v) = wire {
+                positions[v.0].push((j, i));
+            }
+        }
+    }
+    let k = shifts::<E::ScalarField>();
+    let elements: Vec<E::ScalarField> = domain.elements().collect();
+    let mut sigma_evals: Vec<Vec<E::ScalarField>> = k.iter().map(|k| elements.iter().map(|w| *k * w).collect()).collect();
+    for cycle in &positions {
+        for (p, (j, i)) in cycle.iter().enumerate() {
+            let (next_j, next_i) = cycle[(p + 1) % cycle.len()];
+            sigma_evals[*j][*i] = k[next_j] * elements[next_i];
+        }
+    }
+    let sigmas: Vec<_> = sigma_evals.iter().map(|evals| interpolate(evals.clone())).collect();
+
+    let vk = VerifyingKey {
+        n,
+        num_public: l,
+        selectors: selectors.map(|q| commit::<E>(&powers_g1, &q.coeffs)),
+        sigmas: sigmas.iter().map(|s| commit::<E>(&powers_g1, &s.coeffs)).collect(),
+        g1: srs.powers_g1[0],
+        g2: srs.g2,
+        tau_g2: srs.tau_g2,
+    };
+    Ok(ProvingKey { vk, powers_g1, selectors, sigmas, sigma_evals })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plonk::{cubic_circuit, kzg};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::Polynomial;
+    use ark_std::test_rng;
+
+    #[test]
+    fn sigma_links_every_use_of_a_variable() {
+        let srs = kzg::setup::<Bls12_381, _>(8, &mut test_rng());
+        let circuit = cubic_circuit::<Fr>(None);
+        let pk = preprocess(&srs, &circuit).unwrap();
+        assert_eq!(pk.vk.n, 2);
+
+        // out sits at (a, 0) and (c, 1) and the two swap; x at (a, 1) is alone.
+        let w = domain::<Fr>(2).unwrap().group_gen();
+        let k = shifts::<Fr>();
+        assert_eq!(pk.sigma_evals[0], vec![k[2] * w, w]);
+        assert_eq!(pk.sigma_evals[2], vec![k[2], k[0]]);
+        assert_eq!(pk.sigma_evals[1], vec![k[1], k[1] * w]);
+        // q_L is 1 on both rows, the public input's and the cube gate's.
+        assert_eq!(pk.selectors.q_l.evaluate(&w), Fr::from(1u64));
+        assert_eq!(pk.selectors.q_l.coeffs, vec![Fr::from(1u64)]);
+    }
+}
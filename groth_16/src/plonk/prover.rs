@@ -0,0 +1,222 @@
+// The PLONK prover, in five rounds with Fiat-Shamir challenges between them.
+//
+//     1. wires a, b, c, each blinded by (b_1 X + b_2) Z_H
+//     2. beta, gamma; the grand product z with z(1) = 1 and
+//            z(omega^(i+1)) = z(omega^i) prod_j (w_j + beta k_j omega^i + gamma) / (w_j + beta sigma_j + gamma)
+//        blinded by (b_1 X^2 + b_2 X + b_3) Z_H
+//     3. alpha; the quotient t = (gate + PI + alpha perm + alpha^2 (z - 1) L_1) / Z_H,
+//        computed on a coset of the 8n-th roots and committed in pieces
+//     4. zeta; the evaluations a, b, c, sigma_1, sigma_2 at zeta and z at zeta omega
+//     5. v; the linearization r, which is linear in the committed polynomials
+//        once the evaluations are fixed, and the openings
+//            W_zeta = (r + v a + v^2 b + v^3 c + v^4 sigma_1 + v^5 sigma_2) / (X - zeta)
+//            W_zeta_omega = z / (X - zeta omega)
+//        where the division drops the remainder, i.e. the values at the point.
+//
+// t exists only if every gate holds and the wiring is consistent, so an
+// unsatisfied witness shows up as a remainder in the division by Z_H.
+
+use ark_ec::pairing::Pairing;
+use ark_ff::{FftField, Field, PrimeField};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{DenseUVPolynomial, EvaluationDomain, Polynomial};
+use ark_relations::r1cs::SynthesisError;
+use ark_std::rand::Rng;
+use ark_std::{One, UniformRand, Zero};
+
+use super::kzg::commit;
+use super::preprocess::wiring;
+use super::{domain, num_pieces, shifts, transcript, Circuit, Evaluations, PlonkError, Proof, ProvingKey, Var};
+use crate::groth16::aggregate::kzg_quotient;
+use crate::groth16::qap::Domain;
+
+type Poly<F> = DensePolynomial<F>;
+
+// p + (sum_i b_i X^i) Z_H for random b_i.
+fn blind<F: PrimeField, R: Rng>(p: Poly<F>, n: usize, count: usize, rng: &mut R) -> Poly<F> {
+    let mut coeffs = p.coeffs;
+    coeffs.resize(n + count, F::zero());
+    for i in 0..count {
+        let b = F::rand(rng);
+        coeffs[n + i] += b;
+        coeffs[i] -= b;
+    }
+    Poly::from_coefficients_vec(coeffs)
+}
+
+// p(omega X).
+fn shifted<F: PrimeField>(p: &Poly<F>, omega: F) -> Poly<F> {
+    let mut power = F::one();
+    Poly::from_coefficients_vec(
+        p.coeffs
+            .iter()
+            .map(|c| {
+                let out = *c * power;
+                power *= omega;
+                out
+            })
+            .collect(),
+    )
+}
+
+pub fn create_proof<E: Pairing, R: Rng>(
+    pk: &ProvingKey<E>,
+    circuit: &Circuit<E::ScalarField>,
+    rng: &mut R,
+) -> Result<Proof<E>, PlonkError> {
+    let vk = &pk.vk;
+    let n = vk.n;
+    let domain = domain::<E::ScalarField>(n)?;
+    if circuit.num_rows() > n {
+        return Err(PlonkError::Synthesis(SynthesisError::PolynomialDegreeTooLarge));
+    }
+    let inputs = circuit.public_values().ok_or(SynthesisError::AssignmentMissing)?;
+    if inputs.len() != vk.num_public {
+        return Err(PlonkError::PublicInputs { expected: vk.num_public, found: inputs.len() });
+    }
+    let value = |v: &Option<Var>| v.map_or(Ok(E::ScalarField::zero()), |v| circuit.values[v.0].ok_or(SynthesisError::AssignmentMissing));
+    let wires = wiring(circuit, n)
+        .iter()
+        .map(|column| column.iter().map(value).collect::<Result<Vec<_>, _>>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let interpolate = |evals: &[E::ScalarField]| Poly::from_coefficients_vec(domain.ifft(evals));
+    let mut transcript = transcript(vk, &inputs);
+
+    // Round 1.
+    let [a, b, c] = [0, 1, 2].map(|j| blind(interpolate(&wires[j]), n, 2, rng));
+    let [a_commitment, b_commitment, c_commitment] = [&a, &b, &c].map(|p| commit::<E>(&pk.powers_g1, &p.coeffs));
+    transcript.append(&[a_commitment, b_commitment, c_commitment]);
+
+    // Round 2.
+    let beta: E::ScalarField = transcript.challenge();
+    let gamma: E::ScalarField = transcript.challenge();
+    let k = shifts::<E::ScalarField>();
+    let elements: Vec<E::ScalarField> = domain.elements().collect();
+    let mut numerators = Vec::with_capacity(n);
+    let mut denominators = Vec::with_capacity(n);
+    for i in 0..n {
+        let (mut num, mut den) = (E::ScalarField::one(), E::ScalarField::one());
+        for j in 0..3 {
+            num *= wires[j][i] + beta * k[j] * elements[i] + gamma;
+            den *= wires[j][i] + beta * pk.sigma_evals[j][i] + gamma;
+        }
+        numerators.push(num);
+        denominators.push(den);
+    }
+    ark_ff::batch_inversion(&mut denominators);
+    let mut z_evals = vec![E::ScalarField::one()];
+    for i in 0..n - 1 {
+        z_evals.push(z_evals[i] * numerators[i] * denominators[i]);
+    }
+    if z_evals[n - 1] * numerators[n - 1] * denominators[n - 1] != E::ScalarField::one() {
+        return Err(PlonkError::Unsatisfied);
+    }
+    let z = blind(interpolate(&z_evals), n, 3, rng);
+    let z_commitment = commit::<E>(&pk.powers_g1, &z.coeffs);
+    transcript.append(&z_commitment);
+
+    // Round 3, on the coset g <omega_8n>, where Z_H does not vanish.
+    let alpha: E::ScalarField = transcript.challenge();
+    let big = Domain::<E::ScalarField>::new(8 * n).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+    let coset = big.get_coset(E::ScalarField::GENERATOR).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+    let on_coset = |p: &Poly<E::ScalarField>| coset.fft(&p.coeffs);
+    let mut pi = vec![E::ScalarField::zero(); n];
+    for (p, x) in pi.iter_mut().zip(&inputs) {
+        *p = -*x;
+    }
+    let mut l1 = vec![E::ScalarField::zero(); n];
+    l1[0] = E::ScalarField::one();
+    let [a_c, b_c, c_c, z_c, z_omega_c] = [&a, &b, &c, &z, &shifted(&z, domain.group_gen())].map(on_coset);
+    let [pi_c, l1_c] = [interpolate(&pi), interpolate(&l1)].map(|p| on_coset(&p));
+    let q = pk.selectors.map(on_coset);
+    let s = pk.sigmas.iter().map(on_coset).collect::<Vec<_>>();
+    // (g omega_8n^i)^n - 1 repeats with period 8.
+    let z_h_inv: Vec<E::ScalarField> = coset
+        .elements()
+        .take(8)
+        .map(|x| (x.pow([n as u64]) - E::ScalarField::one()).inverse().expect("Z_H is non-zero off H"))
+        .collect();
+    let mut t_evals = Vec::with_capacity(8 * n);
+    for (i, x) in coset.elements().enumerate() {
+        let (a, b, c, z) = (a_c[i], b_c[i], c_c[i], z_c[i]);
+        let gate = q.q_m[i] * a * b + q.q_l[i] * a + q.q_r[i] * b + q.q_o[i] * c + q.q_c[i] + q.q_h[i] * a.square() * a + pi_c[i];
+        let permutation = (a + beta * x + gamma) * (b + beta * k[1] * x + gamma) * (c + beta * k[2] * x + gamma) * z
+            - (a + beta * s[0][i] + gamma) * (b + beta * s[1][i] + gamma) * (c + beta * s[2][i] + gamma) * z_omega_c[i];
+        let start = (z - E::ScalarField::one()) * l1_c[i];
+        t_evals.push((gate + alpha * (permutation + alpha * start)) * z_h_inv[i % 8]);
+    }
+    let mut t = coset.ifft(&t_evals);
+    if t[3 * n + 6..].iter().any(|c| !c.is_zero()) {
+        return Err(PlonkError::Unsatisfied);
+    }
+    t.truncate(3 * n + 6);
+    // Pieces of n coefficients, each but the last blinded by b_i X^n, taken
+    // back from the next piece; sum_i X^(i n) t_i is unchanged.
+    let mut pieces: Vec<Vec<E::ScalarField>> = t.chunks(n).map(|chunk| chunk.to_vec()).collect();
+    pieces.resize(num_pieces(n), Vec::new());
+    for i in 0..pieces.len() - 1 {
+        let b = E::ScalarField::rand(rng);
+        pieces[i].resize(n + 1, E::ScalarField::zero());
+        pieces[i][n] += b;
+        if pieces[i + 1].is_empty() {
+            pieces[i + 1].push(E::ScalarField::zero());
+        }
+        pieces[i + 1][0] -= b;
+    }
+    let t_commitments: Vec<E::G1Affine> = pieces.iter().map(|p| commit::<E>(&pk.powers_g1, p)).collect();
+    transcript.append(&t_commitments);
+
+    // Round 4.
+    let zeta: E::ScalarField = transcript.challenge();
+    let omega = domain.group_gen();
+    let evaluations = Evaluations {
+        a: a.evaluate(&zeta),
+        b: b.evaluate(&zeta),
+        c: c.evaluate(&zeta),
+        s1: pk.sigmas[0].evaluate(&zeta),
+        s2: pk.sigmas[1].evaluate(&zeta),
+        z_omega: z.evaluate(&(zeta * omega)),
+    };
+    transcript.append(&evaluations);
+
+    // Round 5.
+    let v: E::ScalarField = transcript.challenge();
+    let Evaluations { a: a_bar, b: b_bar, c: c_bar, s1, s2, z_omega } = evaluations;
+    let l1_zeta = domain.evaluate_all_lagrange_coefficients(zeta)[0];
+    let z_h_zeta = domain.evaluate_vanishing_polynomial(zeta);
+    let sigma_product = alpha * (a_bar + beta * s1 + gamma) * (b_bar + beta * s2 + gamma) * z_omega;
+    let mut r = &(&(&pk.selectors.q_m * (a_bar * b_bar)) + &(&pk.selectors.q_l * a_bar)) + &(&pk.selectors.q_r * b_bar);
+    r = &r + &(&pk.selectors.q_o * c_bar);
+    r = &r + &pk.selectors.q_c;
+    r = &r + &(&pk.selectors.q_h * (a_bar.square() * a_bar));
+    let z_scalar = alpha * (a_bar + beta * zeta + gamma) * (b_bar + beta * k[1] * zeta + gamma) * (c_bar + beta * k[2] * zeta + gamma)
+        + alpha.square() * l1_zeta;
+    r = &r + &(&z * z_scalar);
+    r = &r - &(&pk.sigmas[2] * (sigma_product * beta));
+    let mut zeta_n = E::ScalarField::one();
+    for piece in &pieces {
+        r = &r - &(&Poly::from_coefficients_slice(piece) * (z_h_zeta * zeta_n));
+        zeta_n *= zeta.pow([n as u64]);
+    }
+    // r's constant term is left out: it only moves the value at zeta, which
+    // the division drops.
+    let mut opened = r;
+    let mut power = E::ScalarField::one();
+    for p in [&a, &b, &c, &pk.sigmas[0], &pk.sigmas[1]] {
+        power *= v;
+        opened = &opened + &(p * power);
+    }
+    let w_zeta = commit::<E>(&pk.powers_g1, &kzg_quotient(&opened.coeffs, zeta));
+    let w_zeta_omega = commit::<E>(&pk.powers_g1, &kzg_quotient(&z.coeffs, zeta * omega));
+
+    Ok(Proof {
+        a: a_commitment,
+        b: b_commitment,
+        c: c_commitment,
+        z: z_commitment,
+        t: t_commitments,
+        evaluations,
+        w_zeta,
+        w_zeta_omega,
+    })
+}
@@ -0,0 +1,81 @@
+// The PLONK verifier. From the challenges and the claimed evaluations it
+// builds the commitment [D] to the linearization r minus its constant term,
+//
+//     [D] = a b [q_M] + a [q_L] + b [q_R] + c [q_O] + [q_C] + a^3 [q_H]
+//         + (alpha (a + beta zeta + gamma)(b + beta k_1 zeta + gamma)(c + beta k_2 zeta + gamma) + alpha^2 L_1(zeta)) [z]
+//         - alpha beta (a + beta sigma_1 + gamma)(b + beta sigma_2 + gamma) z_omega [sigma_3]
+//         - Z_H(zeta) sum_i zeta^(i n) [t_i]
+//
+// with a, b, ... the evaluations. The quotient identity holds at zeta exactly
+// when r(zeta) = -PI(zeta), which fixes D(zeta). Both openings are checked at
+// once with a random u:
+//
+//     e(W_zeta + u W_zeta_omega, [tau]_2) = e(zeta W_zeta + u zeta omega W_zeta_omega + [F] - [E], [1]_2)
+//
+// where [F] = [D] + v [a] + ... + v^5 [sigma_2] + u [z] and E is the matching
+// combination of values.
+
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::Field;
+use ark_poly::EvaluationDomain;
+use ark_std::{One, Zero};
+
+use super::{challenges, domain, num_pieces, shifts, Evaluations, PlonkError, Proof, VerifyingKey};
+
+pub fn verify_proof<E: Pairing>(
+    vk: &VerifyingKey<E>,
+    inputs: &[E::ScalarField],
+    proof: &Proof<E>,
+) -> Result<bool, PlonkError> {
+    if inputs.len() != vk.num_public {
+        return Err(PlonkError::PublicInputs { expected: vk.num_public, found: inputs.len() });
+    }
+    if proof.t.len() != num_pieces(vk.n) {
+        return Ok(false);
+    }
+    let domain = domain::<E::ScalarField>(vk.n)?;
+    let challenges = challenges(vk, inputs, proof);
+    let (beta, gamma, alpha, zeta, v, u) =
+        (challenges.beta, challenges.gamma, challenges.alpha, challenges.zeta, challenges.v, challenges.u);
+    let Evaluations { a, b, c, s1, s2, z_omega } = proof.evaluations;
+    let k = shifts::<E::ScalarField>();
+    let omega = domain.group_gen();
+
+    let lagrange = domain.evaluate_all_lagrange_coefficients(zeta);
+    let pi: E::ScalarField = -inputs.iter().zip(&lagrange).map(|(x, l)| *x * l).sum::<E::ScalarField>();
+    let l1 = lagrange[0];
+    let z_h = domain.evaluate_vanishing_polynomial(zeta);
+
+    let sigma_product = alpha * (a + beta * s1 + gamma) * (b + beta * s2 + gamma) * z_omega;
+    // r's constant term; D(zeta) = r(zeta) - r_0 = -PI(zeta) - r_0.
+    let r_0 = -sigma_product * (c + gamma) - alpha.square() * l1;
+    let z_scalar =
+        alpha * (a + beta * zeta + gamma) * (b + beta * k[1] * zeta + gamma) * (c + beta * k[2] * zeta + gamma) + alpha.square() * l1;
+
+    let q = &vk.selectors;
+    let mut bases = vec![q.q_m, q.q_l, q.q_r, q.q_o, q.q_c, q.q_h, proof.z, vk.sigmas[2]];
+    let mut scalars = vec![a * b, a, b, c, E::ScalarField::one(), a.square() * a, z_scalar + u, -sigma_product * beta];
+    let zeta_n = zeta.pow([vk.n as u64]);
+    let mut power = E::ScalarField::one();
+    for t in &proof.t {
+        bases.push(*t);
+        scalars.push(-z_h * power);
+        power *= zeta_n;
+    }
+    let mut e = -pi - r_0 + u * z_omega;
+    let mut power = E::ScalarField::one();
+    for (commitment, value) in [(proof.a, a), (proof.b, b), (proof.c, c), (vk.sigmas[0], s1), (vk.sigmas[1], s2)] {
+        power *= v;
+        bases.push(commitment);
+        scalars.push(power);
+        e += power * value;
+    }
+    bases.extend([vk.g1, proof.w_zeta, proof.w_zeta_omega]);
+    scalars.extend([-e, zeta, u * zeta * omega]);
+    let rhs = E::G1::msm_unchecked(&bases, &scalars);
+    let lhs = proof.w_zeta.into_group() + proof.w_zeta_omega * u;
+
+    let g1 = E::G1::normalize_batch(&[lhs, -rhs]);
+    Ok(E::multi_pairing(g1, [vk.tau_g2, vk.g2]).is_zero())
+}
@@ -0,0 +1,701 @@
+// Circuits for polynomial equations given at runtime.
+//
+// An equation such as "x^3 + x + 5" or "x^2*y - 3xy + 2 = 7" is parsed into a
+// sparse multivariate polynomial p over the scalar field; the variables are
+// the prover's secrets, in alphabetical order. Without "=" the circuit proves
+// an evaluation: knowledge of an assignment with p(assignment) = out for a
+// public out. With "=" it proves knowledge of a root of lhs - rhs, and has no
+// public inputs at all.
+//
+// p is compiled in Horner form, recursively over the variables:
+//
+//     p = (...(p_d x^(d - e) + p_e) x^(e - f) + ...) x^g + p_0,   p_k free of x
+//
+// Multiplying by a constant or adding is free in R1CS, so only the products
+// with x cost a constraint; exponent gaps above one are filled from a cache of
+// powers built by squaring. The last product is not materialized but becomes
+// the final constraint a * b = out - rest, so x^3 + x + 5 = out takes two
+// constraints where `CubicDemoCircuit` takes three.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use ark_ff::PrimeField;
+use ark_relations::{lc, ns};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, LinearCombination, SynthesisError, Variable};
+
+// Bounds on what parsing expands, so that "x^4000000000" or "(x + y)^1000" is
+// an error rather than a hang.
+pub const MAX_EXPONENT: u32 = 1 << 16;
+pub const MAX_TERMS: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolynomialError {
+    UnexpectedChar { position: usize, found: char },
+    // Something else than what the grammar allows next, or the end of input.
+    Expected { position: usize, expected: &'static str },
+    InvalidNumber(String),
+    // An exponent, given or reached by multiplying, above MAX_EXPONENT.
+    ExponentTooLarge { position: usize },
+    // An expansion with more than MAX_TERMS terms.
+    TooManyTerms { position: usize },
+    UnknownVariable(String),
+    MissingAssignment(String),
+}
+
+impl fmt::Display for PolynomialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolynomialError::UnexpectedChar { position, found } => {
+                write!(f, "unexpected '{}' at position {}", found, position)
+            }
+            PolynomialError::Expected { position, expected } => write!(f, "expected {} at position {}", expected, position),
+            PolynomialError::InvalidNumber(s) => write!(f, "{} is not a field element or exponent", s),
+            PolynomialError::ExponentTooLarge { position } => {
+                write!(f, "exponent at position {} is above {}", position, MAX_EXPONENT)
+            }
+            PolynomialError::TooManyTerms { position } => {
+                write!(f, "expanding at position {} gives more than {} terms", position, MAX_TERMS)
+            }
+            PolynomialError::UnknownVariable(name) => write!(f, "{} does not occur in the equation", name),
+            PolynomialError::MissingAssignment(name) => write!(f, "no value given for {}", name),
+        }
+    }
+}
+
+impl std::error::Error for PolynomialError {}
+
+// Sum of coefficient * prod_i variables[i]^exponents[i], with no zero coefficients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial<F: PrimeField> {
+    pub variables: Vec<String>,
+    pub terms: BTreeMap<Vec<u32>, F>,
+}
+
+impl<F: PrimeField> Polynomial<F> {
+    fn constant(variables: usize, c: F) -> Self {
+        let mut terms = BTreeMap::new();
+        if !c.is_zero() {
+            terms.insert(vec![0; variables], c);
+        }
+        Polynomial { variables: Vec::new(), terms }
+    }
+
+    fn variable(variables: usize, i: usize) -> Self {
+        let mut exponents = vec![0; variables];
+        exponents[i] = 1;
+        Polynomial { variables: Vec::new(), terms: BTreeMap::from([(exponents, F::one())]) }
+    }
+
+    fn add_term(&mut self, exponents: Vec<u32>, c: F) {
+        let entry = self.terms.entry(exponents).or_insert_with(F::zero);
+        *entry += c;
+        if entry.is_zero() {
+            self.terms.retain(|_, c| !c.is_zero());
+        }
+    }
+
+    fn add(mut self, other: Self, sign: F) -> Self {
+        for (exponents, c) in other.terms {
+            self.add_term(exponents, c * sign);
+        }
+        self
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        let mut product = Polynomial { variables: Vec::new(), terms: BTreeMap::new() };
+        for (e1, c1) in &self.terms {
+            for (e2, c2) in &other.terms {
+                product.add_term(e1.iter().zip(e2).map(|(a, b)| a + b).collect(), *c1 * c2);
+            }
+        }
+        product
+    }
+
+    pub fn degree(&self) -> u32 {
+        self.terms.keys().map(|e| e.iter().sum()).max().unwrap_or(0)
+    }
+
+    pub fn evaluate(&self, values: &[F]) -> F {
+        self.terms
+            .iter()
+            .map(|(exponents, c)| exponents.iter().zip(values).fold(*c, |acc, (e, x)| acc * x.pow([*e as u64])))
+            .sum()
+    }
+}
+
+// Coefficients close to the modulus are shown as negative numbers. Returns
+// whether a number was written (it is left out for 1 and -1 before variables).
+fn write_coefficient<F: PrimeField>(f: &mut fmt::Formatter<'_>, c: F, first: bool, is_constant: bool) -> Result<bool, fmt::Error> {
+    let negative = (-c).into_bigint() < c.into_bigint();
+    let magnitude = if negative { -c } else { c };
+    match (first, negative) {
+        (true, true) => write!(f, "-")?,
+        (false, true) => write!(f, " - ")?,
+        (false, false) => write!(f, " + ")?,
+        (true, false) => {}
+    }
+    let written = is_constant || !magnitude.is_one();
+    if written {
+        write!(f, "{}", magnitude)?;
+    }
+    Ok(written)
+}
+
+impl<F: PrimeField> fmt::Display for Polynomial<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.terms.is_empty() {
+            return write!(f, "0");
+        }
+        // Highest total degree first.
+        let mut terms: Vec<_> = self.terms.iter().collect();
+        terms.sort_by(|(a, _), (b, _)| b.iter().sum::<u32>().cmp(&a.iter().sum()).then(b.cmp(a)));
+        for (i, (exponents, c)) in terms.into_iter().enumerate() {
+            let is_constant = exponents.iter().all(|e| *e == 0);
+            let mut needs_star = write_coefficient(f, *c, i == 0, is_constant)?;
+            for (name, e) in self.variables.iter().zip(exponents) {
+                if *e == 0 {
+                    continue;
+                }
+                if needs_star {
+                    write!(f, "*")?;
+                }
+                needs_star = true;
+                match e {
+                    1 => write!(f, "{}", name)?,
+                    _ => write!(f, "{}^{}", name, e)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Statement {
+    // p(assignment) = out, out public.
+    Evaluation,
+    // p(assignment) = 0.
+    Root,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equation<F: PrimeField> {
+    pub polynomial: Polynomial<F>,
+    pub statement: Statement,
+}
+
+impl<F: PrimeField> Equation<F> {
+    // "expr" for an evaluation, "expr = expr" for a root of their difference.
+    pub fn parse(s: &str) -> Result<Self, PolynomialError> {
+        let tokens = tokenize(s)?;
+        let mut variables: Vec<String> =
+            tokens.iter().filter_map(|(_, t)| if let Token::Ident(name) = t { Some(name.clone()) } else { None }).collect();
+        variables.sort();
+        variables.dedup();
+
+        let mut parser = Parser { tokens: &tokens, next: 0, variables: &variables, end: s.len() };
+        let lhs = parser.expression()?;
+        let (mut polynomial, statement) = if parser.eat(&Token::Equals) {
+            (lhs.add(parser.expression()?, -F::one()), Statement::Root)
+        } else {
+            (lhs, Statement::Evaluation)
+        };
+        if let Some((position, _)) = tokens.get(parser.next) {
+            return Err(PolynomialError::Expected { position: *position, expected: "an operator" });
+        }
+        polynomial.variables = variables;
+        Ok(Equation { polynomial, statement })
+    }
+
+    pub fn variables(&self) -> &[String] {
+        &self.polynomial.variables
+    }
+
+    // The values in variable order, from (name, value) pairs.
+    pub fn assignment(&self, values: &[(String, F)]) -> Result<Vec<F>, PolynomialError> {
+        if let Some((name, _)) = values.iter().find(|(name, _)| !self.variables().contains(name)) {
+            return Err(PolynomialError::UnknownVariable(name.clone()));
+        }
+        self.variables()
+            .iter()
+            .map(|v| {
+                values
+                    .iter()
+                    .find(|(name, _)| name == v)
+                    .map(|(_, x)| *x)
+                    .ok_or_else(|| PolynomialError::MissingAssignment(v.clone()))
+            })
+            .collect()
+    }
+}
+
+impl<F: PrimeField> fmt::Display for Equation<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.statement {
+            Statement::Evaluation => write!(f, "{} = out", self.polynomial),
+            Statement::Root => write!(f, "{} = 0", self.polynomial),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(String),
+    Ident(String),
+    Plus,
+    Minus,
+    Times,
+    Caret,
+    Open,
+    Close,
+    Equals,
+}
+
+fn tokenize(s: &str) -> Result<Vec<(usize, Token)>, PolynomialError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.char_indices().peekable();
+    while let Some((position, c)) = chars.next() {
+        let token = match c {
+            ' ' | '\t' => continue,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Times,
+            '^' => Token::Caret,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '=' => Token::Equals,
+            '0'..='9' => {
+                let mut number = c.to_string();
+                while let Some((_, d)) = chars.next_if(|(_, d)| d.is_ascii_digit()) {
+                    number.push(d);
+                }
+                Token::Number(number)
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut name = c.to_string();
+                while let Some((_, d)) = chars.next_if(|(_, d)| d.is_ascii_alphanumeric() || *d == '_') {
+                    name.push(d);
+                }
+                Token::Ident(name)
+            }
+            found => return Err(PolynomialError::UnexpectedChar { position, found }),
+        };
+        tokens.push((position, token));
+    }
+    Ok(tokens)
+}
+
+// expression := term (("+" | "-") term)*
+// term       := ("+" | "-")* power ("*"? power)*
+// power      := (number | name | "(" expression ")") ("^" number)?
+struct Parser<'a> {
+    tokens: &'a [(usize, Token)],
+    next: usize,
+    variables: &'a [String],
+    end: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.next).map(|(_, t)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.next).map_or(self.end, |(p, _)| *p)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.peek() == Some(token);
+        if found {
+            self.next += 1;
+        }
+        found
+    }
+
+    fn expression<F: PrimeField>(&mut self) -> Result<Polynomial<F>, PolynomialError> {
+        let mut sum = self.term()?;
+        loop {
+            if self.eat(&Token::Plus) {
+                sum = sum.add(self.term()?, F::one());
+            } else if self.eat(&Token::Minus) {
+                sum = sum.add(self.term()?, -F::one());
+            } else {
+                return Ok(sum);
+            }
+        }
+    }
+
+    fn term<F: PrimeField>(&mut self) -> Result<Polynomial<F>, PolynomialError> {
+        let mut sign = F::one();
+        loop {
+            if self.eat(&Token::Minus) {
+                sign = -sign;
+            } else if !self.eat(&Token::Plus) {
+                break;
+            }
+        }
+        let position = self.position();
+        let first = self.power()?;
+        let mut product = self.mul(&Polynomial::constant(self.variables.len(), sign), &first, position)?;
+        loop {
+            let implicit = matches!(self.peek(), Some(Token::Number(_) | Token::Ident(_) | Token::Open));
+            if !(self.eat(&Token::Times) || implicit) {
+                return Ok(product);
+            }
+            let position = self.position();
+            let factor = self.power()?;
+            product = self.mul(&product, &factor, position)?;
+        }
+    }
+
+    // a * b, within MAX_EXPONENT and MAX_TERMS.
+    fn mul<F: PrimeField>(&self, a: &Polynomial<F>, b: &Polynomial<F>, position: usize) -> Result<Polynomial<F>, PolynomialError> {
+        let max = |p: &Polynomial<F>, i: usize| p.terms.keys().map(|e| e[i]).max().unwrap_or(0);
+        if (0..self.variables.len()).any(|i| max(a, i) + max(b, i) > MAX_EXPONENT) {
+            return Err(PolynomialError::ExponentTooLarge { position });
+        }
+        let product = a.mul(b);
+        if product.terms.len() > MAX_TERMS {
+            return Err(PolynomialError::TooManyTerms { position });
+        }
+        Ok(product)
+    }
+
+    fn power<F: PrimeField>(&mut self) -> Result<Polynomial<F>, PolynomialError> {
+        let position = self.position();
+        let base = match self.peek() {
+            Some(Token::Number(n)) => {
+                self.next += 1;
+                Polynomial::constant(self.variables.len(), F::from_str(n).map_err(|_| PolynomialError::InvalidNumber(n.clone()))?)
+            }
+            Some(Token::Ident(name)) => {
+                self.next += 1;
+                let i = self.variables.iter().position(|v| v == name).expect("names are collected up front");
+                Polynomial::variable(self.variables.len(), i)
+            }
+            Some(Token::Open) => {
+                self.next += 1;
+                let inner = self.expression()?;
+                if !self.eat(&Token::Close) {
+                    return Err(PolynomialError::Expected { position: self.position(), expected: "')'" });
+                }
+                inner
+            }
+            _ => return Err(PolynomialError::Expected { position, expected: "a number, a variable or '('" }),
+        };
+        if !self.eat(&Token::Caret) {
+            return Ok(base);
+        }
+        let position = self.position();
+        let mut exponent: u32 = match self.peek() {
+            Some(Token::Number(n)) => {
+                self.next += 1;
+                n.parse().map_err(|_| PolynomialError::InvalidNumber(n.clone()))?
+            }
+            _ => return Err(PolynomialError::Expected { position, expected: "an exponent" }),
+        };
+        if exponent > MAX_EXPONENT {
+            return Err(PolynomialError::ExponentTooLarge { position });
+        }
+        // Square and multiply.
+        let mut result = Polynomial::constant(self.variables.len(), F::one());
+        let mut square = base;
+        loop {
+            if exponent & 1 == 1 {
+                result = self.mul(&result, &square, position)?;
+            }
+            exponent >>= 1;
+            if exponent == 0 {
+                return Ok(result);
+            }
+            square = self.mul(&square, &square, position)?;
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PolynomialCircuit<F: PrimeField> {
+    pub equation: Equation<F>,
+    // The secret values in variable order; None during setup.
+    pub assignment: Option<Vec<F>>,
+}
+
+impl<F: PrimeField> PolynomialCircuit<F> {
+    pub fn new(equation: Equation<F>, assignment: Option<Vec<F>>) -> Self {
+        PolynomialCircuit { equation, assignment }
+    }
+
+    // What the verifier needs: [p(assignment)] for an evaluation, nothing for a root.
+    pub fn public_inputs(&self) -> Option<Vec<F>> {
+        match self.equation.statement {
+            Statement::Evaluation => Some(vec![self.equation.polynomial.evaluate(self.assignment.as_ref()?)]),
+            Statement::Root => Some(Vec::new()),
+        }
+    }
+}
+
+// A linear combination and its value, if known.
+#[derive(Clone)]
+struct Lc<F: PrimeField> {
+    lc: LinearCombination<F>,
+    value: Option<F>,
+}
+
+impl<F: PrimeField> Lc<F> {
+    fn constant(c: F) -> Self {
+        Lc { lc: lc!() + (c, Variable::One), value: Some(c) }
+    }
+
+    fn as_constant(&self) -> Option<F> {
+        self.lc.0.iter().all(|(_, v)| *v == Variable::One).then(|| self.lc.0.iter().map(|(c, _)| *c).sum())
+    }
+
+    fn add(self, other: Lc<F>) -> Self {
+        Lc { lc: self.lc + other.lc, value: self.value.zip(other.value).map(|(a, b)| a + b) }
+    }
+
+    fn scale(self, c: F) -> Self {
+        Lc { lc: self.lc * c, value: self.value.map(|v| v * c) }
+    }
+}
+
+// A partially compiled polynomial: a linear combination, or a * b + rest with
+// the product not yet constrained.
+enum Value<F: PrimeField> {
+    Linear(Lc<F>),
+    Product(Lc<F>, Lc<F>, Lc<F>),
+}
+
+struct Compiler<F: PrimeField> {
+    cs: ConstraintSystemRef<F>,
+    variables: Vec<Lc<F>>,
+    // powers[i][&e] = x_i^e for the exponents built so far.
+    powers: Vec<BTreeMap<u32, Lc<F>>>,
+}
+
+impl<F: PrimeField> Compiler<F> {
+    // One constraint: w = a * b + rest.
+    fn materialize(&self, a: Lc<F>, b: Lc<F>, rest: Lc<F>) -> Result<Lc<F>, SynthesisError> {
+        let value = a.value.zip(b.value).zip(rest.value).map(|((a, b), r)| a * b + r);
+        let w = self.cs.new_witness_variable(|| value.ok_or(SynthesisError::AssignmentMissing))?;
+        self.cs.enforce_constraint(a.lc, b.lc, lc!() + w - rest.lc)?;
+        Ok(Lc { lc: lc!() + w, value })
+    }
+
+    fn linear(&self, value: Value<F>) -> Result<Lc<F>, SynthesisError> {
+        match value {
+            Value::Linear(lc) => Ok(lc),
+            Value::Product(a, b, rest) => self.materialize(a, b, rest),
+        }
+    }
+
+    // x_i^e by squaring and multiplying, reusing earlier powers.
+    fn power(&mut self, i: usize, e: u32) -> Result<Lc<F>, SynthesisError> {
+        if e == 1 {
+            return Ok(self.variables[i].clone());
+        }
+        if let Some(p) = self.powers[i].get(&e) {
+            return Ok(p.clone());
+        }
+        let half = self.power(i, e / 2)?;
+        let mut p = self.materialize(half.clone(), half, Lc::constant(F::zero()))?;
+        if e % 2 == 1 {
+            p = self.materialize(p, self.variables[i].clone(), Lc::constant(F::zero()))?;
+        }
+        self.powers[i].insert(e, p.clone());
+        Ok(p)
+    }
+
+    // value * x_i^e, leaving the last product open.
+    fn mul_power(&mut self, value: Value<F>, i: usize, e: u32) -> Result<Value<F>, SynthesisError> {
+        let zero = Lc::constant(F::zero());
+        let value = self.linear(value)?;
+        Ok(match value.as_constant() {
+            Some(c) if e == 1 => Value::Linear(self.variables[i].clone().scale(c)),
+            Some(c) => Value::Product(self.power(i, e - 1)?.scale(c), self.variables[i].clone(), zero),
+            None => Value::Product(value, self.power(i, e)?, zero),
+        })
+    }
+
+    fn add(&self, a: Value<F>, b: Value<F>) -> Result<Value<F>, SynthesisError> {
+        Ok(match (a, b) {
+            (Value::Linear(a), Value::Linear(b)) => Value::Linear(a.add(b)),
+            (Value::Product(a, b, rest), Value::Linear(c)) | (Value::Linear(c), Value::Product(a, b, rest)) => {
+                Value::Product(a, b, rest.add(c))
+            }
+            (Value::Product(a, b, rest), other) => {
+                let materialized = self.materialize(a, b, rest)?;
+                self.add(Value::Linear(materialized), other)?
+            }
+        })
+    }
+
+    // Horner in variable i over the terms, with the coefficients compiled in
+    // the variables after i.
+    fn compile(&mut self, terms: &[(&[u32], F)], i: usize) -> Result<Value<F>, SynthesisError> {
+        if i == self.variables.len() {
+            return Ok(Value::Linear(Lc::constant(terms.iter().map(|(_, c)| *c).sum())));
+        }
+        let mut by_exponent: BTreeMap<u32, Vec<(&[u32], F)>> = BTreeMap::new();
+        for (exponents, c) in terms {
+            by_exponent.entry(exponents[i]).or_default().push((exponents, *c));
+        }
+        let mut groups = by_exponent.into_iter().rev();
+        let (mut previous, first) = groups.next().expect("at least one term");
+        let mut acc = self.compile(&first, i + 1)?;
+        for (e, group) in groups {
+            acc = self.mul_power(acc, i, previous - e)?;
+            let coefficient = self.compile(&group, i + 1)?;
+            acc = self.add(acc, coefficient)?;
+            previous = e;
+        }
+        if previous > 0 {
+            acc = self.mul_power(acc, i, previous)?;
+        }
+        Ok(acc)
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for PolynomialCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let polynomial = &self.equation.polynomial;
+        let mut variables = Vec::new();
+        for i in 0..polynomial.variables.len() {
+            let value = self.assignment.as_ref().map(|a| a[i]);
+            let v = cs.new_witness_variable(|| value.ok_or(SynthesisError::AssignmentMissing))?;
+            variables.push(Lc { lc: lc!() + v, value });
+        }
+        let target = match self.equation.statement {
+            Statement::Evaluation => {
+                let out = self.public_inputs().map(|x| x[0]);
+                lc!() + cs.new_input_variable(|| out.ok_or(SynthesisError::AssignmentMissing))?
+            }
+            Statement::Root => lc!(),
+        };
+
+        let mut compiler = Compiler { cs: cs.clone(), powers: vec![BTreeMap::new(); variables.len()], variables };
+        let terms: Vec<(&[u32], F)> = polynomial.terms.iter().map(|(e, c)| (e.as_slice(), *c)).collect();
+        let value = {
+            let _ns = ns!(cs, "horner");
+            if terms.is_empty() { Value::Linear(Lc::constant(F::zero())) } else { compiler.compile(&terms, 0)? }
+        };
+        let _ns = ns!(cs, "output");
+        match value {
+            Value::Linear(p) => cs.enforce_constraint(p.lc, lc!() + Variable::One, target),
+            Value::Product(a, b, rest) => cs.enforce_constraint(a.lc, b.lc, target - rest.lc),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::debug;
+    use crate::groth16::{create_proof, generate_parameters, verify_proof};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::Field;
+    use ark_std::test_rng;
+
+    fn parse(s: &str) -> Equation<Fr> {
+        Equation::parse(s).unwrap()
+    }
+
+    fn constraints(s: &str) -> usize {
+        debug::statistics(PolynomialCircuit::new(parse(s), None)).unwrap().constraints
+    }
+
+    fn values(xs: &[u64]) -> Vec<Fr> {
+        xs.iter().map(|x| Fr::from(*x)).collect()
+    }
+
+    #[test]
+    fn equations_parse_and_normalize() {
+        let cubic = parse("x^3 + x + 5");
+        assert_eq!(cubic.statement, Statement::Evaluation);
+        assert_eq!(cubic.to_string(), "x^3 + x + 5 = out");
+        assert_eq!(cubic.polynomial.evaluate(&values(&[3])), Fr::from(35u64));
+
+        let root = parse("x^2 - 5x + 6 = 0");
+        assert_eq!(root.statement, Statement::Root);
+        assert_eq!(root.to_string(), "x^2 - 5*x + 6 = 0");
+
+        let e = parse("(x + y)^2 - 2 x*y = 3*y^2 - -z");
+        assert_eq!(e.variables(), ["x", "y", "z"]);
+        assert_eq!(e.polynomial.to_string(), "x^2 - 2*y^2 - z");
+        assert_eq!(e.polynomial.degree(), 2);
+        assert_eq!(parse("x - x").polynomial.to_string(), "0");
+
+        let power = parse("(x + 1)^100");
+        assert_eq!(power.polynomial.terms.len(), 101);
+        assert_eq!(power.polynomial.evaluate(&values(&[1])), Fr::from(2u64).pow([100]));
+        assert_eq!(parse("x^65536 + x^0").polynomial.to_string(), "x^65536 + 1");
+    }
+
+    #[test]
+    fn malformed_equations_are_rejected() {
+        let err = |s: &str| Equation::<Fr>::parse(s).unwrap_err();
+        assert_eq!(err("x $ 2"), PolynomialError::UnexpectedChar { position: 2, found: '$' });
+        assert_eq!(err("x +"), PolynomialError::Expected { position: 3, expected: "a number, a variable or '('" });
+        assert_eq!(err("(x + 1"), PolynomialError::Expected { position: 6, expected: "')'" });
+        assert_eq!(err("x^y"), PolynomialError::Expected { position: 2, expected: "an exponent" });
+        assert_eq!(err("x = 1 = 2"), PolynomialError::Expected { position: 6, expected: "an operator" });
+        assert!(matches!(err("x^99999999999"), PolynomialError::InvalidNumber(_)));
+        assert_eq!(err("x^4000000000"), PolynomialError::ExponentTooLarge { position: 2 });
+        assert_eq!(err("(x^65536)^2"), PolynomialError::ExponentTooLarge { position: 10 });
+        assert_eq!(err("x^40000 * x^40000"), PolynomialError::ExponentTooLarge { position: 10 });
+        assert_eq!(err("(x + y)^1000"), PolynomialError::TooManyTerms { position: 8 });
+        assert_eq!(err("(a + b + c + d)^20"), PolynomialError::TooManyTerms { position: 16 });
+
+        let e = parse("x*y");
+        assert_eq!(e.assignment(&[("y".into(), Fr::from(2u64))]), Err(PolynomialError::MissingAssignment("x".into())));
+        assert_eq!(e.assignment(&[("w".into(), Fr::from(2u64))]), Err(PolynomialError::UnknownVariable("w".into())));
+    }
+
+    #[test]
+    fn horner_form_keeps_constraints_minimal() {
+        // deg - 1 products; the last is the output constraint itself.
+        assert_eq!(constraints("x^3 + x + 5"), 2);
+        assert_eq!(constraints("x^5 + 4x^4 + 3x^3 + 2x^2 + x + 1"), 4);
+        assert_eq!(constraints("2x + 7"), 1);
+        // Sparse: x^99 (squarings), then x^100 = x^99 * x in the output constraint.
+        assert!(constraints("x^100 + 1") <= 11);
+        // Horner in x with coefficients in y: y*x + y^2*x... = (y + y^2) x.
+        assert_eq!(constraints("x*y + x*y^2 = 6"), 2);
+    }
+
+    #[test]
+    fn evaluation_matches_the_cubic_demo() {
+        let mut rng = test_rng();
+        let equation = parse("x^3 + x + 5");
+        let pk = generate_parameters::<Bls12_381, _, _>(PolynomialCircuit::new(equation.clone(), None), &mut rng).unwrap();
+        let circuit = PolynomialCircuit::new(equation, Some(values(&[3])));
+        assert_eq!(circuit.public_inputs(), Some(values(&[35])));
+        let proof = create_proof(&pk, circuit, &mut rng).unwrap();
+        assert!(verify_proof(&pk.vk, &values(&[35]), &proof).unwrap());
+        assert!(!verify_proof(&pk.vk, &values(&[73]), &proof).unwrap());
+    }
+
+    #[test]
+    fn roots_of_multivariate_equations_are_proven() {
+        let mut rng = test_rng();
+        let equation = parse("x^2*y - 3x*y + z^3 = 10");
+        let pk = generate_parameters::<Bls12_381, _, _>(PolynomialCircuit::new(equation.clone(), None), &mut rng).unwrap();
+        // A root statement has no public inputs.
+        assert_eq!(pk.vk.gamma_abc_g1.len(), 1);
+
+        // 25 - 15 + 0 = 10.
+        let root = values(&[5, 1, 0]);
+        assert_eq!(equation.polynomial.evaluate(&root), Fr::from(0u64));
+        let circuit = PolynomialCircuit::new(equation.clone(), Some(root));
+        assert!(debug::check(circuit.clone()).unwrap().is_satisfied());
+        let proof = create_proof(&pk, circuit, &mut rng).unwrap();
+        assert!(verify_proof(&pk.vk, &[], &proof).unwrap());
+
+        // 16 - 12 + 8 = 12.
+        let not_a_root = PolynomialCircuit::new(equation, Some(values(&[4, 1, 2])));
+        assert!(!debug::check(not_a_root.clone()).unwrap().is_satisfied());
+        let proof = create_proof(&pk, not_a_root, &mut rng).unwrap();
+        assert!(!verify_proof(&pk.vk, &[], &proof).unwrap());
+    }
+}
@@ -0,0 +1,125 @@
+// Verifying a BLS12-377 Groth16 proof inside a circuit.
+//
+// BLS12-377 and BW6-761 form a 2-chain of curves: the base field of
+// BLS12-377 is the scalar field of BW6-761, so BLS12-377 points, and the
+// pairing on them, are native arithmetic in a BW6-761 circuit. A proof of
+// `VerifierCircuit` over BW6-761 then attests that the inner proof verifies,
+// which is the step a recursive or aggregating system repeats.
+//
+// The circuit holds the inner verifying key as constants, the inner proof as
+// a witness and the inner public inputs as its own public inputs, packed by
+// `outer_inputs` (one BW6-761 scalar per BLS12-377 scalar, since the latter is
+// smaller). `ark_groth16::constraints` supplies the verifier gadget; the
+// pairing gadget is BLS12-377's from ark-bls12-377's `r1cs` feature.
+//
+// `setup`, `prove` and `verify` run Groth16 over BW6-761 on that circuit;
+// `check` only synthesizes it and reports whether it is satisfied, which is
+// much cheaper and enough to see what the outer prover would be asked.
+
+use ark_bls12_377::constraints::PairingVar;
+use ark_bls12_377::{Bls12_377, Fq, Fr};
+use ark_crypto_primitives::snark::{BooleanInputVar, FromFieldElementsGadget, SNARKGadget};
+use ark_groth16::constraints::{Groth16VerifierGadget, ProofVar, VerifyingKeyVar};
+use ark_bw6_761::BW6_761;
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError};
+use ark_std::rand::Rng;
+use ark_std::Zero;
+
+use crate::groth16::{create_proof, generate_parameters, verify_proof, Proof, ProvingKey, VerifyingKey};
+
+// The scalar field of BW6-761, which the outer circuit is defined over.
+pub type OuterField = Fq;
+
+type Verifier = Groth16VerifierGadget<Bls12_377, PairingVar>;
+
+#[derive(Clone)]
+pub struct VerifierCircuit {
+    pub vk: VerifyingKey<Bls12_377>,
+    pub inputs: Vec<Fr>,
+    // None during the outer setup.
+    pub proof: Option<Proof<Bls12_377>>,
+}
+
+impl ConstraintSynthesizer<OuterField> for VerifierCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<OuterField>) -> Result<(), SynthesisError> {
+        let vk = VerifyingKeyVar::<Bls12_377, PairingVar>::new_constant(cs.clone(), ark_groth16::VerifyingKey::from(self.vk))?;
+        let inputs = BooleanInputVar::<Fr, OuterField>::new_input(cs.clone(), || Ok(self.inputs))?;
+        // ProofVar reads its value even in setup mode, so any proof will do there.
+        let setup = cs.is_in_setup_mode();
+        let proof = ProofVar::<Bls12_377, PairingVar>::new_witness(cs, || match self.proof {
+            Some(proof) => Ok(ark_groth16::Proof::from(proof)),
+            None if setup => Ok(ark_groth16::Proof::default()),
+            None => Err(SynthesisError::AssignmentMissing),
+        })?;
+        Verifier::verify(&vk, &inputs, &proof)?.enforce_equal(&Boolean::TRUE)
+    }
+}
+
+// The outer circuit's public inputs for the given inner ones.
+pub fn outer_inputs(inputs: &[Fr]) -> Vec<OuterField> {
+    BooleanInputVar::<Fr, OuterField>::repack_input(&inputs.to_vec())
+}
+
+// Outer keys for inner proofs under `vk` with `num_inputs` public inputs.
+pub fn setup<R: Rng>(vk: &VerifyingKey<Bls12_377>, num_inputs: usize, rng: &mut R) -> Result<ProvingKey<BW6_761>, SynthesisError> {
+    let circuit = VerifierCircuit { vk: vk.clone(), inputs: vec![Fr::zero(); num_inputs], proof: None };
+    generate_parameters::<BW6_761, _, _>(circuit, rng)
+}
+
+// A BW6-761 proof that `circuit.proof` verifies for `circuit.inputs`.
+pub fn prove<R: Rng>(pk: &ProvingKey<BW6_761>, circuit: VerifierCircuit, rng: &mut R) -> Result<Proof<BW6_761>, SynthesisError> {
+    create_proof(pk, circuit, rng)
+}
+
+// Checks an outer proof against the inner public inputs.
+pub fn verify(vk: &VerifyingKey<BW6_761>, inputs: &[Fr], proof: &Proof<BW6_761>) -> Result<bool, SynthesisError> {
+    verify_proof(vk, &outer_inputs(inputs), proof)
+}
+
+// Whether the inner proof verifies in-circuit, and the number of constraints that took.
+pub fn check(circuit: VerifierCircuit) -> Result<(bool, usize), SynthesisError> {
+    let cs = ConstraintSystem::<OuterField>::new_ref();
+    circuit.generate_constraints(cs.clone())?;
+    Ok((cs.is_satisfied()?, cs.num_constraints()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline;
+    use ark_std::test_rng;
+
+    #[test]
+    fn inner_proof_is_verified_in_circuit() {
+        let mut rng = test_rng();
+        let pk = pipeline::setup::<Bls12_377, _>(&mut rng).unwrap();
+        let (proof, out) = pipeline::prove(&pk, Fr::from(3u64), &mut rng).unwrap();
+        assert_eq!(outer_inputs(&[out]), vec![OuterField::from(35u64)]);
+
+        let circuit = VerifierCircuit { vk: pk.vk.clone(), inputs: vec![out], proof: Some(proof) };
+        let (satisfied, constraints) = check(circuit.clone()).unwrap();
+        assert!(satisfied);
+        assert!(constraints > 10_000, "{} constraints", constraints);
+
+        let wrong = VerifierCircuit { inputs: vec![out + Fr::from(1u64)], ..circuit };
+        assert!(!check(wrong).unwrap().0);
+    }
+
+    // About a minute in release and over ten in a debug build, so not run by default:
+    //
+    //     cargo test --release -- --ignored outer_proof
+    #[test]
+    #[ignore]
+    fn outer_proof_verifies_over_bw6_761() {
+        let mut rng = test_rng();
+        let pk = pipeline::setup::<Bls12_377, _>(&mut rng).unwrap();
+        let (proof, out) = pipeline::prove(&pk, Fr::from(3u64), &mut rng).unwrap();
+
+        let outer_pk = setup(&pk.vk, 1, &mut rng).unwrap();
+        let circuit = VerifierCircuit { vk: pk.vk.clone(), inputs: vec![out], proof: Some(proof) };
+        let outer_proof = prove(&outer_pk, circuit, &mut rng).unwrap();
+        assert!(verify(&outer_pk.vk, &[out], &outer_proof).unwrap());
+        assert!(!verify(&outer_pk.vk, &[out + Fr::from(1u64)], &outer_proof).unwrap());
+    }
+}
@@ -0,0 +1,350 @@
+// Solidity verifiers for BN254 verifying keys.
+//
+// The generated contract hard-codes the key and checks
+//
+//     e(-A, B) e(alpha, beta) e(IC, gamma) e(C, delta) = 1,   IC = IC_0 + sum_i x_i IC_i
+//
+// with the BN254 precompiles: ecAdd (0x06) and ecMul (0x07) for IC, and the
+// EIP-197 pairing check (0x08) for the product. The precompiles take points as
+// big-endian 32-byte words, (x, y) in G1 and (x_im, x_re, y_im, y_re) in G2,
+// with the identity as all zeros; `g1_words` and `g2_words` produce exactly
+// that. The entry point is
+//
+//     verifyProof(uint256[2] a, uint256[2][2] b, uint256[2] c, uint256[n] input)
+//
+// whose calldata, static arrays only, is the selector followed by the words of
+// a, b, c and the inputs, as built by `calldata`. Solidity has no zero-length
+// arrays, so a key without public inputs gets no `input` parameter at all.
+
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField};
+use sha3::{Digest, Keccak256};
+
+use crate::groth16::{Proof, VerifyingKey};
+
+pub type Word = [u8; 32];
+
+fn word<F: PrimeField>(x: &F) -> Word {
+    x.into_bigint().to_bytes_be().try_into().expect("BN254 elements fit in 32 bytes")
+}
+
+pub fn g1_words(p: &G1Affine) -> [Word; 2] {
+    match p.xy() {
+        Some((x, y)) => [word(&x), word(&y)],
+        None => [[0; 32]; 2],
+    }
+}
+
+pub fn g2_words(p: &G2Affine) -> [Word; 4] {
+    match p.xy() {
+        Some((x, y)) => [word(&x.c1), word(&x.c0), word(&y.c1), word(&y.c0)],
+        None => [[0; 32]; 4],
+    }
+}
+
+// The first four bytes of the Keccak-256 hash of a function signature.
+pub fn selector(signature: &str) -> [u8; 4] {
+    Keccak256::digest(signature.as_bytes())[..4].try_into().unwrap()
+}
+
+fn signature(num_inputs: usize) -> String {
+    match num_inputs {
+        0 => "verifyProof(uint256[2],uint256[2][2],uint256[2])".to_string(),
+        n => format!("verifyProof(uint256[2],uint256[2][2],uint256[2],uint256[{}])", n),
+    }
+}
+
+// Calldata for `verifyProof` on the contract generated for a key with this many inputs.
+pub fn calldata(proof: &Proof<Bn254>, public_inputs: &[Fr]) -> Vec<u8> {
+    let mut data = selector(&signature(public_inputs.len())).to_vec();
+    let words = g1_words(&proof.a)
+        .into_iter()
+        .chain(g2_words(&proof.b))
+        .chain(g1_words(&proof.c))
+        .chain(public_inputs.iter().map(word));
+    for w in words {
+        data.extend_from_slice(&w);
+    }
+    data
+}
+
+fn decimal(w: &Word) -> String {
+    Fq::from_be_bytes_mod_order(w).to_string()
+}
+
+// `uint256 constant NAME_X = ...;` lines for a point, with suffixes for its words.
+fn constants(out: &mut String, name: &str, words: &[Word], suffixes: &[&str]) {
+    for (w, suffix) in words.iter().zip(suffixes) {
+        out.push_str(&format!("    uint256 constant {}_{} = {};\n", name, suffix, decimal(w)));
+    }
+}
+
+const G1_SUFFIXES: [&str; 2] = ["X", "Y"];
+const G2_SUFFIXES: [&str; 4] = ["X_IM", "X_RE", "Y_IM", "Y_RE"];
+
+pub fn verifier_contract(vk: &VerifyingKey<Bn254>) -> String {
+    let n = vk.gamma_abc_g1.len() - 1;
+    let mut keys = String::new();
+    constants(&mut keys, "ALPHA", &g1_words(&vk.alpha_g1), &G1_SUFFIXES);
+    constants(&mut keys, "BETA", &g2_words(&vk.beta_g2), &G2_SUFFIXES);
+    constants(&mut keys, "GAMMA", &g2_words(&vk.gamma_g2), &G2_SUFFIXES);
+    constants(&mut keys, "DELTA", &g2_words(&vk.delta_g2), &G2_SUFFIXES);
+    for (i, ic) in vk.gamma_abc_g1.iter().enumerate() {
+        constants(&mut keys, &format!("IC{}", i), &g1_words(ic), &G1_SUFFIXES);
+    }
+
+    // The input parameter and its range check, if there are inputs.
+    let (parameter, check) = match n {
+        0 => (String::new(), String::new()),
+        n => (
+            format!(",\n        uint256[{}] calldata input", n),
+            format!("        for (uint256 i = 0; i < {}; i++) {{\n            if (input[i] >= R) return false;\n        }}\n", n),
+        ),
+    };
+    let mut accumulate = String::new();
+    for i in 1..=n {
+        accumulate.push_str(&format!(
+            "        vk_x = ecAdd(vk_x, ecMul([IC{}_X, IC{}_Y], input[{}]));\n",
+            i,
+            i,
+            i - 1
+        ));
+    }
+
+    // (-A, B), (alpha, beta), (IC, gamma), (C, delta).
+    let words = [
+        "a[0]", "(Q - a[1] % Q) % Q", "b[0][0]", "b[0][1]", "b[1][0]", "b[1][1]",
+        "ALPHA_X", "ALPHA_Y", "BETA_X_IM", "BETA_X_RE", "BETA_Y_IM", "BETA_Y_RE",
+        "vk_x[0]", "vk_x[1]", "GAMMA_X_IM", "GAMMA_X_RE", "GAMMA_Y_IM", "GAMMA_Y_RE",
+        "c[0]", "c[1]", "DELTA_X_IM", "DELTA_X_RE", "DELTA_Y_IM", "DELTA_Y_RE",
+    ];
+    let pairs: String = words.iter().enumerate().map(|(i, w)| format!("        p[{}] = {};\n", i, w)).collect();
+
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+// Groth16 verifier for one BN254 verifying key, generated by groth_16.
+contract Groth16Verifier {{
+    // Base field and scalar field moduli.
+    uint256 constant Q = {q};
+    uint256 constant R = {r};
+
+{keys}
+    function verifyProof(
+        uint256[2] calldata a,
+        uint256[2][2] calldata b,
+        uint256[2] calldata c{parameter}
+    ) public view returns (bool) {{
+{check}        uint256[2] memory vk_x = [IC0_X, IC0_Y];
+{accumulate}
+        // Filled one word at a time: a 24-element literal is too deep for the stack.
+        uint256[24] memory p;
+{pairs}        uint256[1] memory out;
+        bool ok;
+        assembly {{
+            ok := staticcall(gas(), 0x08, p, 768, out, 32)
+        }}
+        return ok && out[0] == 1;
+    }}
+
+    function ecAdd(uint256[2] memory p1, uint256[2] memory p2) internal view returns (uint256[2] memory r) {{
+        uint256[4] memory input = [p1[0], p1[1], p2[0], p2[1]];
+        bool ok;
+        assembly {{
+            ok := staticcall(gas(), 0x06, input, 128, r, 64)
+        }}
+        require(ok, "ecAdd failed");
+    }}
+
+    function ecMul(uint256[2] memory p1, uint256 s) internal view returns (uint256[2] memory r) {{
+        uint256[3] memory input = [p1[0], p1[1], s];
+        bool ok;
+        assembly {{
+            ok := staticcall(gas(), 0x07, input, 96, r, 64)
+        }}
+        require(ok, "ecMul failed");
+    }}
+}}
+"#,
+        q = Fq::MODULUS,
+        r = Fr::MODULUS,
+        keys = keys,
+        parameter = parameter,
+        check = check,
+        accumulate = accumulate,
+        pairs = pairs,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::CubicDemoCircuit;
+    use crate::groth16::{create_proof, generate_parameters, verify_proof};
+    use ark_bn254::Fq2;
+    use ark_ec::pairing::Pairing;
+    use ark_ec::CurveGroup;
+    use ark_std::test_rng;
+    use ark_std::Zero;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    #[test]
+    fn selectors_match_known_values() {
+        assert_eq!(selector("transfer(address,uint256)"), [0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(selector("balanceOf(address)"), [0x70, 0xa0, 0x82, 0x31]);
+    }
+
+    // A model of what the EVM does with the contract and the calldata, with
+    // the precompiles played by arkworks: reads the key from the contract's
+    // constants and the proof from the calldata words, builds the 768-byte
+    // pairing input the contract builds, and decodes it the EIP-197 way. It
+    // checks the encodings; tests/solidity.rs (feature `solc-tests`) compiles
+    // the contract with solc and runs it on an EVM.
+    fn run_contract(contract: &str, calldata: &[u8], num_inputs: usize) -> bool {
+        let constants: HashMap<&str, Fq> = contract
+            .lines()
+            .filter_map(|l| l.trim().strip_prefix("uint256 constant "))
+            .filter_map(|l| l.strip_suffix(';')?.split_once(" = "))
+            .filter(|(name, _)| !["Q", "R"].contains(name))
+            .map(|(name, value)| (name, Fq::from_str(value).unwrap_or_else(|_| panic!("{}", value))))
+            .collect();
+        let key = |name: &str| word(&constants[name]);
+        let g1_key = |name: &str| [key(&format!("{}_X", name)), key(&format!("{}_Y", name))];
+        let g2_key = |name: &str| G2_SUFFIXES.map(|s| key(&format!("{}_{}", name, s)));
+
+        assert_eq!(calldata[..4], selector(&signature(num_inputs)));
+        let words: Vec<Word> = calldata[4..].chunks(32).map(|c| c.try_into().unwrap()).collect();
+        assert_eq!(words.len(), 8 + num_inputs);
+        let r = Fr::MODULUS.to_bytes_be();
+        if words[8..].iter().any(|w| w[..] >= r[..]) {
+            return false;
+        }
+
+        // ecMul and ecAdd.
+        let mut vk_x = decode_g1(&g1_key("IC0")).unwrap().into_group();
+        for (i, input) in words[8..].iter().enumerate() {
+            vk_x += decode_g1(&g1_key(&format!("IC{}", i + 1))).unwrap() * Fr::from_be_bytes_mod_order(input);
+        }
+        let vk_x = g1_words(&vk_x.into_affine());
+        let neg_a_y = word(&-Fq::from_be_bytes_mod_order(&words[1]));
+
+        let mut input = Vec::new();
+        for w in [words[0], neg_a_y, words[2], words[3], words[4], words[5]]
+            .into_iter()
+            .chain(g1_key("ALPHA"))
+            .chain(g2_key("BETA"))
+            .chain(vk_x)
+            .chain(g2_key("GAMMA"))
+            .chain([words[6], words[7]])
+            .chain(g2_key("DELTA"))
+        {
+            input.extend_from_slice(&w);
+        }
+        pairing_precompile(&input)
+    }
+
+    fn fq(bytes: &[u8]) -> Fq {
+        Fq::from_be_bytes_mod_order(bytes)
+    }
+
+    // None for an invalid point, on which the precompiles fail.
+    fn decode_g1(w: &[Word; 2]) -> Option<G1Affine> {
+        let (x, y) = (fq(&w[0]), fq(&w[1]));
+        if x.is_zero() && y.is_zero() {
+            return Some(G1Affine::zero());
+        }
+        let p = G1Affine::new_unchecked(x, y);
+        p.is_on_curve().then_some(p)
+    }
+
+    fn decode_g2(pair: &[u8]) -> Option<G2Affine> {
+        let x = Fq2::new(fq(&pair[32..64]), fq(&pair[..32]));
+        let y = Fq2::new(fq(&pair[96..128]), fq(&pair[64..96]));
+        if x.is_zero() && y.is_zero() {
+            return Some(G2Affine::zero());
+        }
+        let p = G2Affine::new_unchecked(x, y);
+        (p.is_on_curve() && p.is_in_correct_subgroup_assuming_on_curve()).then_some(p)
+    }
+
+    // EIP-197: k pairs of 192 bytes; whether the call succeeds and the product of pairings is one.
+    fn pairing_precompile(input: &[u8]) -> bool {
+        assert_eq!(input.len() % 192, 0);
+        let pairs: Option<Vec<(G1Affine, G2Affine)>> = input
+            .chunks(192)
+            .map(|pair| Some((decode_g1(&[pair[..32].try_into().unwrap(), pair[32..64].try_into().unwrap()])?, decode_g2(&pair[64..])?)))
+            .collect();
+        match pairs {
+            Some(pairs) => {
+                let (g1, g2): (Vec<_>, Vec<_>) = pairs.into_iter().unzip();
+                Bn254::multi_pairing(g1, g2).is_zero()
+            }
+            None => false,
+        }
+    }
+
+    #[test]
+    fn calldata_is_accepted_exactly_when_the_rust_verifier_accepts() {
+        let mut rng = test_rng();
+        let pk = generate_parameters::<Bn254, _, _>(CubicDemoCircuit::<Fr> { x: None }, &mut rng).unwrap();
+        let contract = verifier_contract(&pk.vk);
+        assert!(contract.contains("uint256[1] calldata input"));
+
+        for x in [3u64, 4] {
+            let proof = create_proof(&pk, CubicDemoCircuit { x: Some(Fr::from(x)) }, &mut rng).unwrap();
+            for out in [35u64, 73, 0] {
+                let inputs = [Fr::from(out)];
+                let data = calldata(&proof, &inputs);
+                assert_eq!(data.len(), 4 + 32 * 9);
+                assert_eq!(run_contract(&contract, &data, 1), verify_proof(&pk.vk, &inputs, &proof).unwrap(), "x = {}, out = {}", x, out);
+            }
+        }
+        // A valid proof with its input.
+        let proof = create_proof(&pk, CubicDemoCircuit { x: Some(Fr::from(3u64)) }, &mut rng).unwrap();
+        assert!(run_contract(&contract, &calldata(&proof, &[Fr::from(35u64)]), 1));
+
+        // G2 words swapped into (re, im) order are a different point.
+        let mut swapped = calldata(&proof, &[Fr::from(35u64)]);
+        let (im, re) = (swapped[4 + 64..4 + 96].to_vec(), swapped[4 + 96..4 + 128].to_vec());
+        swapped[4 + 64..4 + 96].copy_from_slice(&re);
+        swapped[4 + 96..4 + 128].copy_from_slice(&im);
+        assert!(!run_contract(&contract, &swapped, 1));
+    }
+
+    #[test]
+    fn contract_embeds_every_input_commitment() {
+        let mut rng = test_rng();
+        let mut vk = generate_parameters::<Bn254, _, _>(CubicDemoCircuit::<Fr> { x: None }, &mut rng).unwrap().vk;
+        vk.gamma_abc_g1.push((G1Affine::generator() * Fr::from(7u64)).into_affine());
+        let contract = verifier_contract(&vk);
+        assert!(contract.contains("uint256[2] calldata input"));
+        assert!(contract.contains("uint256 constant IC2_X = "));
+        assert!(contract.contains("vk_x = ecAdd(vk_x, ecMul([IC2_X, IC2_Y], input[1]));"));
+    }
+
+    #[test]
+    fn keys_without_inputs_take_no_input_parameter() {
+        use crate::polynomial::{Equation, PolynomialCircuit};
+
+        let mut rng = test_rng();
+        // A root proof has no public inputs.
+        let equation = Equation::parse("x^2 - 5x + 6 = 0").unwrap();
+        let pk = generate_parameters::<Bn254, _, _>(PolynomialCircuit::<Fr>::new(equation.clone(), None), &mut rng).unwrap();
+        assert_eq!(pk.vk.gamma_abc_g1.len(), 1);
+        let contract = verifier_contract(&pk.vk);
+        assert!(!contract.contains("calldata input") && !contract.contains("input["));
+        assert!(contract.contains("uint256[2] calldata c\n    ) public view returns (bool)"));
+        assert_eq!(signature(0), "verifyProof(uint256[2],uint256[2][2],uint256[2])");
+
+        let proof = create_proof(&pk, PolynomialCircuit::new(equation, Some(vec![Fr::from(2u64)])), &mut rng).unwrap();
+        let data = calldata(&proof, &[]);
+        assert_eq!(data.len(), 4 + 32 * 8);
+        assert!(run_contract(&contract, &data, 0));
+        let mut bad = proof.clone();
+        bad.c = proof.a;
+        assert!(!run_contract(&contract, &calldata(&bad, &[]), 0));
+    }
+}
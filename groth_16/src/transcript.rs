@@ -0,0 +1,36 @@
+// Fiat-Shamir transcripts for the non-interactive arguments (SnarkPack, PLONK):
+// a running SHA-256 over everything the prover has sent, from which the
+// verifier's challenges are derived.
+
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use sha2::{Digest, Sha256};
+
+pub(crate) struct Transcript {
+    state: Sha256,
+}
+
+impl Transcript {
+    // The label separates the protocols using it.
+    pub(crate) fn new(label: &[u8]) -> Self {
+        Transcript { state: Sha256::new_with_prefix(label) }
+    }
+
+    pub(crate) fn append<T: CanonicalSerialize>(&mut self, value: &T) {
+        let mut bytes = Vec::new();
+        value.serialize_compressed(&mut bytes).expect("writing to a vector");
+        self.state.update(bytes);
+    }
+
+    // A non-zero challenge, which is also fed back into the transcript.
+    pub(crate) fn challenge<F: PrimeField>(&mut self) -> F {
+        loop {
+            let wide: Vec<u8> = [0u8, 1].iter().flat_map(|i| self.state.clone().chain_update([*i]).finalize()).collect();
+            self.state.update(&wide);
+            let c = F::from_le_bytes_mod_order(&wide);
+            if !c.is_zero() {
+                return c;
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+// The setup/prove/verify commands end to end, through the built binary.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_groth_16")).args(args).output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap().trim().to_string()
+}
+
+// A fresh directory per test.
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("groth_16-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// setup, prove x = 3 and verify out = 35 under `flags`, in `dir`.
+fn round_trip(dir: &str, flags: &[&str]) {
+    let (pk, vk, proof) = (format!("{}/proving.key", dir), format!("{}/verifying.key", dir), format!("{}/proof", dir));
+    assert_eq!(run(&[&["setup", dir][..], flags].concat()).status.code(), Some(0));
+    let proved = run(&[&["prove", &pk, "3", &proof][..], flags].concat());
+    assert_eq!(proved.status.code(), Some(0));
+    assert_eq!(stdout(&proved), "35");
+
+    let curve: Vec<&str> = flags.iter().copied().skip_while(|f| *f != "--curve").take(2).collect();
+    let verify = |out: &str| run(&[&["verify", &vk, &proof, out][..], &curve].concat()).status.code();
+    assert_eq!(verify("35"), Some(0));
+    // Again, now from the prepared-key cache.
+    assert!(PathBuf::from(format!("{}.prepared", vk)).is_file());
+    assert_eq!(verify("35"), Some(0));
+    assert_eq!(verify("36"), Some(1));
+}
+
+#[test]
+fn setup_prove_verify_round_trips() {
+    let dir = scratch("round-trip");
+    for (name, flags) in [
+        ("compressed", &[][..]),
+        ("uncompressed", &["--uncompressed"][..]),
+        ("bn254", &["--curve", "bn254"][..]),
+        ("bls12-377", &["--curve", "bls12-377", "--uncompressed"][..]),
+    ] {
+        round_trip(dir.join(name).to_str().unwrap(), flags);
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn proofs_do_not_verify_under_another_key() {
+    let dir = scratch("other-key");
+    let (a, b) = (dir.join("a"), dir.join("b"));
+    let (a, b) = (a.to_str().unwrap(), b.to_str().unwrap());
+    round_trip(a, &[]);
+    round_trip(b, &[]);
+    let code = |vk: &str, proof: &str| {
+        run(&["verify", &format!("{}/verifying.key", vk), &format!("{}/proof", proof), "35"]).status.code()
+    };
+    assert_eq!(code(a, b), Some(1));
+
+    // A prepared-key cache copied from another key is not used.
+    fs::copy(format!("{}/verifying.key.prepared", b), format!("{}/verifying.key.prepared", a)).unwrap();
+    assert_eq!(code(a, b), Some(1));
+    assert_eq!(code(a, a), Some(0));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn bad_input_is_an_error() {
+    let dir = scratch("errors");
+    let keys = dir.join("keys");
+    let keys = keys.to_str().unwrap();
+    round_trip(keys, &[]);
+    let (pk, vk, proof) = (format!("{}/proving.key", keys), format!("{}/verifying.key", keys), format!("{}/proof", keys));
+
+    // A truncated proof, a key given where the proof belongs, the wrong curve.
+    let truncated = dir.join("truncated");
+    let bytes = fs::read(&proof).unwrap();
+    fs::write(&truncated, &bytes[..bytes.len() - 1]).unwrap();
+    assert_eq!(run(&["verify", &vk, truncated.to_str().unwrap(), "35"]).status.code(), Some(2));
+    assert_eq!(run(&["verify", &vk, &pk, "35"]).status.code(), Some(2));
+    assert_eq!(run(&["verify", &vk, &proof, "35", "--curve", "bn254"]).status.code(), Some(2));
+
+    // Usage errors.
+    assert_eq!(run(&["prove", &pk, "3"]).status.code(), Some(2));
+    assert_eq!(run(&["verify", &vk, &proof]).status.code(), Some(2));
+    assert_eq!(run(&["prove", &pk, "not-a-number", &proof]).status.code(), Some(2));
+    assert_eq!(run(&["setup", keys, "--curve"]).status.code(), Some(2));
+    fs::remove_dir_all(dir).unwrap();
+}
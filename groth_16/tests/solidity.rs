@@ -0,0 +1,135 @@
+// The generated Solidity verifier compiled with solc and executed on revm,
+// against the Rust verifier. Needs solc 0.8 on PATH, or its path in $SOLC:
+//
+//     cargo test --features solc-tests --test solidity
+
+#![cfg(feature = "solc-tests")]
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use ark_bn254::{Bn254, Fr};
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::test_rng;
+use groth_16::cubic::CubicDemoCircuit;
+use groth_16::groth16::{create_proof, generate_parameters, verify_proof};
+use groth_16::polynomial::{Equation, PolynomialCircuit};
+use groth_16::solidity::{calldata, verifier_contract};
+use revm::db::{CacheDB, EmptyDB};
+use revm::primitives::{Address, ExecutionResult, Output, TxKind};
+use revm::Evm;
+
+// Creation bytecode of the contract in `source`.
+fn compile(source: &str) -> Vec<u8> {
+    let solc = std::env::var("SOLC").unwrap_or_else(|_| "solc".to_string());
+    let mut child = Command::new(&solc)
+        .args(["--bin", "--optimize", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap_or_else(|e| panic!("cannot run {}: {}", solc, e));
+    child.stdin.take().unwrap().write_all(source.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "solc failed:\n{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let hex = stdout.lines().skip_while(|l| *l != "Binary:").nth(1).expect("no bytecode in the solc output");
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+}
+
+struct Chain {
+    db: CacheDB<EmptyDB>,
+    address: Address,
+}
+
+impl Chain {
+    fn deploy(source: &str) -> Self {
+        let mut db = CacheDB::new(EmptyDB::default());
+        let bytecode = compile(source);
+        let result = Evm::builder()
+            .with_db(&mut db)
+            .modify_tx_env(|tx| {
+                tx.transact_to = TxKind::Create;
+                tx.data = bytecode.into();
+            })
+            .build()
+            .transact_commit()
+            .unwrap();
+        match result {
+            ExecutionResult::Success { output: Output::Create(_, Some(address)), .. } => Chain { db, address },
+            other => panic!("deployment failed: {:?}", other),
+        }
+    }
+
+    // What verifyProof returns, or None if the call reverts.
+    fn call(&mut self, data: Vec<u8>) -> Option<bool> {
+        let address = self.address;
+        let result = Evm::builder()
+            .with_db(&mut self.db)
+            .modify_tx_env(|tx| {
+                tx.transact_to = TxKind::Call(address);
+                tx.data = data.into();
+            })
+            .build()
+            .transact()
+            .unwrap()
+            .result;
+        match result {
+            ExecutionResult::Success { output: Output::Call(bytes), .. } => {
+                assert_eq!(bytes.len(), 32);
+                Some(bytes[31] == 1)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn contract_agrees_with_the_rust_verifier() {
+    let mut rng = test_rng();
+    let pk = generate_parameters::<Bn254, _, _>(CubicDemoCircuit::<Fr> { x: None }, &mut rng).unwrap();
+    let mut chain = Chain::deploy(&verifier_contract(&pk.vk));
+
+    for x in [3u64, 4] {
+        let proof = create_proof(&pk, CubicDemoCircuit { x: Some(Fr::from(x)) }, &mut rng).unwrap();
+        for out in [35u64, 73, 0] {
+            let inputs = [Fr::from(out)];
+            let expected = verify_proof(&pk.vk, &inputs, &proof).unwrap();
+            assert_eq!(chain.call(calldata(&proof, &inputs)), Some(expected), "x = {}, out = {}", x, out);
+        }
+    }
+
+    let proof = create_proof(&pk, CubicDemoCircuit { x: Some(Fr::from(3u64)) }, &mut rng).unwrap();
+    let valid = calldata(&proof, &[Fr::from(35u64)]);
+    assert_eq!(chain.call(valid.clone()), Some(true));
+
+    // An input of r or more is refused rather than reduced.
+    let mut unreduced = valid.clone();
+    let r = Fr::MODULUS.to_bytes_be();
+    unreduced[4 + 8 * 32..].copy_from_slice(&r);
+    assert_eq!(chain.call(unreduced), Some(false));
+
+    // B's words swapped into (re, im) order: not a point the precompile accepts.
+    let mut swapped = valid.clone();
+    let (im, re) = (swapped[4 + 64..4 + 96].to_vec(), swapped[4 + 96..4 + 128].to_vec());
+    swapped[4 + 64..4 + 96].copy_from_slice(&re);
+    swapped[4 + 96..4 + 128].copy_from_slice(&im);
+    assert_eq!(chain.call(swapped), Some(false));
+}
+
+#[test]
+fn contract_without_public_inputs_compiles_and_verifies() {
+    let mut rng = test_rng();
+    let equation = Equation::parse("x^2 - 5x + 6 = 0").unwrap();
+    let pk = generate_parameters::<Bn254, _, _>(PolynomialCircuit::<Fr>::new(equation.clone(), None), &mut rng).unwrap();
+    assert_eq!(pk.vk.gamma_abc_g1.len(), 1);
+    let mut chain = Chain::deploy(&verifier_contract(&pk.vk));
+
+    for root in [2u64, 3] {
+        let proof = create_proof(&pk, PolynomialCircuit::new(equation.clone(), Some(vec![Fr::from(root)])), &mut rng).unwrap();
+        assert_eq!(chain.call(calldata(&proof, &[])), Some(true));
+        let mut bad = proof.clone();
+        bad.c = proof.a;
+        assert_eq!(chain.call(calldata(&bad, &[])), Some(false));
+    }
+}